@@ -3,6 +3,7 @@ local M = {}
 -- Imports
 local job = require("regex-railroad.job")
 local config = require("regex-railroad.config")
+local utils = require("regex-railroad.utils")
 
 -- Variables
 local jobid
@@ -10,16 +11,16 @@ local jobid
 
 --- Send RPC command to generate and display a railroad diagram of the regular expression
 ---
---- @param filename string name of current file
+--- @param source table filename, filetype and tree-sitter language of the buffer
 --- @param text string text containing regular expression
 --- @return table
-local function regex_railroad(filename, text)
+local function regex_railroad(source, text)
     local response = vim.api.nvim_call_function(
         "rpcrequest",
         {
             jobid,
             "regexrailroad",
-            { filename, text }
+            { source, text }
         }
     )
 
@@ -29,16 +30,95 @@ end
 
 --- Send RPC command to generate and display a text description of the regular expression
 ---
---- @param filename string name of current file
+--- @param source table filename, filetype and tree-sitter language of the buffer
 --- @param text string text containing regular expression
 --- @return table
-local function regex_text(filename, text)
+local function regex_text(source, text)
     local response = vim.api.nvim_call_function(
         "rpcrequest",
         {
             jobid,
             "regextext",
-            { filename, text }
+            { source, text }
+        }
+    )
+
+    return response
+end
+
+
+--- Send RPC command to generate and display the state graph of the regular expression
+---
+--- @param source table filename, filetype and tree-sitter language of the buffer
+--- @param text string text containing regular expression
+--- @param kind string automaton to draw, either "dfa" or "nfa"
+--- @return table
+local function regex_automaton(source, text, kind)
+    local response = vim.api.nvim_call_function(
+        "rpcrequest",
+        {
+            jobid,
+            "regexautomaton",
+            { source, text, kind }
+        }
+    )
+
+    return response
+end
+
+
+--- Send RPC command to compare the strings matched by two regular expressions
+---
+--- @param source table filename, filetype and tree-sitter language of the buffer
+--- @param left string text containing first regular expression
+--- @param right string text containing second regular expression
+--- @return table
+local function regex_compare(source, left, right)
+    local response = vim.api.nvim_call_function(
+        "rpcrequest",
+        {
+            jobid,
+            "regexcompare",
+            { source, left, right }
+        }
+    )
+
+    return response
+end
+
+
+--- Send RPC command to generate a railroad diagram of the changes between two regular expressions
+---
+--- @param source table filename, filetype and tree-sitter language of the buffer
+--- @param old string text containing original regular expression
+--- @param new string text containing changed regular expression
+--- @return table
+local function regex_diff(source, old, new)
+    local response = vim.api.nvim_call_function(
+        "rpcrequest",
+        {
+            jobid,
+            "regexdiff",
+            { source, old, new }
+        }
+    )
+
+    return response
+end
+
+
+--- Send RPC command to list the capture groups of the regular expression
+---
+--- @param source table filename, filetype and tree-sitter language of the buffer
+--- @param text string text containing regular expression
+--- @return table
+local function regex_groups(source, text)
+    local response = vim.api.nvim_call_function(
+        "rpcrequest",
+        {
+            jobid,
+            "regexgroups",
+            { source, text }
         }
     )
 
@@ -142,16 +222,11 @@ end
 --- Runs when :RegexRailroad command executed
 function M.run_diagram_command()
     -- Use treesitter to extract regex text
-    local line
-    local node = vim.treesitter.get_node()
-    if node then
-        line = vim.treesitter.get_node_text(node, 0)
-    else
-        line = ""
-    end
+    local node = utils.get_regex_node()
+    local line = utils.get_regex_text(node)
 
-    -- Use filename to extract current language
-    local filename = vim.api.nvim_buf_get_name(0)
+    -- Use filetype and filename to extract current language
+    local source = utils.get_source(0, node)
 
     -- Set highlight group from config
     vim.api.nvim_set_hl(
@@ -160,8 +235,8 @@ function M.run_diagram_command()
         config.opts.highlight
     )
 
-    jobid = job.attach(filename)
-    local ret = regex_railroad(filename, line)
+    jobid = job.attach(source.filename)
+    local ret = regex_railroad(source, line)
     if ret.error == nil then
         create_win(ret.text, ret.width, ret.height)
     else
@@ -175,16 +250,11 @@ end
 --- Runs when :RegexText command executed
 function M.run_text_command()
     -- Use treesitter to extract regex text
-    local line
-    local node = vim.treesitter.get_node()
-    if node then
-        line = vim.treesitter.get_node_text(node, 0)
-    else
-        line = ""
-    end
+    local node = utils.get_regex_node()
+    local line = utils.get_regex_text(node)
 
-    -- Use filename to extract current language
-    local filename = vim.api.nvim_buf_get_name(0)
+    -- Use filetype and filename to extract current language
+    local source = utils.get_source(0, node)
 
     -- Set highlight group from config
     vim.api.nvim_set_hl(
@@ -193,8 +263,8 @@ function M.run_text_command()
         config.opts.highlight
     )
 
-    jobid = job.attach(filename)
-    local ret = regex_text(filename, line)
+    jobid = job.attach(source.filename)
+    local ret = regex_text(source, line)
 
     if ret.error == nil then
         create_win(ret.text, ret.width, ret.height)
@@ -207,4 +277,114 @@ function M.run_text_command()
 end
 
 
+--- Runs when :RegexAutomaton command executed
+---
+--- @param kind string|nil automaton to draw, defaults to "dfa"
+function M.run_automaton_command(kind)
+    -- Use treesitter to extract regex text
+    local node = utils.get_regex_node()
+    local line = utils.get_regex_text(node)
+
+    -- Use filetype and filename to extract current language
+    local source = utils.get_source(0, node)
+
+    jobid = job.attach(source.filename)
+    local ret = regex_automaton(source, line, kind or "dfa")
+
+    if ret.error == nil then
+        create_win(ret.text, ret.width, ret.height)
+    else
+        vim.api.nvim_command(
+            string.format("echohl ErrorMsg | echo \"%s\" | echohl None", ret.error)
+        )
+    end
+end
+
+
+--- Runs when :RegexCompare command executed
+---
+--- If only one pattern is given, the regular expression under the cursor is compared
+--- against it
+---
+--- @param patterns table patterns passed to the command
+function M.run_compare_command(patterns)
+    local left, right, node
+    if #patterns >= 2 then
+        left, right = patterns[1], patterns[2]
+    else
+        node = utils.get_regex_node()
+        left = utils.get_regex_text(node)
+        right = patterns[1] or ""
+    end
+
+    -- Use filetype and filename to extract current language
+    local source = utils.get_source(0, node)
+
+    jobid = job.attach(source.filename)
+    local ret = regex_compare(source, left, right)
+
+    if ret.error == nil then
+        create_win(ret.text, ret.width, ret.height)
+    else
+        vim.api.nvim_command(
+            string.format("echohl ErrorMsg | echo \"%s\" | echohl None", ret.error)
+        )
+    end
+end
+
+
+--- Runs when :RegexDiff command executed
+---
+--- If only one pattern is given, it is treated as the original version of the regular
+--- expression under the cursor
+---
+--- @param patterns table patterns passed to the command
+function M.run_diff_command(patterns)
+    local old, new, node
+    if #patterns >= 2 then
+        old, new = patterns[1], patterns[2]
+    else
+        old = patterns[1] or ""
+        node = utils.get_regex_node()
+        new = utils.get_regex_text(node)
+    end
+
+    -- Use filetype and filename to extract current language
+    local source = utils.get_source(0, node)
+
+    jobid = job.attach(source.filename)
+    local ret = regex_diff(source, old, new)
+
+    if ret.error == nil then
+        create_win(ret.text, ret.width, ret.height)
+    else
+        vim.api.nvim_command(
+            string.format("echohl ErrorMsg | echo \"%s\" | echohl None", ret.error)
+        )
+    end
+end
+
+
+--- Runs when :RegexGroups command executed
+function M.run_groups_command()
+    -- Use treesitter to extract regex text
+    local node = utils.get_regex_node()
+    local line = utils.get_regex_text(node)
+
+    -- Use filetype and filename to extract current language
+    local source = utils.get_source(0, node)
+
+    jobid = job.attach(source.filename)
+    local ret = regex_groups(source, line)
+
+    if ret.error == nil then
+        create_win(ret.text, ret.width, ret.height)
+    else
+        vim.api.nvim_command(
+            string.format("echohl ErrorMsg | echo \"%s\" | echohl None", ret.error)
+        )
+    end
+end
+
+
 return M
@@ -24,4 +24,37 @@ function M.set_defaults(user)
 end
 
 
+--- Get the user defined languages from setup() and the languages file
+---
+--- Languages given as a table keyed by name take their key as the name
+---
+--- @return table list of language definitions
+function M.languages()
+    local languages = vim.deepcopy(M.opts.languages or {})
+    local file = M.opts.languages_file
+    if file ~= nil and file ~= "" then
+        local ok, decoded = pcall(function()
+            return vim.json.decode(table.concat(vim.fn.readfile(vim.fn.expand(file)), "\n"))
+        end)
+        if ok then
+            languages = vim.tbl_extend("force", languages, decoded)
+        else
+            vim.notify(
+                string.format("Could not read languages file %s: %s", file, decoded),
+                vim.log.levels.ERROR
+            )
+        end
+    end
+
+    local definitions = {}
+    for key, definition in pairs(languages) do
+        if type(key) == "string" and definition.name == nil then
+            definition.name = key
+        end
+        definitions[#definitions + 1] = definition
+    end
+    return definitions
+end
+
+
 return M
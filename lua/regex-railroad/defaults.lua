@@ -7,6 +7,20 @@ local M = {
         fg = "fg",
         bg = "bg"
     },
+    --- Highlight group used for matches in :RegexMatch
+    match_highlight = {
+        link = "Search"
+    },
+    --- Highlight group used for capture groups in :RegexMatch
+    group_highlight = {
+        link = "IncSearch"
+    },
+    --- Additional languages, keyed by name
+    languages = {},
+    --- JSON file of additional languages, in the same form as `languages`
+    languages_file = "",
+    --- Regular expression syntax of patterns in YAML, TOML and JSON files
+    config_flavor = "PCRE",
     --- Look for binary in development dir
     dev = false
 }
@@ -21,6 +21,11 @@ function M.attach(filename)
         end
     end
 
+    -- Reuse the job if it is still running
+    if M.jobid ~= nil and M.jobid > 0 and vim.fn.jobwait({ M.jobid }, 0)[1] == -1 then
+        return M.jobid
+    end
+
     local binlist
     if filename == nil or filename == "" then
         binlist = { binary }
@@ -40,9 +45,37 @@ function M.attach(filename)
         return false
     elseif M.jobid == -1 then
         return false
-    else
-        return M.jobid
     end
+
+    -- Send the options which change how patterns are read to the new job
+    local response = vim.api.nvim_call_function(
+        "rpcrequest",
+        {
+            M.jobid,
+            "regexconfig",
+            { { config_flavor = config.opts.config_flavor } }
+        }
+    )
+    if response.error ~= nil then
+        vim.notify(response.error, vim.log.levels.ERROR)
+    end
+
+    -- Send any user defined languages to the new job
+    local languages = config.languages()
+    if #languages > 0 then
+        local response = vim.api.nvim_call_function(
+            "rpcrequest",
+            {
+                M.jobid,
+                "regexlanguages",
+                { languages }
+            }
+        )
+        if response.error ~= nil then
+            vim.notify(response.error, vim.log.levels.ERROR)
+        end
+    end
+    return M.jobid
 end
 
 
@@ -0,0 +1,161 @@
+local M = {}
+
+-- Imports
+local job = require("regex-railroad.job")
+local config = require("regex-railroad.config")
+local utils = require("regex-railroad.utils")
+
+-- Variables
+local namespace = vim.api.nvim_create_namespace("regex_railroad_match")
+local regex_namespace = vim.api.nvim_create_namespace("regex_railroad_match_regex")
+local augroup = "regex_railroad_match"
+local session = nil
+
+
+--- Send RPC command to find every match of the regular expression in a list of lines
+---
+--- @param source table filename, filetype and tree-sitter language of the regular expression's buffer
+--- @param text string text containing regular expression
+--- @param lines table lines to match against
+--- @param first integer zero-indexed number of the first line
+--- @return table
+local function regex_match(source, text, lines, first)
+    local response = vim.api.nvim_call_function(
+        "rpcrequest",
+        {
+            job.attach(source.filename),
+            "regexmatch",
+            { source, text, lines, first }
+        }
+    )
+
+    return response
+end
+
+
+--- Get the text of the regular expression being tested
+---
+--- The position of the regular expression is tracked with an extmark so it follows edits
+---
+--- @return string, TSNode|nil
+local function get_regex_text()
+    local pos = vim.api.nvim_buf_get_extmark_by_id(session.regex_buf, regex_namespace, session.regex_mark, {})
+    if #pos == 0 then
+        return "", nil
+    end
+    local node = utils.get_regex_node({ bufnr = session.regex_buf, pos = pos })
+    return utils.get_regex_text(node, session.regex_buf, pos[1]), node
+end
+
+
+--- Match the regular expression against the target lines and place extmarks
+function M.update()
+    if session == nil then
+        return
+    end
+    if not vim.api.nvim_buf_is_valid(session.regex_buf) or not vim.api.nvim_buf_is_valid(session.target_buf) then
+        M.stop()
+        return
+    end
+
+    local lines = vim.api.nvim_buf_get_lines(session.target_buf, session.first, session.last, false)
+    local text, node = get_regex_text()
+    local ret = regex_match(utils.get_source(session.regex_buf, node), text, lines, session.first)
+
+    vim.api.nvim_buf_clear_namespace(session.target_buf, namespace, 0, -1)
+    if ret.error ~= nil then
+        vim.api.nvim_echo({ { ret.error, "ErrorMsg" } }, false, {})
+        return
+    end
+
+    for _, match in ipairs(ret.matches) do
+        vim.api.nvim_buf_set_extmark(session.target_buf, namespace, match.line, match.start, {
+            end_col = match["end"],
+            hl_group = "RegexMatch",
+            priority = 200
+        })
+        for _, group in ipairs(match.groups) do
+            vim.api.nvim_buf_set_extmark(session.target_buf, namespace, match.line, group.start, {
+                end_col = group["end"],
+                hl_group = "RegexGroup",
+                priority = 201
+            })
+        end
+    end
+end
+
+
+--- Start highlighting matches of the regular expression under the cursor
+---
+--- @param target_buf integer buffer to match against
+--- @param first integer zero-indexed first line to match
+--- @param last integer zero-indexed, end-exclusive last line to match, or -1 for the end
+function M.start(target_buf, first, last)
+    M.stop()
+
+    local regex_buf = vim.api.nvim_get_current_buf()
+    local node = utils.get_regex_node()
+    if node == nil then
+        vim.api.nvim_echo({ { "No regular expression under cursor", "ErrorMsg" } }, false, {})
+        return
+    end
+    local row, col = node:range()
+
+    -- Set highlight groups from config
+    vim.api.nvim_set_hl(0, "RegexMatch", config.opts.match_highlight)
+    vim.api.nvim_set_hl(0, "RegexGroup", config.opts.group_highlight)
+
+    session = {
+        regex_buf = regex_buf,
+        regex_mark = vim.api.nvim_buf_set_extmark(regex_buf, regex_namespace, row, col, {}),
+        target_buf = target_buf,
+        first = first,
+        last = last
+    }
+
+    -- Update matches as either the regular expression or the text changes
+    local group = vim.api.nvim_create_augroup(augroup, { clear = true })
+    for _, buf in ipairs({ regex_buf, target_buf }) do
+        vim.api.nvim_create_autocmd({ "TextChanged", "TextChangedI" }, {
+            group = group,
+            buffer = buf,
+            callback = M.update
+        })
+    end
+
+    M.update()
+end
+
+
+--- Open a scratch buffer to type test strings into
+function M.start_scratch()
+    local regex_win = vim.api.nvim_get_current_win()
+    local scratch = vim.api.nvim_create_buf(false, true)
+    vim.bo[scratch].bufhidden = "wipe"
+
+    M.start(scratch, 0, -1)
+    if session == nil then
+        return
+    end
+    vim.api.nvim_command("belowright split")
+    vim.api.nvim_win_set_buf(0, scratch)
+    vim.api.nvim_set_current_win(regex_win)
+end
+
+
+--- Stop highlighting matches
+function M.stop()
+    pcall(vim.api.nvim_del_augroup_by_name, augroup)
+    if session ~= nil then
+        if vim.api.nvim_buf_is_valid(session.target_buf) then
+            vim.api.nvim_buf_clear_namespace(session.target_buf, namespace, 0, -1)
+        end
+        if vim.api.nvim_buf_is_valid(session.regex_buf) then
+            vim.api.nvim_buf_clear_namespace(session.regex_buf, regex_namespace, 0, -1)
+        end
+    end
+    session = nil
+end
+
+
+return M
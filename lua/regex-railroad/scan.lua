@@ -0,0 +1,86 @@
+local M = {}
+
+-- Imports
+local job = require("regex-railroad.job")
+local utils = require("regex-railroad.utils")
+
+
+--- Send RPC command to find every regular expression in the text of a buffer
+---
+--- @param source table filename and filetype of the buffer
+--- @param text string full text of the buffer
+--- @return table
+local function regex_scan(source, text)
+    local response = vim.api.nvim_call_function(
+        "rpcrequest",
+        {
+            job.attach(source.filename),
+            "regexscan",
+            { source, text }
+        }
+    )
+
+    return response
+end
+
+
+--- Find every regular expression in a buffer, for use in a picker
+---
+--- Each item has the zero-indexed `line`, `column`, `end_line` and `end_column` of the
+--- regular expression, its source `text`, `pattern` and literal `kind` such as "string" or
+--- "regex literal", any parse `error` and a list of `lints` with a `severity` of "warning"
+--- or "hint" and a `message`
+---
+--- @param bufnr integer|nil buffer to scan, defaults to current
+--- @return table|nil, string|nil regular expressions found, or an error message
+function M.regexes(bufnr)
+    bufnr = bufnr or vim.api.nvim_get_current_buf()
+    local lines = vim.api.nvim_buf_get_lines(bufnr, 0, -1, false)
+    local ret = regex_scan(utils.get_source(bufnr), table.concat(lines, "\n"))
+    if ret.error ~= nil then
+        return nil, ret.error
+    end
+    return ret.regexes, nil
+end
+
+
+--- Fill the quickfix list with every regular expression in the current buffer, listing
+--- parse errors and lint findings as separate entries
+function M.quickfix()
+    local bufnr = vim.api.nvim_get_current_buf()
+    local regexes, err = M.regexes(bufnr)
+    if regexes == nil then
+        vim.api.nvim_echo({ { err, "ErrorMsg" } }, false, {})
+        return
+    end
+
+    local items = {}
+    for _, regex in ipairs(regexes) do
+        local function add(text, type)
+            table.insert(items, {
+                bufnr = bufnr,
+                lnum = regex.line + 1,
+                col = regex.column + 1,
+                end_lnum = regex.end_line + 1,
+                end_col = regex.end_column + 1,
+                text = text,
+                type = type
+            })
+        end
+
+        if regex.error ~= nil then
+            add(string.format("%s: %s", regex.text, regex.error), "E")
+        elseif #regex.lints == 0 then
+            add(regex.pattern, "I")
+        end
+        for _, lint in ipairs(regex.lints) do
+            add(string.format("%s: %s", regex.pattern, lint.message), lint.severity == "warning" and "W" or "I")
+        end
+    end
+
+    vim.fn.setqflist({}, " ", { title = "Regular expressions", items = items })
+    vim.cmd("copen")
+end
+
+
+return M
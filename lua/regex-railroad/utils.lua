@@ -88,4 +88,188 @@ function M.lines_from_file(file)
 end
 
 
+-- Treesitter node types of function calls and the method chains built on them
+local call_types = {
+    call = true,
+    call_expression = true,
+    function_call_expression = true,
+    invocation_expression = true,
+    object_creation_expression = true,
+    new_expression = true,
+    macro_invocation = true,
+    method_invocation = true,
+    command = true,
+    test_command = true,
+    invocation = true
+}
+local chain_types = {
+    attribute = true,
+    field_expression = true,
+    member_access_expression = true,
+    member_expression = true,
+    navigation_expression = true,
+    selector_expression = true
+}
+-- Treesitter node types of expressions whose operator decides how their pattern is read,
+-- such as SQL's `name LIKE 'a%'`, by filetype
+local operator_types = {
+    sql = { binary_expression = true }
+}
+-- Treesitter node types of strings in configuration files, whose quotes are needed to
+-- read their escapes, by filetype
+local string_types = {
+    yaml = { double_quote_scalar = true, single_quote_scalar = true, block_scalar = true },
+    toml = { string = true },
+    json = { string = true },
+    jsonc = { string = true }
+}
+-- Treesitter node types of regex literals, whose delimiters and modifiers are needed
+local literal_types = {
+    regex = true,
+    regex_literal = true,
+    quoted_regexp = true,
+    match_regexp = true,
+    substitution_regexp = true,
+    substitute_statement = true,
+    global_statement = true,
+    sigil = true,
+    quasiquote = true
+}
+
+
+--- Get the node containing the regular expression under the cursor
+---
+--- If the cursor is within the arguments of a call, the whole call is used so that
+--- the pattern and any flags can be read from it
+---
+--- @param opts table|nil options passed to vim.treesitter.get_node
+--- @return TSNode|nil
+function M.get_regex_node(opts)
+    local ok, node = pcall(vim.treesitter.get_node, opts)
+    if not ok or node == nil then
+        return nil
+    end
+
+    -- Use the whole regex literal or string when the cursor is on its pattern, modifiers
+    -- or an escape
+    local strings = string_types[vim.bo[opts and opts.bufnr or 0].filetype] or {}
+    local literal = node
+    for _ = 1, 2 do
+        literal = literal:parent()
+        if literal == nil then
+            break
+        elseif literal_types[literal:type()] or strings[literal:type()] then
+            node = literal
+            break
+        end
+    end
+
+    -- Use the whole comparison when the cursor is on its pattern or operator
+    local operators = operator_types[vim.bo[opts and opts.bufnr or 0].filetype]
+    if operators and node:parent() ~= nil and operators[node:parent():type()] then
+        return node:parent()
+    end
+
+    -- Only look a few levels up so that literals outside of calls are not swallowed
+    local call = node
+    for _ = 1, 4 do
+        if call == nil or call_types[call:type()] then
+            break
+        end
+        call = call:parent()
+    end
+    if call == nil or not call_types[call:type()] then
+        return node
+    end
+
+    -- Include chained method calls such as RegexBuilder::new(...).case_insensitive(true)
+    while true do
+        local parent = call:parent()
+        if parent ~= nil and call_types[parent:type()] then
+            call = parent
+        elseif parent ~= nil and chain_types[parent:type()] and parent:parent() ~= nil
+            and call_types[parent:parent():type()] then
+            call = parent:parent()
+        else
+            break
+        end
+    end
+    return call
+end
+
+
+-- Filetypes with one pattern per line, such as .gitignore files, whose patterns are not
+-- tree-sitter nodes
+local line_filetypes = {
+    gitignore = true,
+    codeowners = true,
+    editorconfig = true
+}
+
+
+--- Get the text of the regular expression under the cursor
+---
+--- @param node TSNode|nil node containing the regular expression
+--- @param bufnr integer|nil buffer containing the regular expression, defaults to current
+--- @param row integer|nil zero-indexed line of the regular expression, defaults to the cursor's
+--- @return string
+function M.get_regex_text(node, bufnr, row)
+    bufnr = bufnr or vim.api.nvim_get_current_buf()
+    if line_filetypes[vim.bo[bufnr].filetype] then
+        row = row or vim.api.nvim_win_get_cursor(0)[1] - 1
+        return vim.api.nvim_buf_get_lines(bufnr, row, row + 1, false)[1] or ""
+    elseif node then
+        return vim.treesitter.get_node_text(node, bufnr)
+    end
+    return ""
+end
+
+
+--- Convert a position within the text of a regular expression, such as the `start` or
+--- `end` of a capture group, into a buffer position
+---
+--- Positions are returned by the RPC as a zero-indexed `line` and byte `column` within
+--- the text, so escapes decoded from a string literal are accounted for.
+---
+--- @param node TSNode|nil node containing the regular expression
+--- @param position table zero-indexed line and byte column within the text
+--- @param bufnr integer|nil buffer containing the regular expression, defaults to current
+--- @param row integer|nil zero-indexed line of the regular expression, defaults to the cursor's
+--- @return integer, integer zero-indexed row and byte column in the buffer
+function M.get_buffer_position(node, position, bufnr, row)
+    bufnr = bufnr or vim.api.nvim_get_current_buf()
+    if line_filetypes[vim.bo[bufnr].filetype] or node == nil then
+        row = row or vim.api.nvim_win_get_cursor(0)[1] - 1
+        return row + position.line, position.column
+    end
+    local start_row, start_col = node:range()
+    if position.line == 0 then
+        return start_row, start_col + position.column
+    end
+    return start_row + position.line, position.column
+end
+
+
+--- Describe the buffer for the RPC, so the language can be detected from its filetype or
+--- from the tree-sitter language of the node when it is injected into another language
+---
+--- @param bufnr integer|nil buffer containing the regular expression, defaults to current
+--- @param node TSNode|nil node containing the regular expression
+--- @return table
+function M.get_source(bufnr, node)
+    bufnr = bufnr or vim.api.nvim_get_current_buf()
+    local source = {
+        filename = vim.api.nvim_buf_get_name(bufnr),
+        filetype = vim.bo[bufnr].filetype
+    }
+    if node then
+        local ok, parser = pcall(vim.treesitter.get_parser, bufnr)
+        if ok and parser then
+            source.lang = parser:language_for_range({ node:range() }):lang()
+        end
+    end
+    return source
+end
+
+
 return M
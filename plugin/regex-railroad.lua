@@ -19,6 +19,79 @@ vim.api.nvim_create_user_command(
     {}
 )
 
+vim.api.nvim_create_user_command(
+    "RegexAutomaton",
+    function(opts)
+        require("regex-railroad.command").run_automaton_command(opts.fargs[1])
+    end,
+    {
+        nargs = "?",
+        complete = function()
+            return { "dfa", "nfa" }
+        end
+    }
+)
+
+vim.api.nvim_create_user_command(
+    "RegexCompare",
+    function(opts)
+        require("regex-railroad.command").run_compare_command(opts.fargs)
+    end,
+    { nargs = "+" }
+)
+
+vim.api.nvim_create_user_command(
+    "RegexDiff",
+    function(opts)
+        require("regex-railroad.command").run_diff_command(opts.fargs)
+    end,
+    { nargs = "+" }
+)
+
+vim.api.nvim_create_user_command(
+    "RegexGroups",
+    function()
+        require("regex-railroad.command").run_groups_command()
+    end,
+    {}
+)
+
+vim.api.nvim_create_user_command(
+    "RegexMatch",
+    function(opts)
+        require("regex-railroad.matches").start(
+            vim.api.nvim_get_current_buf(),
+            opts.line1 - 1,
+            opts.line2
+        )
+    end,
+    { range = "%" }
+)
+
+vim.api.nvim_create_user_command(
+    "RegexMatchScratch",
+    function()
+        require("regex-railroad.matches").start_scratch()
+    end,
+    {}
+)
+
+vim.api.nvim_create_user_command(
+    "RegexMatchStop",
+    function()
+        require("regex-railroad.matches").stop()
+    end,
+    {}
+)
+
+vim.api.nvim_create_user_command(
+    "RegexScan",
+    function()
+        require("regex-railroad.scan").quickfix()
+    end,
+    {}
+)
+
 vim.api.nvim_create_user_command(
     "UpdateRegexRailroad",
     function()
@@ -0,0 +1,56 @@
+use tracing::warn;
+
+use crate::{
+    automaton::{
+        dfa::{Dfa, DFA_STATE_LIMIT},
+        nfa::Nfa,
+        renderer::GraphRenderer,
+    },
+    error::Error,
+    flags::Flags,
+    parser::RegEx,
+};
+
+pub mod charset;
+pub mod compare;
+pub mod dfa;
+pub mod nfa;
+pub mod renderer;
+
+/// Which automaton to draw for a regular expression
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum AutomatonKind {
+    Nfa,
+    Dfa,
+}
+
+impl AutomatonKind {
+    pub fn from_name(name: &str) -> AutomatonKind {
+        match name.to_lowercase().as_str() {
+            "nfa" => AutomatonKind::Nfa,
+            _ => AutomatonKind::Dfa,
+        }
+    }
+}
+
+/// Draw the state graph of a regular expression, falling back to the NFA with a
+/// warning if the minimized DFA cannot be built within the state limit
+pub fn render_state_graph(tree: &RegEx, flags: &Flags, kind: AutomatonKind) -> Result<Vec<String>, Error> {
+    let nfa = Nfa::from_regex_with_flags(tree, flags)?;
+    if kind == AutomatonKind::Nfa {
+        return Ok(GraphRenderer::render(&nfa));
+    }
+    match Dfa::from_nfa(&nfa) {
+        Ok(dfa) => Ok(GraphRenderer::render(&dfa.minimize())),
+        Err(Error::StateLimit(_)) => {
+            warn!("DFA exceeded {} states", DFA_STATE_LIMIT);
+            let mut text = vec![
+                format!("WARNING: DFA exceeds {} states, showing NFA", DFA_STATE_LIMIT),
+                String::new(),
+            ];
+            text.extend(GraphRenderer::render(&nfa));
+            Ok(text)
+        }
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,295 @@
+use std::fmt::Display;
+
+use crate::{
+    error::Error,
+    flags::Flags,
+    parser::{CharacterType, MetaCharacter},
+};
+
+/// Largest set whose characters are case folded one at a time
+const CASE_FOLD_LIMIT: u64 = 0x10000;
+
+/// Largest valid Unicode scalar value
+pub const MAX_CHAR: u32 = char::MAX as u32;
+
+/// A set of characters stored as sorted, non-overlapping inclusive ranges
+#[derive(Clone, Eq, Hash, Ord, PartialEq, PartialOrd, Debug, Default)]
+pub struct CharSet {
+    ranges: Vec<(u32, u32)>,
+}
+
+impl CharSet {
+    /// Create a set from possibly overlapping ranges
+    pub fn new(mut ranges: Vec<(u32, u32)>) -> CharSet {
+        ranges.sort();
+        let mut merged: Vec<(u32, u32)> = Vec::new();
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1.saturating_add(1) => {
+                    last.1 = std::cmp::max(last.1, end);
+                }
+                _ => merged.push((start, end)),
+            }
+        }
+        CharSet { ranges: merged }
+    }
+
+    /// Set containing a single character
+    pub fn single(c: char) -> CharSet {
+        CharSet { ranges: vec![(c as u32, c as u32)] }
+    }
+
+    /// Set containing every character
+    pub fn full() -> CharSet {
+        CharSet { ranges: vec![(0, MAX_CHAR)] }
+    }
+
+    pub fn ranges(&self) -> &[(u32, u32)] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        self.contains_code(c as u32)
+    }
+
+    /// Check for a code point, which may not be a valid `char`
+    pub fn contains_code(&self, c: u32) -> bool {
+        self.ranges.iter().any(|(start, end)| *start <= c && c <= *end)
+    }
+
+    /// Every character not in this set
+    pub fn complement(&self) -> CharSet {
+        let mut ranges = Vec::new();
+        let mut next = 0;
+        for (start, end) in self.ranges.iter() {
+            if *start > next {
+                ranges.push((next, start - 1));
+            }
+            next = end + 1;
+        }
+        if next <= MAX_CHAR {
+            ranges.push((next, MAX_CHAR));
+        }
+        CharSet { ranges }
+    }
+
+    pub fn union(&self, other: &CharSet) -> CharSet {
+        CharSet::new(self.ranges.iter().chain(other.ranges.iter()).cloned().collect())
+    }
+
+    /// Number of characters in the set
+    fn size(&self) -> u64 {
+        self.ranges.iter().map(|(start, end)| (end - start) as u64 + 1).sum()
+    }
+
+    /// Convert a parsed character class into a set
+    pub fn from_character(character: &CharacterType) -> Result<CharSet, Error> {
+        match character {
+            CharacterType::Any(v) => Self::from_characters(v),
+            CharacterType::Not(v) => Ok(Self::from_characters(v)?.complement()),
+            CharacterType::Between(a, b) => match (a.as_ref(), b.as_ref()) {
+                (CharacterType::Terminal(a), CharacterType::Terminal(b)) => {
+                    if a > b {
+                        return Err(Error::CharacterRange(*a, *b));
+                    }
+                    Ok(CharSet::new(vec![(*a as u32, *b as u32)]))
+                }
+                _ => Err(Error::InvalidParsing),
+            },
+            CharacterType::Terminal(c) => Ok(CharSet::single(*c)),
+            CharacterType::Meta(meta) => Self::from_meta(meta),
+        }
+    }
+
+    /// Convert a parsed character class into a set, applying the case insensitive and
+    /// dot matches newline flags
+    pub fn from_character_with_flags(character: &CharacterType, flags: &Flags) -> Result<CharSet, Error> {
+        let set = match character {
+            CharacterType::Meta(MetaCharacter::Any) if flags.dot_all => CharSet::full(),
+            other => Self::from_character(other)?,
+        };
+        if flags.case_insensitive {
+            Ok(set.case_folded())
+        } else {
+            Ok(set)
+        }
+    }
+
+    /// Add the upper and lower case forms of every character in the set
+    pub fn case_folded(&self) -> CharSet {
+        // Large sets are usually negated classes, which are folded through their complement
+        if self.size() > CASE_FOLD_LIMIT {
+            return self.complement().case_folded().complement();
+        }
+        let mut ranges = self.ranges.clone();
+        for c in self.ranges.iter().flat_map(|(start, end)| *start..=*end).filter_map(char::from_u32) {
+            for folded in c.to_lowercase().chain(c.to_uppercase()) {
+                ranges.push((folded as u32, folded as u32));
+            }
+        }
+        CharSet::new(ranges)
+    }
+
+    fn from_characters(characters: &[Box<CharacterType>]) -> Result<CharSet, Error> {
+        let mut set = CharSet::default();
+        for c in characters.iter() {
+            set = set.union(&Self::from_character(c)?);
+        }
+        Ok(set)
+    }
+
+    fn from_meta(meta: &MetaCharacter) -> Result<CharSet, Error> {
+        let (set, matches) = match meta {
+            MetaCharacter::Word(m) => (
+                CharSet::new(vec![
+                    ('0' as u32, '9' as u32),
+                    ('A' as u32, 'Z' as u32),
+                    ('_' as u32, '_' as u32),
+                    ('a' as u32, 'z' as u32),
+                ]),
+                *m,
+            ),
+            MetaCharacter::Digit(m) => (CharSet::new(vec![('0' as u32, '9' as u32)]), *m),
+            MetaCharacter::Whitespace(m) => (
+                CharSet::new(vec![(0x09, 0x0d), (' ' as u32, ' ' as u32)]),
+                *m,
+            ),
+            MetaCharacter::Any => (CharSet::single('\n'), false),
+            MetaCharacter::Property(name, m) => (Self::from_property(name)?, *m),
+        };
+        if matches {
+            Ok(set)
+        } else {
+            Ok(set.complement())
+        }
+    }
+
+    /// Every character for which the predicate holds
+    fn from_predicate(predicate: fn(char) -> bool) -> CharSet {
+        let mut ranges = Vec::new();
+        let mut start = None;
+        for code in 0..=MAX_CHAR + 1 {
+            let inside = char::from_u32(code).is_some_and(predicate);
+            match (inside, start) {
+                (true, None) => start = Some(code),
+                (false, Some(first)) => {
+                    ranges.push((first, code - 1));
+                    start = None;
+                }
+                _ => (),
+            }
+        }
+        CharSet::new(ranges)
+    }
+
+    /// Characters with a POSIX, Unicode or Java character property
+    fn from_property(name: &str) -> Result<CharSet, Error> {
+        let ascii = |ranges: &[(char, char)]| {
+            CharSet::new(ranges.iter().map(|(a, b)| (*a as u32, *b as u32)).collect())
+        };
+        // POSIX classes only cover ASCII, unlike the Unicode properties of the same name
+        let set = match name {
+            "Lower" => ascii(&[('a', 'z')]),
+            "Upper" => ascii(&[('A', 'Z')]),
+            "ASCII" => ascii(&[('\0', '\x7f')]),
+            "Alpha" => ascii(&[('A', 'Z'), ('a', 'z')]),
+            "Digit" => ascii(&[('0', '9')]),
+            "Alnum" => ascii(&[('0', '9'), ('A', 'Z'), ('a', 'z')]),
+            "Punct" => ascii(&[('!', '/'), (':', '@'), ('[', '`'), ('{', '~')]),
+            "Graph" => ascii(&[('!', '~')]),
+            "Print" => ascii(&[(' ', '~')]),
+            "Blank" => ascii(&[(' ', ' '), ('\t', '\t')]),
+            "Cntrl" => ascii(&[('\0', '\x1f'), ('\x7f', '\x7f')]),
+            "XDigit" => ascii(&[('0', '9'), ('A', 'F'), ('a', 'f')]),
+            "Space" => ascii(&[('\t', '\r'), (' ', ' ')]),
+            other => Self::from_predicate(match other.strip_prefix("Is").unwrap_or(other) {
+                "L" | "Letter" | "Alphabetic" | "javaLetter" | "javaAlphabetic" => char::is_alphabetic,
+                "Ll" | "Lowercase" | "Lowercase_Letter" | "javaLowerCase" => char::is_lowercase,
+                "Lu" | "Uppercase" | "Uppercase_Letter" | "javaUpperCase" => char::is_uppercase,
+                "N" | "Nd" | "Number" | "Digit" | "javaDigit" => char::is_numeric,
+                "White_Space" | "WhiteSpace" | "javaWhitespace" => char::is_whitespace,
+                "Cc" | "Control" | "javaISOControl" => char::is_control,
+                "javaLetterOrDigit" => char::is_alphanumeric,
+                _ => return Err(Error::UnknownProperty(name.to_string())),
+            }),
+        };
+        Ok(set)
+    }
+
+    /// A printable character from the set, preferring letters and digits
+    pub fn example(&self) -> Option<char> {
+        for preferred in ['a', 'b', 'x', '0', 'A', ' ', '_', '-'] {
+            if self.contains(preferred) {
+                return Some(preferred);
+            }
+        }
+        self.ranges
+            .iter()
+            .flat_map(|(start, end)| {
+                [std::cmp::max(*start, 0x21), *start]
+                    .into_iter()
+                    .filter(move |c| c <= end)
+            })
+            .find_map(char::from_u32)
+    }
+}
+
+/// Escape a character for display in a diagram
+fn escape(c: u32) -> String {
+    match char::from_u32(c) {
+        Some('\n') => String::from("\\n"),
+        Some('\t') => String::from("\\t"),
+        Some('\r') => String::from("\\r"),
+        Some(c) if c.is_control() => format!("\\x{:02x}", c as u32),
+        Some(c) => c.to_string(),
+        None => format!("\\u{{{:x}}}", c),
+    }
+}
+
+fn format_ranges(ranges: &[(u32, u32)]) -> String {
+    ranges
+        .iter()
+        .map(|(start, end)| match end - start {
+            0 => escape(*start),
+            1 => format!("{}{}", escape(*start), escape(*end)),
+            _ => format!("{}-{}", escape(*start), escape(*end)),
+        })
+        .collect::<String>()
+}
+
+impl Display for CharSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let complement = self.complement();
+        if complement.is_empty() {
+            write!(f, "Any")
+        } else if complement.size() < self.size() {
+            write!(f, "[^{}]", format_ranges(&complement.ranges))
+        } else if self.size() == 1 {
+            write!(f, "{}", escape(self.ranges[0].0))
+        } else {
+            write!(f, "[{}]", format_ranges(&self.ranges))
+        }
+    }
+}
+
+/// Split the character space into the coarsest intervals that every set either fully
+/// contains or fully excludes
+pub fn partition<'a, I>(sets: I) -> Vec<(u32, u32)>
+where
+    I: IntoIterator<Item = &'a CharSet>,
+{
+    let mut boundaries = vec![0, MAX_CHAR + 1];
+    for set in sets {
+        for (start, end) in set.ranges.iter() {
+            boundaries.push(*start);
+            boundaries.push(end + 1);
+        }
+    }
+    boundaries.sort();
+    boundaries.dedup();
+    boundaries.windows(2).map(|w| (w[0], w[1] - 1)).collect()
+}
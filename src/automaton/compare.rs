@@ -0,0 +1,182 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+};
+use tracing::info;
+
+use crate::{
+    automaton::{
+        charset::{partition, CharSet},
+        dfa::Dfa,
+        nfa::Nfa,
+    },
+    error::Error,
+    flags::Flags,
+    parser::RegEx,
+};
+
+/// How the languages of two regular expressions relate
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Relation {
+    Equal,
+    Subset,
+    Superset,
+    Incomparable,
+}
+
+impl Display for Relation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Relation::Equal => write!(f, "equal"),
+            Relation::Subset => write!(f, "subset"),
+            Relation::Superset => write!(f, "superset"),
+            Relation::Incomparable => write!(f, "incomparable"),
+        }
+    }
+}
+
+/// Result of comparing two regular expressions, with the shortest string matched by
+/// only one of them where such a string exists
+#[derive(Debug)]
+pub struct Comparison {
+    pub relation: Relation,
+    pub only_left: Option<String>,
+    pub only_right: Option<String>,
+}
+
+/// A state of the product automaton, where `None` is the dead state
+type Pair = (Option<usize>, Option<usize>);
+
+impl Comparison {
+    /// Compare the full-match languages of two regular expressions
+    pub fn new(left: (&RegEx, &Flags), right: (&RegEx, &Flags)) -> Result<Comparison, Error> {
+        let left = Nfa::from_regex_with_flags(left.0, left.1)?;
+        let right = Nfa::from_regex_with_flags(right.0, right.1)?;
+
+        // Both automata must share character classes to be walked in lockstep
+        let classes = partition(left.charsets().chain(right.charsets()));
+        let examples: Vec<Option<char>> = classes
+            .iter()
+            .map(|range| CharSet::new(vec![*range]).example())
+            .collect();
+        let left = Dfa::with_classes(&left, classes.clone())?.minimize();
+        let right = Dfa::with_classes(&right, classes)?.minimize();
+
+        let accepts = |dfa: &Dfa, state: Option<usize>| state.is_some_and(|s| dfa.states[s].accepting);
+        let step = |dfa: &Dfa, state: Option<usize>, class: usize| {
+            state.and_then(|s| dfa.states[s].transitions[class])
+        };
+
+        // Breadth first search of the product automaton finds the shortest strings
+        let start: Pair = (Some(left.start), Some(right.start));
+        let mut parent: HashMap<Pair, Option<(Pair, char)>> = HashMap::from([(start, None)]);
+        let mut queue = VecDeque::from([start]);
+        let mut only_left = None;
+        let mut only_right = None;
+        while let Some(pair) = queue.pop_front() {
+            let (l, r) = (accepts(&left, pair.0), accepts(&right, pair.1));
+            if l && !r && only_left.is_none() {
+                only_left = Some(Self::trace(&parent, pair));
+            }
+            if r && !l && only_right.is_none() {
+                only_right = Some(Self::trace(&parent, pair));
+            }
+            if only_left.is_some() && only_right.is_some() {
+                break;
+            }
+            for (class, example) in examples.iter().enumerate() {
+                let Some(c) = example else { continue };
+                let next = (step(&left, pair.0, class), step(&right, pair.1, class));
+                if next == (None, None) || parent.contains_key(&next) {
+                    continue;
+                }
+                parent.insert(next, Some((pair, *c)));
+                queue.push_back(next);
+            }
+        }
+
+        let relation = match (&only_left, &only_right) {
+            (None, None) => Relation::Equal,
+            (None, Some(_)) => Relation::Subset,
+            (Some(_), None) => Relation::Superset,
+            (Some(_), Some(_)) => Relation::Incomparable,
+        };
+        info!("Regular expressions are {}", relation);
+
+        Ok(Comparison {
+            relation,
+            only_left,
+            only_right,
+        })
+    }
+
+    /// Rebuild the string leading to a product state
+    fn trace(parent: &HashMap<Pair, Option<(Pair, char)>>, mut pair: Pair) -> String {
+        let mut string = Vec::new();
+        while let Some(Some((previous, c))) = parent.get(&pair) {
+            string.push(*c);
+            pair = *previous;
+        }
+        string.iter().rev().collect()
+    }
+
+    /// Describe the comparison for display
+    pub fn render(&self, left: &str, right: &str) -> Vec<String> {
+        let mut text = vec![
+            format!("A: {}", left),
+            format!("B: {}", right),
+            String::new(),
+        ];
+        text.push(match self.relation {
+            Relation::Equal => String::from("EQUAL: A and B match the same strings"),
+            Relation::Subset => String::from("SUBSET: every string matched by A is matched by B"),
+            Relation::Superset => String::from("SUPERSET: every string matched by B is matched by A"),
+            Relation::Incomparable => String::from("INCOMPARABLE: A and B each match strings the other does not"),
+        });
+        if let Some(s) = &self.only_left {
+            text.push(format!("    Only A matches \"{}\"", s.escape_debug()));
+        }
+        if let Some(s) = &self.only_right {
+            text.push(format!("    Only B matches \"{}\"", s.escape_debug()));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        automaton::compare::{Comparison, Relation},
+        extract::Language,
+        flags::Flags,
+        parser::{RegEx, RegExParser},
+    };
+
+    fn parse(regex: &str) -> RegEx {
+        RegExParser::new(Language::Rust, &regex.to_string()).parse().unwrap()
+    }
+
+    fn compare(left: &str, right: &str) -> Comparison {
+        let flags = Flags::default();
+        Comparison::new((&parse(left), &flags), (&parse(right), &flags)).unwrap()
+    }
+
+    #[test]
+    fn test_compare_relation() {
+        assert_eq!(compare("a+", "aa*").relation, Relation::Equal);
+        assert_eq!(compare("[0-9]", "\\d").relation, Relation::Equal);
+        assert_eq!(compare("ab", "a[a-z]").relation, Relation::Subset);
+        assert_eq!(compare("a{1,3}", "a{2}").relation, Relation::Superset);
+        assert_eq!(compare("a|b", "b|c").relation, Relation::Incomparable);
+    }
+
+    #[test]
+    fn test_compare_counterexample() {
+        let comparison = compare("a{1,3}", "a{2}");
+        assert_eq!(comparison.only_left, Some(String::from("a")));
+        assert_eq!(comparison.only_right, None);
+
+        let comparison = compare("x*", "x+");
+        assert_eq!(comparison.only_left, Some(String::new()));
+    }
+}
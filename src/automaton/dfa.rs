@@ -0,0 +1,291 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use tracing::info;
+
+use crate::{
+    automaton::{
+        charset::{partition, CharSet},
+        nfa::{Assertion, Label, Nfa},
+    },
+    error::Error,
+};
+
+/// Maximum number of DFA states before subset construction is abandoned
+pub const DFA_STATE_LIMIT: usize = 500;
+
+#[derive(Clone, Debug)]
+pub struct DfaState {
+    /// Target state for each character class, or `None` for the dead state
+    pub transitions: Vec<Option<usize>>,
+    pub accepting: bool,
+}
+
+/// A deterministic automaton over disjoint character classes, accepting strings which
+/// the regular expression matches in full
+#[derive(Debug)]
+pub struct Dfa {
+    pub classes: Vec<(u32, u32)>,
+    pub states: Vec<DfaState>,
+    pub start: usize,
+}
+
+/// Set of live NFA states and whether the accepting state was reached
+type Closure = (BTreeSet<usize>, bool);
+
+impl Dfa {
+    /// Build a DFA using the character classes of the NFA
+    pub fn from_nfa(nfa: &Nfa) -> Result<Dfa, Error> {
+        let classes = partition(nfa.charsets());
+        Self::with_classes(nfa, classes)
+    }
+
+    /// Build a DFA using the given character classes, which must not split any
+    /// character set used by the NFA
+    pub fn with_classes(nfa: &Nfa, classes: Vec<(u32, u32)>) -> Result<Dfa, Error> {
+        let mut states: Vec<DfaState> = Vec::new();
+        let mut ids: HashMap<Closure, usize> = HashMap::new();
+        let mut queue: VecDeque<Closure> = VecDeque::new();
+
+        let start = Self::closure(nfa, &BTreeSet::from([nfa.start]), true);
+        ids.insert(start.clone(), 0);
+        states.push(DfaState {
+            transitions: vec![None; classes.len()],
+            accepting: start.1,
+        });
+        queue.push_back(start);
+
+        while let Some(current) = queue.pop_front() {
+            let id = ids[&current];
+            for (class, (lower, _)) in classes.iter().enumerate() {
+                let mut seeds = BTreeSet::new();
+                for state in current.0.iter() {
+                    for (label, to) in nfa.states[*state].transitions.iter() {
+                        if let Label::Set(set) = label {
+                            if set.contains_code(*lower) {
+                                seeds.insert(*to);
+                            }
+                        }
+                    }
+                }
+                if seeds.is_empty() {
+                    continue;
+                }
+                let next = Self::closure(nfa, &seeds, false);
+                let next_id = match ids.get(&next) {
+                    Some(next_id) => *next_id,
+                    None => {
+                        if states.len() >= DFA_STATE_LIMIT {
+                            return Err(Error::StateLimit(DFA_STATE_LIMIT));
+                        }
+                        states.push(DfaState {
+                            transitions: vec![None; classes.len()],
+                            accepting: next.1,
+                        });
+                        ids.insert(next.clone(), states.len() - 1);
+                        queue.push_back(next);
+                        states.len() - 1
+                    }
+                };
+                states[id].transitions[class] = Some(next_id);
+            }
+        }
+        info!("Built DFA with {} states", states.len());
+
+        Ok(Dfa {
+            classes,
+            states,
+            start: 0,
+        })
+    }
+
+    /// Follow epsilon and assertion transitions from the seed states
+    ///
+    /// States reached through an end of line assertion can only accept, so are not
+    /// included in the live set
+    fn closure(nfa: &Nfa, seeds: &BTreeSet<usize>, at_start: bool) -> Closure {
+        let mut live = BTreeSet::new();
+        let mut accepting = false;
+        let mut visited = BTreeSet::new();
+        let mut stack: Vec<(usize, bool)> = seeds.iter().map(|s| (*s, false)).collect();
+        while let Some((state, after_end)) = stack.pop() {
+            if !visited.insert((state, after_end)) {
+                continue;
+            }
+            if state == nfa.accept {
+                accepting = true;
+            }
+            if !after_end {
+                live.insert(state);
+            }
+            for (label, to) in nfa.states[state].transitions.iter() {
+                match label {
+                    Label::Epsilon => stack.push((*to, after_end)),
+                    Label::Assert(Assertion::Start) if at_start => stack.push((*to, after_end)),
+                    Label::Assert(Assertion::End) => stack.push((*to, true)),
+                    _ => (),
+                }
+            }
+        }
+        (live, accepting)
+    }
+
+    /// Index of the character class containing `c`
+    pub fn class_of(&self, c: char) -> Option<usize> {
+        let c = c as u32;
+        self.classes
+            .binary_search_by(|(lower, upper)| {
+                if *upper < c {
+                    std::cmp::Ordering::Less
+                } else if c < *lower {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .ok()
+    }
+
+    /// Check if the whole of `text` is accepted
+    pub fn matches(&self, text: &str) -> bool {
+        let mut state = self.start;
+        for c in text.chars() {
+            match self.class_of(c).and_then(|class| self.states[state].transitions[class]) {
+                Some(next) => state = next,
+                None => return false,
+            }
+        }
+        self.states[state].accepting
+    }
+
+    /// Outgoing transitions of a state, merging classes with the same target
+    pub fn edges(&self, state: usize) -> Vec<(CharSet, usize)> {
+        let mut edges: Vec<(CharSet, usize)> = Vec::new();
+        for (class, target) in self.states[state].transitions.iter().enumerate() {
+            if let Some(target) = target {
+                let range = CharSet::new(vec![self.classes[class]]);
+                match edges.iter_mut().find(|(_, t)| t == target) {
+                    Some(edge) => edge.0 = edge.0.union(&range),
+                    None => edges.push((range, *target)),
+                }
+            }
+        }
+        edges
+    }
+
+    /// States from which an accepting state can be reached
+    fn productive(&self) -> Vec<bool> {
+        let mut productive: Vec<bool> = self.states.iter().map(|s| s.accepting).collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (i, state) in self.states.iter().enumerate() {
+                if !productive[i] && state.transitions.iter().flatten().any(|t| productive[*t]) {
+                    productive[i] = true;
+                    changed = true;
+                }
+            }
+        }
+        productive
+    }
+
+    /// Produce the minimal equivalent DFA, without a dead state
+    pub fn minimize(&self) -> Dfa {
+        // Transitions into states which can never accept are equivalent to the dead state
+        let productive = self.productive();
+        let target = |t: &Option<usize>| t.filter(|t| productive[*t]);
+
+        // Refine partitions until states in a block agree on every transition
+        let mut block: Vec<usize> = self.states.iter().map(|s| s.accepting as usize).collect();
+        loop {
+            let mut signatures: HashMap<(usize, Vec<Option<usize>>), usize> = HashMap::new();
+            let mut next_block = Vec::with_capacity(self.states.len());
+            for (i, state) in self.states.iter().enumerate() {
+                let signature = (
+                    block[i],
+                    state
+                        .transitions
+                        .iter()
+                        .map(|t| target(t).map(|t| block[t]))
+                        .collect::<Vec<Option<usize>>>(),
+                );
+                let count = signatures.len();
+                next_block.push(*signatures.entry(signature).or_insert(count));
+            }
+            let stable = signatures.len() == block.iter().collect::<BTreeSet<_>>().len();
+            block = next_block;
+            if stable {
+                break;
+            }
+        }
+
+        // Renumber reachable blocks in breadth first order from the start state
+        let mut order: HashMap<usize, usize> = HashMap::new();
+        let mut representative: Vec<usize> = Vec::new();
+        let mut queue = VecDeque::from([self.start]);
+        order.insert(block[self.start], 0);
+        representative.push(self.start);
+        while let Some(state) = queue.pop_front() {
+            for t in self.states[state].transitions.iter().filter_map(target) {
+                if let std::collections::hash_map::Entry::Vacant(e) = order.entry(block[t]) {
+                    e.insert(representative.len());
+                    representative.push(t);
+                    queue.push_back(t);
+                }
+            }
+        }
+
+        let states = representative
+            .iter()
+            .map(|state| DfaState {
+                transitions: self.states[*state]
+                    .transitions
+                    .iter()
+                    .map(|t| target(t).map(|t| order[&block[t]]))
+                    .collect(),
+                accepting: self.states[*state].accepting,
+            })
+            .collect();
+
+        Dfa {
+            classes: self.classes.clone(),
+            states,
+            start: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        automaton::{dfa::Dfa, nfa::Nfa},
+        extract::Language,
+        parser::RegExParser,
+    };
+
+    fn compile(regex: &str) -> Dfa {
+        let tree = RegExParser::new(Language::Rust, &regex.to_string()).parse().unwrap();
+        Dfa::from_nfa(&Nfa::from_regex(&tree).unwrap()).unwrap().minimize()
+    }
+
+    #[test]
+    fn test_dfa_matches() {
+        let dfa = compile("^[a-c]+d?$");
+        assert!(dfa.matches("abc"));
+        assert!(dfa.matches("cd"));
+        assert!(!dfa.matches("d"));
+        assert!(!dfa.matches("abdd"));
+
+        let dfa = compile("one(two){2}three");
+        assert!(dfa.matches("onetwotwothree"));
+        assert!(!dfa.matches("onetwothree"));
+    }
+
+    #[test]
+    fn test_dfa_minimize() {
+        // (a|b)*abb needs four states once minimized
+        assert_eq!(compile("(a|b)*abb").states.len(), 4);
+        // Equivalent alternatives collapse to the same automaton
+        assert_eq!(compile("a|a").states.len(), compile("a").states.len());
+        // Nothing can follow an end of line anchor
+        assert_eq!(compile("a$b").states.len(), 1);
+    }
+}
@@ -0,0 +1,218 @@
+use tracing::info;
+
+use crate::{
+    automaton::charset::CharSet,
+    error::Error,
+    flags::Flags,
+    parser::{AnchorType, QuantifierMode, RegEx, RepetitionType},
+};
+
+/// Maximum number of NFA states before construction is abandoned
+pub const NFA_STATE_LIMIT: usize = 10_000;
+
+/// The condition for following an NFA transition
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Label {
+    Epsilon,
+    Set(CharSet),
+    Assert(Assertion),
+}
+
+/// Zero-width assertions which can be represented in a finite automaton
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Assertion {
+    Start,
+    End,
+}
+
+#[derive(Debug, Default)]
+pub struct State {
+    pub transitions: Vec<(Label, usize)>,
+}
+
+/// A Thompson NFA with a single start and accepting state
+#[derive(Debug)]
+pub struct Nfa {
+    pub states: Vec<State>,
+    pub start: usize,
+    pub accept: usize,
+    flags: Flags,
+}
+
+/// Entry and exit states of a partially constructed automaton
+type Fragment = (usize, usize);
+
+impl Nfa {
+    /// Compile a parsed regular expression using Thompson's construction
+    pub fn from_regex(tree: &RegEx) -> Result<Nfa, Error> {
+        Self::from_regex_with_flags(tree, &Flags::default())
+    }
+
+    /// Compile a parsed regular expression, applying the case insensitive and dot
+    /// matches newline flags
+    pub fn from_regex_with_flags(tree: &RegEx, flags: &Flags) -> Result<Nfa, Error> {
+        let mut nfa = Nfa {
+            states: Vec::new(),
+            start: 0,
+            accept: 0,
+            flags: *flags,
+        };
+        let (start, accept) = nfa.compile(tree)?;
+        nfa.start = start;
+        nfa.accept = accept;
+        info!("Compiled NFA with {} states", nfa.states.len());
+        Ok(nfa)
+    }
+
+    /// Every character set used on a transition
+    pub fn charsets(&self) -> impl Iterator<Item = &CharSet> {
+        self.states.iter().flat_map(|s| {
+            s.transitions.iter().filter_map(|(label, _)| match label {
+                Label::Set(set) => Some(set),
+                _ => None,
+            })
+        })
+    }
+
+    fn add_state(&mut self) -> Result<usize, Error> {
+        if self.states.len() >= NFA_STATE_LIMIT {
+            return Err(Error::StateLimit(NFA_STATE_LIMIT));
+        }
+        self.states.push(State::default());
+        Ok(self.states.len() - 1)
+    }
+
+    fn connect(&mut self, from: usize, label: Label, to: usize) {
+        self.states[from].transitions.push((label, to));
+    }
+
+    /// Fragment which matches the empty string
+    fn empty(&mut self) -> Result<Fragment, Error> {
+        let start = self.add_state()?;
+        let end = self.add_state()?;
+        self.connect(start, Label::Epsilon, end);
+        Ok((start, end))
+    }
+
+    fn single(&mut self, label: Label) -> Result<Fragment, Error> {
+        let start = self.add_state()?;
+        let end = self.add_state()?;
+        self.connect(start, label, end);
+        Ok((start, end))
+    }
+
+    fn concatenate(&mut self, fragments: Vec<Fragment>) -> Result<Fragment, Error> {
+        match (fragments.first(), fragments.last()) {
+            (Some(first), Some(last)) => {
+                for pair in fragments.windows(2) {
+                    self.connect(pair[0].1, Label::Epsilon, pair[1].0);
+                }
+                Ok((first.0, last.1))
+            }
+            _ => self.empty(),
+        }
+    }
+
+    fn optional(&mut self, fragment: Fragment) -> Result<Fragment, Error> {
+        let start = self.add_state()?;
+        let end = self.add_state()?;
+        self.connect(start, Label::Epsilon, fragment.0);
+        self.connect(start, Label::Epsilon, end);
+        self.connect(fragment.1, Label::Epsilon, end);
+        Ok((start, end))
+    }
+
+    fn star(&mut self, fragment: Fragment) -> Result<Fragment, Error> {
+        let (start, end) = self.optional(fragment)?;
+        self.connect(fragment.1, Label::Epsilon, fragment.0);
+        Ok((start, end))
+    }
+
+    fn compile(&mut self, tree: &RegEx) -> Result<Fragment, Error> {
+        match tree {
+            RegEx::Element(v) => {
+                let mut fragments = Vec::new();
+                for elem in v.iter() {
+                    fragments.push(self.compile(elem)?);
+                }
+                self.concatenate(fragments)
+            }
+            RegEx::Alternation(v) => {
+                let start = self.add_state()?;
+                let end = self.add_state()?;
+                for elem in v.iter() {
+                    let (a, b) = self.compile(elem)?;
+                    self.connect(start, Label::Epsilon, a);
+                    self.connect(b, Label::Epsilon, end);
+                }
+                Ok((start, end))
+            }
+            RegEx::Repetition(repetition, a) => {
+                let (min, max) = match repetition {
+                    RepetitionType::OrMore(n) => (*n, None),
+                    RepetitionType::ZeroOrOne => (0, Some(1)),
+                    RepetitionType::Exactly(n) => (*n, Some(*n)),
+                    RepetitionType::Between(n, m) => (*n, Some(*m)),
+                };
+                let mut fragments = Vec::new();
+                for _ in 0..min {
+                    fragments.push(self.compile(a)?);
+                }
+                match max {
+                    None => {
+                        let fragment = self.compile(a)?;
+                        fragments.push(self.star(fragment)?);
+                    }
+                    Some(max) => {
+                        for _ in min..max {
+                            let fragment = self.compile(a)?;
+                            fragments.push(self.optional(fragment)?);
+                        }
+                    }
+                }
+                self.concatenate(fragments)
+            }
+            RegEx::Character(a) => {
+                self.single(Label::Set(CharSet::from_character_with_flags(a, &self.flags)?))
+            }
+            RegEx::Anchor(a) => match a {
+                AnchorType::Start | AnchorType::PathStart => self.single(Label::Assert(Assertion::Start)),
+                AnchorType::End => self.single(Label::Assert(Assertion::End)),
+                // Moving the reported start or end of a match doesn't change which strings match
+                AnchorType::MatchStart | AnchorType::MatchEnd => self.empty(),
+                other => Err(Error::UnsupportedAutomaton(format!("{:?}", other))),
+            },
+            RegEx::Terminal(a) => {
+                let mut fragments = Vec::new();
+                for c in a.chars() {
+                    let set = if self.flags.case_insensitive {
+                        CharSet::single(c).case_folded()
+                    } else {
+                        CharSet::single(c)
+                    };
+                    fragments.push(self.single(Label::Set(set))?);
+                }
+                self.concatenate(fragments)
+            }
+            RegEx::Capture(_, _, a) => self.compile(a),
+            // Laziness changes which match is found but not which strings match
+            RegEx::Quantifier(QuantifierMode::Possessive, _) => {
+                Err(Error::UnsupportedAutomaton(String::from("Possessive quantifier")))
+            }
+            RegEx::Quantifier(_, a) => self.compile(a),
+            RegEx::Lookaround(kind, _) => Err(Error::UnsupportedAutomaton(format!("{:?}", kind))),
+            RegEx::Backreference(..) => Err(Error::UnsupportedAutomaton(String::from("Backreference"))),
+            RegEx::Balanced(..) => Err(Error::UnsupportedAutomaton(String::from("Balanced match"))),
+            RegEx::Frontier(_) => Err(Error::UnsupportedAutomaton(String::from("Frontier"))),
+            RegEx::OptionalSequence(v) => {
+                let mut fragment = self.empty()?;
+                for atom in v.iter().rev() {
+                    let atom = self.compile(atom)?;
+                    let sequence = self.concatenate(vec![atom, fragment])?;
+                    fragment = self.optional(sequence)?;
+                }
+                Ok(fragment)
+            }
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use std::iter;
+
+use crate::{
+    automaton::{
+        dfa::Dfa,
+        nfa::{Assertion, Label, Nfa},
+    },
+    railroad::sym,
+};
+
+// Repeat character n times
+fn repeat(character: char, n: usize) -> String {
+    iter::repeat_n(character, n).collect::<String>()
+}
+
+/// A directed graph of numbered states which can be drawn as text
+pub trait StateGraph {
+    fn state_count(&self) -> usize;
+
+    fn start(&self) -> usize;
+
+    fn is_accepting(&self, state: usize) -> bool;
+
+    /// Outgoing transitions as (label, target) pairs
+    fn labelled_edges(&self, state: usize) -> Vec<(String, usize)>;
+}
+
+impl StateGraph for Nfa {
+    fn state_count(&self) -> usize {
+        self.states.len()
+    }
+
+    fn start(&self) -> usize {
+        self.start
+    }
+
+    fn is_accepting(&self, state: usize) -> bool {
+        state == self.accept
+    }
+
+    fn labelled_edges(&self, state: usize) -> Vec<(String, usize)> {
+        self.states[state]
+            .transitions
+            .iter()
+            .map(|(label, to)| {
+                let text = match label {
+                    Label::Epsilon => String::from("ε"),
+                    Label::Set(set) => format!("{}", set),
+                    Label::Assert(Assertion::Start) => String::from("LINE START"),
+                    Label::Assert(Assertion::End) => String::from("LINE END"),
+                };
+                (text, *to)
+            })
+            .collect()
+    }
+}
+
+impl StateGraph for Dfa {
+    fn state_count(&self) -> usize {
+        self.states.len()
+    }
+
+    fn start(&self) -> usize {
+        self.start
+    }
+
+    fn is_accepting(&self, state: usize) -> bool {
+        self.states[state].accepting
+    }
+
+    fn labelled_edges(&self, state: usize) -> Vec<(String, usize)> {
+        self.edges(state)
+            .iter()
+            .map(|(set, to)| (format!("{}", set), *to))
+            .collect()
+    }
+}
+
+/// Draws a state graph, one state per block of rows
+///
+///          ┌───┐
+///   START╟─┤ 0 ├┬─ a ─┤ 1
+///          └───┘╰─ b ─┤ 2
+///          ┏━━━┓
+///          ┨ 1 ┃
+///          ┗━━━┛
+///
+/// Accepting states are drawn with a bold border
+pub struct GraphRenderer {}
+
+impl GraphRenderer {
+    pub fn render<G: StateGraph>(graph: &G) -> Vec<String> {
+        let id_width = format!("{}", graph.state_count().saturating_sub(1)).chars().count();
+        let box_width = id_width + 4;
+        let start_text = format!("START{}{}", sym::START, sym::L_HORZ);
+        let margin = repeat(' ', start_text.chars().count());
+        let label_width = (0..graph.state_count())
+            .flat_map(|s| graph.labelled_edges(s))
+            .map(|(label, _)| label.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut diagram = Vec::new();
+        for state in 0..graph.state_count() {
+            let edges = graph.labelled_edges(state);
+            let (tl, tr, bl, br, horz, left, right, vert) = if graph.is_accepting(state) {
+                (
+                    sym::C_TL_SQR_B, sym::C_TR_SQR_B, sym::C_BL_SQR_B, sym::C_BR_SQR_B,
+                    sym::L_HORZ_B, sym::J_LEFT_B, sym::J_RIGHT_B, sym::L_VERT_B,
+                )
+            } else {
+                (
+                    sym::C_TL_SQR, sym::C_TR_SQR, sym::C_BL_SQR, sym::C_BR_SQR,
+                    sym::L_HORZ, sym::J_LEFT, sym::J_RIGHT, sym::L_VERT,
+                )
+            };
+            let left = if state == graph.start() { left } else { vert };
+            let right = if edges.is_empty() { vert } else { right };
+
+            let rows = std::cmp::max(3, edges.len() + 1);
+            for row in 0..rows {
+                let prefix = if row == 1 && state == graph.start() {
+                    start_text.clone()
+                } else {
+                    margin.clone()
+                };
+                let body = match row {
+                    0 => format!("{}{}{}", tl, repeat(horz, box_width - 2), tr),
+                    1 => format!("{} {:>width$} {}", left, state, right, width = id_width),
+                    2 => format!("{}{}{}", bl, repeat(horz, box_width - 2), br),
+                    _ => repeat(' ', box_width),
+                };
+                let edge = match row.checked_sub(1).and_then(|i| edges.get(i).map(|e| (i, e))) {
+                    Some((i, (label, to))) => {
+                        let connector = if edges.len() == 1 {
+                            sym::L_HORZ
+                        } else if i == 0 {
+                            sym::J_DOWN
+                        } else if i == edges.len() - 1 {
+                            sym::C_BL_RND
+                        } else {
+                            sym::J_RIGHT
+                        };
+                        format!(
+                            "{}{} {}{} {}{} {}",
+                            connector,
+                            sym::L_HORZ,
+                            label,
+                            repeat(' ', label_width - label.chars().count()),
+                            sym::L_HORZ,
+                            sym::J_LEFT,
+                            to
+                        )
+                    }
+                    None => String::new(),
+                };
+                diagram.push(format!("{}{}{}", prefix, body, edge));
+            }
+        }
+
+        // Pad all rows to the same width
+        let width = diagram.iter().map(|x| x.chars().count()).max().unwrap_or(0);
+        diagram
+            .into_iter()
+            .map(|x| {
+                let len = x.chars().count();
+                format!("{}{}", x, repeat(' ', width - len))
+            })
+            .collect()
+    }
+}
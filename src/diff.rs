@@ -0,0 +1,183 @@
+use std::mem::discriminant;
+
+use crate::parser::{RegEx, RepetitionType};
+
+/// Change applied to a node between the old and new regular expression
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Status {
+    Unchanged,
+    Inserted,
+    Deleted,
+    Modified,
+}
+
+/// A node of the merged old and new syntax trees
+#[derive(Eq, PartialEq, Debug)]
+pub struct DiffTree<'a> {
+    pub status: Status,
+    pub kind: DiffKind<'a>,
+}
+
+#[derive(Eq, PartialEq, Debug)]
+pub enum DiffKind<'a> {
+    /// A subtree taken whole from one of the regular expressions
+    Node(&'a RegEx),
+    Element(Vec<DiffTree<'a>>),
+    Alternation(Vec<DiffTree<'a>>),
+    Repetition(RepetitionType, Box<DiffTree<'a>>),
+    Capture(Option<String>, usize, Box<DiffTree<'a>>),
+}
+
+impl<'a> DiffTree<'a> {
+    fn leaf(status: Status, node: &'a RegEx) -> DiffTree<'a> {
+        DiffTree { status, kind: DiffKind::Node(node) }
+    }
+
+    /// Structurally diff two regular expressions
+    pub fn new(old: &'a RegEx, new: &'a RegEx) -> DiffTree<'a> {
+        if old == new {
+            return Self::leaf(Status::Unchanged, new);
+        }
+        match (old, new) {
+            (RegEx::Element(a), RegEx::Element(b)) => DiffTree {
+                status: Status::Unchanged,
+                kind: DiffKind::Element(Self::diff_children(a, b)),
+            },
+            (RegEx::Alternation(a), RegEx::Alternation(b)) => DiffTree {
+                status: Status::Unchanged,
+                kind: DiffKind::Alternation(Self::diff_children(a, b)),
+            },
+            (RegEx::Repetition(t1, a), RegEx::Repetition(t2, b)) => DiffTree {
+                status: if t1 == t2 { Status::Unchanged } else { Status::Modified },
+                kind: DiffKind::Repetition(*t2, Box::new(Self::new(a, b))),
+            },
+            (RegEx::Capture(n1, g1, a), RegEx::Capture(n2, g2, b)) => DiffTree {
+                status: if n1 == n2 && g1 == g2 { Status::Unchanged } else { Status::Modified },
+                kind: DiffKind::Capture(n2.clone(), *g2, Box::new(Self::new(a, b))),
+            },
+            (RegEx::Terminal(_), RegEx::Terminal(_))
+            | (RegEx::Character(_), RegEx::Character(_))
+            | (RegEx::Anchor(_), RegEx::Anchor(_))
+            | (RegEx::Lookaround(..), RegEx::Lookaround(..))
+            | (RegEx::Quantifier(..), RegEx::Quantifier(..))
+            | (RegEx::OptionalSequence(..), RegEx::OptionalSequence(..))
+            | (RegEx::Balanced(..), RegEx::Balanced(..))
+            | (RegEx::Frontier(_), RegEx::Frontier(_))
+            | (RegEx::Backreference(..), RegEx::Backreference(..)) => Self::leaf(Status::Modified, new),
+            // Nodes of different types are replaced outright
+            _ => DiffTree {
+                status: Status::Unchanged,
+                kind: DiffKind::Element(vec![
+                    Self::leaf(Status::Deleted, old),
+                    Self::leaf(Status::Inserted, new),
+                ]),
+            },
+        }
+    }
+
+    /// Align two lists of children on their longest common subsequence, pairing up
+    /// remaining children of the same type as modifications
+    fn diff_children(old: &'a [Box<RegEx>], new: &'a [Box<RegEx>]) -> Vec<DiffTree<'a>> {
+        // lcs[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+        let mut lcs = vec![vec![0; new.len() + 1]; old.len() + 1];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lcs[i][j] = if old[i] == new[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    std::cmp::max(lcs[i + 1][j], lcs[i][j + 1])
+                };
+            }
+        }
+
+        let mut children = Vec::new();
+        let mut deleted: Vec<&RegEx> = Vec::new();
+        let mut inserted: Vec<&RegEx> = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && old[i] == new[j] {
+                Self::flush(&mut children, &mut deleted, &mut inserted);
+                children.push(Self::leaf(Status::Unchanged, &new[j]));
+                i += 1;
+                j += 1;
+            } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+                deleted.push(&old[i]);
+                i += 1;
+            } else {
+                inserted.push(&new[j]);
+                j += 1;
+            }
+        }
+        Self::flush(&mut children, &mut deleted, &mut inserted);
+        children
+    }
+
+    /// Emit the changes between two common children
+    fn flush(children: &mut Vec<DiffTree<'a>>, deleted: &mut Vec<&'a RegEx>, inserted: &mut Vec<&'a RegEx>) {
+        let mut inserted_iter = std::mem::take(inserted).into_iter().peekable();
+        for old in std::mem::take(deleted) {
+            match inserted_iter.peek() {
+                Some(new) if discriminant(old) == discriminant(*new) => {
+                    children.push(Self::new(old, new));
+                    inserted_iter.next();
+                }
+                _ => children.push(Self::leaf(Status::Deleted, old)),
+            }
+        }
+        children.extend(inserted_iter.map(|new| Self::leaf(Status::Inserted, new)));
+    }
+
+    /// Number of inserted, deleted and modified nodes
+    pub fn count(&self) -> (usize, usize, usize) {
+        let own = match self.status {
+            Status::Inserted => (1, 0, 0),
+            Status::Deleted => (0, 1, 0),
+            Status::Modified => (0, 0, 1),
+            Status::Unchanged => (0, 0, 0),
+        };
+        let children: Vec<&DiffTree> = match &self.kind {
+            DiffKind::Node(_) => Vec::new(),
+            DiffKind::Element(v) | DiffKind::Alternation(v) => v.iter().collect(),
+            DiffKind::Repetition(_, a) | DiffKind::Capture(_, _, a) => vec![a.as_ref()],
+        };
+        children.iter().map(|c| c.count()).fold(own, |acc, c| {
+            (acc.0 + c.0, acc.1 + c.1, acc.2 + c.2)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        diff::{DiffKind, DiffTree, Status},
+        extract::Language,
+        parser::{RegEx, RegExParser},
+    };
+
+    fn parse(regex: &str) -> RegEx {
+        RegExParser::new(Language::Rust, &regex.to_string()).parse().unwrap()
+    }
+
+    #[test]
+    fn test_diff_unchanged() {
+        let (old, new) = (parse("a(b|c)+"), parse("a(b|c)+"));
+        let diff = DiffTree::new(&old, &new);
+        assert_eq!(diff.status, Status::Unchanged);
+        assert_eq!(diff.count(), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_diff_changes() {
+        // Terminal modified, character class inserted
+        let (old, new) = (parse("ab*"), parse("ac*[0-9]"));
+        let diff = DiffTree::new(&old, &new);
+        assert_eq!(diff.count(), (1, 0, 1));
+
+        // Repetition count changed and alternative removed
+        let (old, new) = (parse("(a|b|c){2}"), parse("(a|c){3}"));
+        let diff = DiffTree::new(&old, &new);
+        assert_eq!(diff.count(), (0, 1, 1));
+        let DiffKind::Element(children) = diff.kind else { panic!("Expected element") };
+        assert_eq!(children[0].status, Status::Modified);
+    }
+}
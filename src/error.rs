@@ -1,6 +1,6 @@
 use std::convert::From;
 
-use crate::extract::Language;
+use crate::{extract::Language, parser::Flavor};
 
 #[derive(Clone, Debug)]
 pub enum Error {
@@ -12,6 +12,20 @@ pub enum Error {
     InvalidString(Language, String),
     InvalidCharacter(char, usize),
     InvalidParsing,
+    InvalidFlag(char),
+    UnterminatedLiteral(String),
+    UnexpectedEnd(String),
+    FormatField(String),
+    MissingPattern(String),
+    UnsupportedSyntax(Flavor, String),
+    UnknownGroup(String),
+    UnknownProperty(String),
+    InvalidDefinition(String, String),
+    UnknownFlavor(String),
+    StateLimit(usize),
+    MatchLimit(usize),
+    ProgramLimit(usize),
+    UnsupportedAutomaton(String),
 }
 
 impl std::fmt::Display for Error {
@@ -31,6 +45,27 @@ impl std::fmt::Display for Error {
             Self::InvalidString(lang, string) => write!(f, "Invalid {} string {}", lang, string),
             Self::InvalidCharacter(c, idx) => write!(f, "Character {} invalid in index {}", c, idx),
             Self::InvalidParsing => write!(f, "Invalid parsing"),
+            Self::InvalidFlag(a) => write!(f, "Unknown regular expression flag '{}'", a),
+            Self::UnterminatedLiteral(a) => write!(f, "Unterminated literal {}", a),
+            Self::UnexpectedEnd(a) => write!(f, "Pattern {} ends before it is complete", a),
+            Self::UnsupportedSyntax(flavor, a) => write!(f, "{} regular expressions do not support {}", flavor, a),
+            Self::UnknownGroup(a) => write!(f, "Reference to unknown group '{}'", a),
+            Self::UnknownProperty(a) => write!(f, "Unknown character property '{}'", a),
+            Self::InvalidDefinition(name, reason) => write!(f, "Invalid definition of language '{}': {}", name, reason),
+            Self::UnknownFlavor(a) => {
+                let names: Vec<String> = Flavor::ALL.iter().map(|x| x.to_string()).collect();
+                write!(f, "Unknown regular expression flavor '{}', expected one of {}", a, names.join(", "))
+            }
+            Self::MissingPattern(a) => write!(f, "No pattern literal found in call to {}", a),
+            Self::FormatField(a) => {
+                write!(f, "Cannot draw interpolated value {{{}}} known only at runtime", a)
+            }
+            Self::StateLimit(a) => write!(f, "Automaton exceeds {} states", a),
+            Self::MatchLimit(a) => write!(f, "Matching exceeded {} backtracking steps", a),
+            Self::ProgramLimit(a) => write!(f, "Pattern is too large to match, exceeding {} instructions", a),
+            Self::UnsupportedAutomaton(a) => {
+                write!(f, "{} cannot be represented as a finite automaton", a)
+            }
         }
     }
 }
@@ -1,9 +1,34 @@
 use lazy_static::lazy_static;
-use std::{collections::HashMap, fmt::Display};
+use std::{collections::HashMap, fmt::Display, ops::Range};
 use tracing::info;
 
-use crate::error::Error;
+use crate::{
+    error::Error,
+    extract::{
+        call::{Call, FlagSyntax},
+        decoded::Decoded,
+        definition::LanguageDefinition,
+    },
+    flags::Flags,
+    parser::Flavor,
+};
 
+pub mod call;
+pub mod config;
+pub mod decoded;
+pub mod definition;
+pub mod delimited;
+pub mod glob;
+pub mod literal;
+pub mod shell;
+pub mod sql;
+
+/// Parses a complete string literal into the text it represents
+pub type LiteralParser = fn(&str) -> Result<Decoded, Error>;
+
+/// Parses a regex literal with delimiters and modifiers, returning `None` if the text is
+/// not one
+pub type RegexLiteralParser = fn(&str) -> Option<Result<ExtractedRegex, Error>>;
 
 #[derive(Debug)]
 pub struct StringFormat {
@@ -11,12 +36,24 @@ pub struct StringFormat {
     escape_character: char,
     literal_string_start: Option<Vec<String>>,
     literal_string_end: Option<Vec<String>>,
+    regex_literal_flags: Option<String>,
+    /// Parser for the language's full string literal syntax, used instead of stripping
+    /// the start and end characters
+    literal_parser: Option<LiteralParser>,
+    /// Parser for regex literals with arbitrary delimiters, such as Perl's `s{...}{...}g`
+    regex_parser: Option<RegexLiteralParser>,
+    /// Regular expression syntax used by the language's standard library
+    flavor: Flavor,
 }
 
 impl StringFormat {
     pub fn escape_char(&self) -> char {
         self.escape_character
     }
+
+    pub fn flavor(&self) -> Flavor {
+        self.flavor
+    }
 }
 
 #[derive(Clone, Eq, Hash, PartialEq, Debug)]
@@ -24,69 +61,569 @@ pub enum Language {
     Python,
     Rust,
     Javascript,
+    Go,
+    Java,
+    Kotlin,
+    Cpp,
+    Ruby,
+    Perl,
+    Php,
+    Csharp,
+    Swift,
+    Typescript,
+    Vim,
+    Lua,
+    Shell,
+    Awk,
+    Sed,
+    /// `.gitignore` and other ignore files, with one pattern per line
+    Gitignore,
+    /// `CODEOWNERS` files, with a pattern and its owners on each line
+    Codeowners,
+    Sql,
+    Elixir,
+    Erlang,
+    Haskell,
+    Yaml,
+    Toml,
+    Json,
+    /// `.editorconfig` files, whose section headers are globs
+    Editorconfig,
+    /// A language defined by the user
+    Custom(String),
     Unknown(String),
     None,
 }
 
 impl Display for Language {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Language::Custom(name) => write!(f, "{}", name),
+            other => write!(f, "{:?}", other),
+        }
     }
 }
 
 impl Language {
     pub fn from_filename(filename: &str) -> Language {
-        match filename.split('.').last() {
+        // Files named without an extension
+        if filename.rsplit(['/', '\\']).next() == Some("CODEOWNERS") {
+            return Language::Codeowners;
+        }
+        match filename.split('.').next_back() {
             Some(extension) => {
                 info!("Found file extension '.{}'", extension);
                 match extension {
-                    "py" => Language::Python,
+                    "py" | "pyi" => Language::Python,
                     "rs" => Language::Rust,
-                    "js" => Language::Javascript,
+                    "js" | "mjs" | "cjs" => Language::Javascript,
+                    "go" => Language::Go,
+                    "java" => Language::Java,
+                    "kt" | "kts" => Language::Kotlin,
+                    "c" | "cpp" | "cc" | "cxx" | "hpp" | "h" => Language::Cpp,
+                    "rb" => Language::Ruby,
+                    "pl" | "pm" => Language::Perl,
+                    "php" => Language::Php,
+                    "cs" => Language::Csharp,
+                    "swift" => Language::Swift,
+                    "ts" | "tsx" | "mts" | "cts" => Language::Typescript,
+                    "vim" => Language::Vim,
+                    "lua" => Language::Lua,
+                    "sh" | "bash" | "zsh" => Language::Shell,
+                    "awk" => Language::Awk,
+                    "sed" => Language::Sed,
+                    "gitignore" | "dockerignore" | "npmignore" | "ignore" => Language::Gitignore,
+                    "sql" => Language::Sql,
+                    "ex" | "exs" => Language::Elixir,
+                    "erl" | "hrl" => Language::Erlang,
+                    "hs" => Language::Haskell,
+                    "yaml" | "yml" => Language::Yaml,
+                    "toml" => Language::Toml,
+                    "json" | "jsonc" => Language::Json,
+                    "editorconfig" => Language::Editorconfig,
                     _ => Language::Unknown(extension.to_string()),
                 }
             }
             None => Language::None,
         }
     }
+
+    /// Map a Neovim filetype or tree-sitter language name to a language
+    pub fn from_filetype(filetype: &str) -> Option<Language> {
+        let language = match filetype {
+            "python" => Language::Python,
+            "rust" => Language::Rust,
+            "javascript" | "javascriptreact" => Language::Javascript,
+            "go" => Language::Go,
+            "java" => Language::Java,
+            "kotlin" => Language::Kotlin,
+            "c" | "cpp" => Language::Cpp,
+            "ruby" => Language::Ruby,
+            "perl" => Language::Perl,
+            "php" => Language::Php,
+            "cs" | "c_sharp" => Language::Csharp,
+            "swift" => Language::Swift,
+            "typescript" | "typescriptreact" | "tsx" => Language::Typescript,
+            "vim" => Language::Vim,
+            "lua" => Language::Lua,
+            "sh" | "bash" | "zsh" => Language::Shell,
+            "awk" => Language::Awk,
+            "sed" => Language::Sed,
+            "gitignore" => Language::Gitignore,
+            "codeowners" => Language::Codeowners,
+            "sql" | "mysql" | "plsql" => Language::Sql,
+            "elixir" => Language::Elixir,
+            "erlang" => Language::Erlang,
+            "haskell" => Language::Haskell,
+            "yaml" => Language::Yaml,
+            "toml" => Language::Toml,
+            "json" | "jsonc" => Language::Json,
+            "editorconfig" => Language::Editorconfig,
+            _ => return None,
+        };
+        info!("Found language {} from filetype '{}'", language, filetype);
+        Some(language)
+    }
+
 }
 
 lazy_static! {
     /// Mapping of file extension to the language's string format
     pub static ref STRING_FORMAT: HashMap<Language, StringFormat> = HashMap::from([
         (Language::Python, StringFormat {
-                string_character: ["\""].iter().map(|x| x.to_string()).collect(),
+                string_character: ["\"", "'", "\"\"\"", "'''"].iter().map(|x| x.to_string()).collect(),
                 escape_character: '\\',
-                literal_string_start: Some(["r\""].iter().map(|x| x.to_string()).collect()),
-                literal_string_end: Some(["\""].iter().map(|x| x.to_string()).collect()),
+                literal_string_start: Some(["r\"", "r'"].iter().map(|x| x.to_string()).collect()),
+                literal_string_end: Some(["\"", "'"].iter().map(|x| x.to_string()).collect()),
+                regex_literal_flags: None,
+                regex_parser: None,
+                literal_parser: Some(literal::python_string),
+                flavor: Flavor::Pcre,
         }),
         (Language::Rust, StringFormat {
                 string_character: ["\""].iter().map(|x| x.to_string()).collect(),
                 escape_character: '\\',
                 literal_string_start: Some(["r\""].iter().map(|x| x.to_string()).collect()),
                 literal_string_end: Some(["\""].iter().map(|x| x.to_string()).collect()),
+                regex_literal_flags: None,
+                regex_parser: None,
+                literal_parser: Some(literal::rust_string),
+                flavor: Flavor::Pcre,
         }),
         (Language::Javascript, StringFormat {
+                string_character: ["\"", "'", "`"].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: Some(String::from("dgimsuvy")),
+                regex_parser: None,
+                literal_parser: Some(literal::javascript_string),
+                flavor: Flavor::Pcre,
+        }),
+        (Language::Typescript, StringFormat {
+                string_character: ["\"", "'", "`"].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: Some(String::from("dgimsuvy")),
+                regex_parser: None,
+                literal_parser: Some(literal::javascript_string),
+                flavor: Flavor::Pcre,
+        }),
+        (Language::Go, StringFormat {
+                string_character: ["\""].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: Some(["`"].iter().map(|x| x.to_string()).collect()),
+                literal_string_end: Some(["`"].iter().map(|x| x.to_string()).collect()),
+                regex_literal_flags: None,
+                regex_parser: None,
+                literal_parser: Some(literal::go_string),
+                flavor: Flavor::Re2,
+        }),
+        (Language::Java, StringFormat {
+                string_character: ["\"", "\"\"\""].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: None,
+                literal_parser: Some(literal::java_string),
+                flavor: Flavor::Java,
+        }),
+        (Language::Kotlin, StringFormat {
+                string_character: ["\""].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: Some(["\"\"\""].iter().map(|x| x.to_string()).collect()),
+                literal_string_end: Some(["\"\"\""].iter().map(|x| x.to_string()).collect()),
+                regex_literal_flags: None,
+                regex_parser: None,
+                literal_parser: Some(literal::kotlin_string),
+                flavor: Flavor::Java,
+        }),
+        (Language::Cpp, StringFormat {
                 string_character: ["\""].iter().map(|x| x.to_string()).collect(),
                 escape_character: '\\',
+                literal_string_start: Some(["R\"("].iter().map(|x| x.to_string()).collect()),
+                literal_string_end: Some([")\""].iter().map(|x| x.to_string()).collect()),
+                regex_literal_flags: None,
+                regex_parser: None,
+                literal_parser: Some(literal::cpp_string),
+                flavor: Flavor::Ecmascript,
+        }),
+        (Language::Ruby, StringFormat {
+                string_character: ["\"", "'"].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: Some(delimited::ruby_regex),
+                literal_parser: Some(literal::ruby_string),
+                flavor: Flavor::Pcre,
+        }),
+        (Language::Perl, StringFormat {
+                string_character: ["\"", "'"].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: Some(delimited::perl_regex),
+                literal_parser: Some(literal::perl_string),
+                flavor: Flavor::Pcre,
+        }),
+        (Language::Php, StringFormat {
+                string_character: ["\"", "'"].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: Some(delimited::php_regex),
+                literal_parser: Some(literal::php_string),
+                flavor: Flavor::Pcre,
+        }),
+        (Language::Csharp, StringFormat {
+                string_character: ["\""].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: Some(["@\""].iter().map(|x| x.to_string()).collect()),
+                literal_string_end: Some(["\""].iter().map(|x| x.to_string()).collect()),
+                regex_literal_flags: None,
+                regex_parser: None,
+                literal_parser: Some(literal::csharp_string),
+                flavor: Flavor::Pcre,
+        }),
+        (Language::Swift, StringFormat {
+                string_character: ["\"", "\"\"\""].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: Some(["#\""].iter().map(|x| x.to_string()).collect()),
+                literal_string_end: Some(["\"#"].iter().map(|x| x.to_string()).collect()),
+                regex_literal_flags: None,
+                regex_parser: Some(delimited::swift_regex),
+                literal_parser: Some(literal::swift_string),
+                flavor: Flavor::Pcre,
+        }),
+        (Language::Vim, StringFormat {
+                string_character: ["\"", "'"].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
                 literal_string_start: None,
                 literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: Some(delimited::vim_regex),
+                literal_parser: Some(literal::vim_string),
+                flavor: Flavor::Vim,
+        }),
+        (Language::Lua, StringFormat {
+                string_character: ["\"", "'"].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: None,
+                literal_parser: Some(literal::lua_string),
+                flavor: Flavor::Lua,
+        }),
+        (Language::Shell, StringFormat {
+                string_character: ["\"", "'"].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: Some(shell::shell_regex),
+                literal_parser: Some(shell::shell_string),
+                flavor: Flavor::Bre,
+        }),
+        (Language::Awk, StringFormat {
+                string_character: ["\""].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: Some(delimited::awk_regex),
+                literal_parser: Some(literal::awk_string),
+                flavor: Flavor::Ere,
+        }),
+        (Language::Sed, StringFormat {
+                string_character: Vec::new(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: Some(delimited::sed_regex),
+                literal_parser: None,
+                flavor: Flavor::Bre,
+        }),
+        (Language::Gitignore, StringFormat {
+                string_character: Vec::new(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: Some(glob::gitignore_pattern),
+                literal_parser: None,
+                flavor: Flavor::Gitignore,
+        }),
+        (Language::Codeowners, StringFormat {
+                string_character: Vec::new(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: Some(glob::codeowners_pattern),
+                literal_parser: None,
+                flavor: Flavor::Gitignore,
+        }),
+        (Language::Sql, StringFormat {
+                string_character: ["'"].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: Some(sql::sql_regex),
+                literal_parser: Some(literal::sql_string),
+                flavor: Flavor::Pcre,
+        }),
+        (Language::Elixir, StringFormat {
+                string_character: ["\"", "'", "\"\"\""].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: Some(delimited::elixir_regex),
+                literal_parser: Some(literal::elixir_string),
+                flavor: Flavor::Pcre,
+        }),
+        (Language::Erlang, StringFormat {
+                string_character: ["\""].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: None,
+                literal_parser: Some(literal::erlang_string),
+                flavor: Flavor::Pcre,
+        }),
+        (Language::Haskell, StringFormat {
+                string_character: ["\""].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: Some(delimited::haskell_regex),
+                literal_parser: Some(literal::haskell_string),
+                flavor: Flavor::Pcre,
+        }),
+        (Language::Yaml, StringFormat {
+                string_character: ["\"", "'"].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: None,
+                literal_parser: Some(config::yaml_string),
+                flavor: Flavor::Pcre,
+        }),
+        (Language::Toml, StringFormat {
+                string_character: ["\"", "\"\"\""].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: Some(["'", "'''"].iter().map(|x| x.to_string()).collect()),
+                literal_string_end: Some(["'", "'''"].iter().map(|x| x.to_string()).collect()),
+                regex_literal_flags: None,
+                regex_parser: None,
+                literal_parser: Some(config::toml_string),
+                flavor: Flavor::Pcre,
+        }),
+        (Language::Json, StringFormat {
+                string_character: ["\""].iter().map(|x| x.to_string()).collect(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: None,
+                literal_parser: Some(config::json_string),
+                flavor: Flavor::Pcre,
+        }),
+        (Language::Editorconfig, StringFormat {
+                string_character: Vec::new(),
+                escape_character: '\\',
+                literal_string_start: None,
+                literal_string_end: None,
+                regex_literal_flags: None,
+                regex_parser: Some(glob::editorconfig_pattern),
+                literal_parser: None,
+                flavor: Flavor::Glob,
         })
     ]);
 }
 
+/// How the pattern of a regular expression is written in the source
+#[derive(Clone, Copy, Default, Eq, PartialEq, Debug)]
+pub enum LiteralKind {
+    /// A string literal, whose escapes are decoded
+    #[default]
+    String,
+    /// A raw string literal, such as Python's `r"..."`, read as written
+    RawString,
+    /// A regex literal or operator with delimiters and modifiers, such as `/a+/i`
+    RegexLiteral,
+    /// A line of a file with one pattern per line, such as a `.gitignore` file
+    Line,
+}
+
+impl Display for LiteralKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LiteralKind::String => write!(f, "string"),
+            LiteralKind::RawString => write!(f, "raw string"),
+            LiteralKind::RegexLiteral => write!(f, "regex literal"),
+            LiteralKind::Line => write!(f, "line"),
+        }
+    }
+}
+
+/// A regular expression extracted from source code
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+pub struct ExtractedRegex {
+    pub pattern: String,
+    pub flags: Flags,
+    /// Whether the pattern is written differently in the source, such as when string
+    /// escapes have been decoded
+    pub decoded: bool,
+    /// Replacement text of a substitution, such as Perl's `s/a/b/`
+    pub replacement: Option<String>,
+    /// Syntax of the pattern when it differs from its language's, such as a Vim pattern
+    /// passed to `vim.regex()` in Lua
+    pub flavor: Option<Flavor>,
+    /// How the pattern is written in the source
+    pub kind: LiteralKind,
+    /// Byte offset in the source text of each character of the pattern, followed by the
+    /// offset of its end, or empty if the pattern could not be found in the source
+    pub offsets: Vec<usize>,
+}
+
+impl ExtractedRegex {
+    pub fn new(pattern: String) -> ExtractedRegex {
+        ExtractedRegex { pattern, ..Default::default() }
+    }
+
+    /// Pattern read from a literal, with the offsets of its characters in the literal
+    pub fn from_literal(literal: Decoded) -> ExtractedRegex {
+        ExtractedRegex {
+            pattern: literal.value(),
+            decoded: literal.escaped,
+            kind: literal.kind,
+            offsets: literal.offsets(),
+            ..Default::default()
+        }
+    }
+
+    /// Byte range in the source text of a range of the pattern's characters, such as the
+    /// span of a group, which includes any escapes they were decoded from
+    pub fn source_range(&self, chars: Range<usize>) -> Option<Range<usize>> {
+        let start = *self.offsets.get(chars.start)?;
+        let end = *self.offsets.get(chars.end)?;
+        Some(start..end.max(start))
+    }
+}
+
 #[derive(Default)]
-pub struct RegexExtractor {}
+pub struct RegexExtractor {
+    /// String formats of languages defined by the user
+    custom_formats: HashMap<Language, StringFormat>,
+    /// User defined languages by filetype or tree-sitter language
+    custom_filetypes: HashMap<String, Language>,
+    /// User defined languages by file extension
+    custom_extensions: HashMap<String, Language>,
+    /// Syntax of patterns in configuration files such as YAML, which is decided by the
+    /// tool reading them rather than the format
+    config_flavor: Flavor,
+}
 
 impl RegexExtractor {
     /// Create new instance of RegexExtractor
     pub fn new() -> RegexExtractor {
-        RegexExtractor {}
+        RegexExtractor::default()
+    }
+
+    /// Add a user defined language, replacing any earlier definition of the same name
+    ///
+    /// Its filetypes and extensions take precedence over those of the built-in languages
+    pub fn define_language(&mut self, definition: &LanguageDefinition) -> Result<Language, Error> {
+        let string_format = definition.string_format()?;
+        let language = definition.language();
+        info!("Defined language {} as {:?}", language, string_format);
+
+        self.custom_filetypes.retain(|_, x| *x != language);
+        self.custom_extensions.retain(|_, x| *x != language);
+        for filetype in definition.filetypes.iter().chain([&definition.name]) {
+            self.custom_filetypes.insert(filetype.clone(), language.clone());
+        }
+        for extension in definition.extensions.iter() {
+            self.custom_extensions.insert(extension.trim_start_matches('.').to_string(), language.clone());
+        }
+        self.custom_formats.insert(language.clone(), string_format);
+        Ok(language)
+    }
+
+    /// Detect the language of a regular expression, preferring the tree-sitter language
+    /// of its node so that injected languages such as JavaScript inside HTML are found,
+    /// then the buffer's filetype and only then the file extension
+    pub fn detect_language(&self, filename: &str, filetype: Option<&str>, tree_sitter: Option<&str>) -> Language {
+        let from_filetype = |name: &str| {
+            self.custom_filetypes
+                .get(name)
+                .cloned()
+                .or_else(|| Language::from_filetype(name))
+        };
+        tree_sitter
+            .into_iter()
+            .chain(filetype)
+            .find_map(from_filetype)
+            .unwrap_or_else(|| {
+                let extension = filename.rsplit('.').next().unwrap_or_default();
+                match self.custom_extensions.get(extension) {
+                    Some(language) => language.clone(),
+                    None => Language::from_filename(filename),
+                }
+            })
+    }
+
+    /// Set the syntax of patterns in YAML, TOML and JSON files
+    pub fn set_config_flavor(&mut self, flavor: Flavor) {
+        info!("Using {} for configuration files", flavor);
+        self.config_flavor = flavor;
+    }
+
+    /// Regular expression syntax of a language
+    pub fn flavor(&self, language: &Language) -> Flavor {
+        if matches!(language, Language::Yaml | Language::Toml | Language::Json) {
+            return self.config_flavor;
+        }
+        self.get_string_format(language)
+            .map(|x| x.flavor())
+            .unwrap_or_default()
     }
 
     /// Find string characters used for file type
-    fn get_string_format(&self, language: &Language) -> Result<&StringFormat, Error> {
-        match STRING_FORMAT.get(language) {
+    pub fn get_string_format(&self, language: &Language) -> Result<&StringFormat, Error> {
+        match self.custom_formats.get(language).or_else(|| STRING_FORMAT.get(language)) {
             Some(string_format) => {
                 info!("Found escape character '{:?}'", string_format);
                 Ok(string_format)
@@ -97,54 +634,653 @@ impl RegexExtractor {
 
     /// Checks if start/end of text is consistent with the language's string specification
     /// and strips the start/end characters
-    fn strip_string_start_end(&self, text: &str, start: &[String], end: &[String]) -> String {
-        // Ensure text is long enough to contain start and end characters
-        let text_len = text.len();
-
-        let mut max_start_len = 0;
-        let mut max_end_len = 0;
-
-        for s in start.iter() {
-            if text_len > s.len() {
-                info!("Start: {} - {:?}", &text[0..s.len()], s);
-                if s.contains(&text[0..s.len()].to_string()) {
-                    max_start_len = std::cmp::max(max_start_len, s.len());
+    ///
+    /// The longest matching start is stripped first, and then the longest end which is
+    /// still in the rest of the text.
+    fn strip_string_start_end<'t>(&self, text: &'t str, start: &[String], end: &[String]) -> &'t str {
+        let body = start.iter().filter_map(|x| text.strip_prefix(x.as_str())).min_by_key(|x| x.len()).unwrap_or(text);
+        let body = end.iter().filter_map(|x| body.strip_suffix(x.as_str())).min_by_key(|x| x.len()).unwrap_or(body);
+        info!("Stripped string delimiters of {:?} to {:?}", text, body);
+        body
+    }
+
+    /// Split a `/pattern/flags` regex literal into its pattern and flags
+    ///
+    /// A `/` only closes the literal if it is not escaped and not inside a character class
+    fn parse_regex_literal(&self, text: &str, allowed_flags: &str) -> Result<ExtractedRegex, Error> {
+        let mut chars = text.char_indices().skip(1);
+        let mut in_class = false;
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '[' => in_class = true,
+                ']' => in_class = false,
+                '/' if !in_class => {
+                    let flags = Flags::from_chars(&text[idx + 1..], allowed_flags)?;
+                    info!("Found regex literal with flags {:?}", flags);
+                    return Ok(ExtractedRegex {
+                        pattern: text[1..idx].to_string(),
+                        flags,
+                        kind: LiteralKind::RegexLiteral,
+                        offsets: Decoded::slice(text, &text[1..idx]).offsets(),
+                        ..Default::default()
+                    });
                 }
+                _ => (),
             }
         }
-        for e in end.iter() {
-            if text_len > e.len() {
-                info!("End: {} - {:?}", &text[text_len - end.len()..], end);
-                if end.contains(&text[text_len - e.len()..].to_string()) {
-                    max_end_len = std::cmp::max(max_end_len, e.len());
-                }
+        Err(Error::UnterminatedLiteral(text.to_string()))
+    }
+
+    /// Extract the pattern and flags from a call such as `re.compile(r"\d+", re.I)`
+    ///
+    /// Calls which are not known to compile a regular expression use their first literal
+    /// argument as the pattern
+    ///
+    /// Offsets of the pattern are into the text of the whole call.
+    fn get_call_regex(&self, language: &Language, text: &str, call: &Call) -> Result<ExtractedRegex, Error> {
+        let string_format = self.get_string_format(language)?;
+        let missing = || Error::MissingPattern(call.callee.clone());
+        let Some(regex_call) = call.regex_call(language) else {
+            info!("Unknown call {}, using first literal argument", call.callee);
+            let argument = call.arguments.iter().find(|x| call::is_literal(x)).ok_or_else(missing)?;
+            let mut regex = self.get_literal_regex(language, argument)?;
+            decoded::shift(&mut regex.offsets, text, argument);
+            return Ok(regex);
+        };
+        info!("Found regular expression call {}", regex_call.name);
+
+        let pattern = call.argument(regex_call.pattern, "pattern").ok_or_else(missing)?;
+        let mut regex = self.get_literal_regex(language, pattern)?;
+        decoded::shift(&mut regex.offsets, text, pattern);
+        regex.flavor = regex_call.flavor.or(regex.flavor);
+        let flags_argument = regex_call.flags.and_then(|position| call.argument(position, "flags"));
+        let flags = match (regex_call.flag_syntax, flags_argument) {
+            (FlagSyntax::Builder, _) => call::builder_flags(&call.methods),
+            (FlagSyntax::Constants, Some(argument)) => call::constant_flags(argument),
+            (FlagSyntax::Letters(allowed), Some(argument)) => {
+                let letters = self.get_literal_regex(language, argument)?.pattern;
+                Flags::from_chars(&letters, allowed)?
             }
+            (_, None) => Flags::default(),
+        };
+        regex.flags = regex.flags.union(&flags);
+
+        // Replacements are plain strings rather than patterns
+        regex.replacement = regex_call
+            .replacement
+            .and_then(|position| call.argument(position, "repl"))
+            .filter(|x| call::is_literal(x))
+            .map(|x| self.get_string(string_format, x).map(|x| x.value()))
+            .transpose()?;
+        Ok(regex)
+    }
+
+    /// Check if text is a regular expression based on language, either as a literal or
+    /// as the pattern argument of a call
+    pub fn get_regex(&self, language: &Language, text: &str) -> Result<ExtractedRegex, Error> {
+        // Operators such as Perl's `m(...)` look like calls, so are checked first
+        if let Some(regex) = self.get_delimited_regex(self.get_string_format(language)?, text) {
+            return regex;
+        }
+        match call::parse_call(text) {
+            Some(call) => self.get_call_regex(language, text, &call),
+            None => self.get_literal_regex(language, text),
         }
-        text[max_start_len..text_len - max_end_len].to_string()
     }
 
-    /// Check if text is a regular expression based on language
-    pub fn get_regex<'a>(&'a self, language: &Language, text: &'a str) -> Result<String, Error> {
+    /// Extract a regular expression written as a regex literal with arbitrary delimiters
+    fn get_delimited_regex(&self, string_format: &StringFormat, text: &str) -> Option<Result<ExtractedRegex, Error>> {
+        string_format.regex_parser?(text)
+    }
+
+    /// Extract a regular expression written as a string or regex literal
+    fn get_literal_regex(&self, language: &Language, text: &str) -> Result<ExtractedRegex, Error> {
         let string_format = self.get_string_format(language)?;
 
-        // Iterate through line and check for literal string
-        if string_format.literal_string_start.is_some()
-            && string_format.literal_string_end.is_some()
-        {
-            let str_start = string_format
-                .literal_string_start
-                .as_ref()
-                .expect("Literal string start already checked with '.is_some()'");
-            let str_end = string_format
-                .literal_string_end
-                .as_ref()
-                .expect("Literal string end already checked with '.is_some()'");
-            // Ensure text is long enough to be a valid regex
-            Ok(self.strip_string_start_end(text, str_start, str_end))
-        } else {
-            // Not a literal string, lets check for a normal string
-            let str_character = string_format.string_character.as_ref();
-            Ok(self.strip_string_start_end(text, str_character, str_character))
+        // Regex literals are delimited by slashes, with flags after the closing slash
+        if let Some(allowed_flags) = string_format.regex_literal_flags.as_ref() {
+            if text.starts_with('/') {
+                return self.parse_regex_literal(text, allowed_flags);
+            }
+        }
+        if let Some(regex) = self.get_delimited_regex(string_format, text) {
+            return regex;
+        }
+
+        Ok(ExtractedRegex::from_literal(self.get_string(string_format, text)?))
+    }
+
+    /// Read the value of a string literal
+    fn get_string(&self, string_format: &StringFormat, text: &str) -> Result<Decoded, Error> {
+        if let Some(literal_parser) = string_format.literal_parser {
+            return literal_parser(text);
+        }
+
+        // Raw strings are stripped of their start and end characters as written
+        if let (Some(str_start), Some(str_end)) = (
+            string_format.literal_string_start.as_ref(),
+            string_format.literal_string_end.as_ref(),
+        ) {
+            if str_start.iter().any(|x| text.starts_with(x.as_str())) {
+                return Ok(Decoded::raw(text, self.strip_string_start_end(text, str_start, str_end)));
+            }
+        }
+
+        // Other strings also have escaped delimiters and escape characters unescaped
+        let str_character = string_format.string_character.as_ref();
+        let body = self.strip_string_start_end(text, str_character, str_character);
+        let escape = string_format.escape_character;
+        let body = Decoded::slice(text, body);
+        let mut unescaped = body.cleared();
+        let mut chars = body.chars();
+        while let Some((c, offset)) = chars.next() {
+            let escaped = match c == escape {
+                true => chars.next_if(|(x, _)| *x == escape || str_character.iter().any(|s| s.starts_with(*x))),
+                false => None,
+            };
+            match escaped {
+                Some((x, _)) => unescaped.push_escape(x, offset),
+                None => unescaped.push(c, offset),
+            }
         }
+        Ok(unescaped)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        extract::{definition::LanguageDefinition, Language, LiteralKind, RegexExtractor},
+        parser::Flavor,
+    };
+
+    #[test]
+    fn test_regex_literal() {
+        let extractor = RegexExtractor::new();
+        let regex = extractor.get_regex(&Language::Javascript, "/a\\/[/]b/gi").unwrap();
+        assert_eq!(regex.pattern, "a\\/[/]b");
+        assert!(regex.flags.case_insensitive && regex.flags.global);
+        assert!(!regex.flags.multiline);
+
+        assert!(matches!(
+            extractor.get_regex(&Language::Javascript, "/a/q"),
+            Err(Error::InvalidFlag('q'))
+        ));
+        assert!(matches!(
+            extractor.get_regex(&Language::Javascript, "/a[/]"),
+            Err(Error::UnterminatedLiteral(_))
+        ));
+    }
+
+    #[test]
+    fn test_rust_string() {
+        let extractor = RegexExtractor::new();
+        let pattern = |text: &str| extractor.get_regex(&Language::Rust, text).unwrap().pattern;
+        assert_eq!(pattern("\"\\\\d+\\t\\\"\""), "\\d+\t\"");
+        assert_eq!(pattern("r\"\\d+\""), "\\d+");
+        assert_eq!(pattern("r#\"\"(\\w+)\"\"#"), "\"(\\w+)\"");
+        assert_eq!(pattern("r##\"a\"#b\"##"), "a\"#b");
+        assert_eq!(pattern("b\"\\x41\\u{1F600}\""), "A\u{1F600}");
+        assert_eq!(pattern("br\"\\x41\""), "\\x41");
+
+        assert!(extractor.get_regex(&Language::Rust, "r#\"a\"").is_err());
+        assert_eq!(extractor.get_regex(&Language::Rust, "r#\"a\"#").unwrap().kind, LiteralKind::RawString);
+        assert!(extractor.get_regex(&Language::Rust, "#\"a\"#").is_err());
+
+        // Patterns which are also a part of their literal may still be decoded
+        assert!(extractor.get_regex(&Language::Rust, r#""\\d+""#).unwrap().decoded);
+        assert!(!extractor.get_regex(&Language::Rust, r#"r"\d+""#).unwrap().decoded);
+    }
+
+    #[test]
+    fn test_python_string() {
+        let extractor = RegexExtractor::new();
+        let pattern = |text: &str| extractor.get_regex(&Language::Python, text).unwrap().pattern;
+        assert_eq!(pattern(r#"'\\d+\t'"#), "\\d+\t");
+        assert_eq!(pattern(r#"R"\d+\"""#), "\\d+\\\"");
+        assert_eq!(pattern(r#"rb'\w'"#), "\\w");
+        assert_eq!(pattern("r\"\"\"\n  \\d+  # digits\n\"\"\""), "\n  \\d+  # digits\n");
+        assert_eq!(pattern(r#"f"a{{2}}""#), "a{2}");
+        assert_eq!(pattern(r#"u"\x41\101\u0041""#), "AAA");
+        assert_eq!(pattern("(\"a\"  # first\n  r'\\b')"), "a\\b");
+
+        assert!(matches!(
+            extractor.get_regex(&Language::Python, r#"f"{name}\d""#),
+            Err(Error::FormatField(_))
+        ));
+        assert!(extractor.get_regex(&Language::Python, r#"x"a""#).is_err());
+    }
+
+    #[test]
+    fn test_call_regex() {
+        let extractor = RegexExtractor::new();
+        let regex = extractor
+            .get_regex(&Language::Python, "re.compile(r\"a, (b)\", re.IGNORECASE | re.X)")
+            .unwrap();
+        assert_eq!(regex.pattern, "a, (b)");
+        assert!(regex.flags.case_insensitive && regex.flags.extended);
+
+        let regex = extractor.get_regex(&Language::Python, "re.sub(\"a\", \"b\", s, flags=re.S)").unwrap();
+        assert_eq!(regex.pattern, "a");
+        assert!(regex.flags.dot_all);
+
+        let regex = extractor
+            .get_regex(&Language::Rust, "RegexBuilder::new(r#\"\"\\d\"\"#).case_insensitive(true).build()")
+            .unwrap();
+        assert_eq!(regex.pattern, "\"\\d\"");
+        assert!(regex.flags.case_insensitive);
+
+        let regex = extractor.get_regex(&Language::Javascript, "new RegExp(\"a,b\", \"gi\")").unwrap();
+        assert_eq!(regex.pattern, "a,b");
+        assert!(regex.flags.global && regex.flags.case_insensitive);
+
+        let regex = extractor.get_regex(&Language::Go, "regexp.MustCompile(`\\d+`)").unwrap();
+        assert_eq!(regex.pattern, "\\d+");
+        let regex = extractor.get_regex(&Language::Go, "regexp.MustCompile(\"\\\\d+\")").unwrap();
+        assert_eq!(regex.pattern, "\\d+");
+
+        let regex = extractor.get_regex(&Language::Rust, "print(x, \"ab\")").unwrap();
+        assert_eq!(regex.pattern, "ab");
+        assert!(extractor.get_regex(&Language::Python, "re.compile(pattern)").is_err());
+    }
+
+    #[test]
+    fn test_java_kotlin_string() {
+        let extractor = RegexExtractor::new();
+        let regex = extractor.get_regex(&Language::Java, r#""\\d+\\.\\w""#).unwrap();
+        assert_eq!(regex.pattern, r"\d+\.\w");
+        assert!(regex.decoded);
+        assert!(extractor.get_regex(&Language::Java, r#""\\d+""#).unwrap().decoded);
+        assert!(!extractor.get_regex(&Language::Java, r#""a+""#).unwrap().decoded);
+
+        let regex = extractor
+            .get_regex(&Language::Java, r#"Pattern.compile("a\\s", Pattern.CASE_INSENSITIVE | Pattern.COMMENTS)"#)
+            .unwrap();
+        assert_eq!(regex.pattern, r"a\s");
+        assert!(regex.flags.case_insensitive && regex.flags.extended);
+
+        let regex = extractor.get_regex(&Language::Kotlin, r#""""\d+$""""#).unwrap();
+        assert_eq!(regex.pattern, r"\d+$");
+        assert!(!regex.decoded);
+
+        let regex = extractor
+            .get_regex(&Language::Kotlin, r#""\\w+".toRegex(setOf(RegexOption.IGNORE_CASE))"#)
+            .unwrap();
+        assert_eq!(regex.pattern, r"\w+");
+        assert!(regex.flags.case_insensitive);
+
+        assert!(matches!(
+            extractor.get_regex(&Language::Kotlin, r#""$prefix\\d""#),
+            Err(Error::FormatField(_))
+        ));
+    }
+
+    #[test]
+    fn test_cpp_string() {
+        let extractor = RegexExtractor::new();
+        let pattern = |text: &str| extractor.get_regex(&Language::Cpp, text).unwrap().pattern;
+        assert_eq!(pattern(r#""\\d+\?""#), r"\d+?");
+        assert_eq!(pattern(r#"R"(\d+"\w)""#), r#"\d+"\w"#);
+        assert_eq!(pattern(r#"u8R"xyz(a)"b)xyz""#), r#"a)"b"#);
+        assert_eq!(pattern("L\"a\" // first\n  \"\\\\b\""), r"a\b");
+
+        let regex = extractor
+            .get_regex(&Language::Cpp, r#"std::regex(R"(\w+)", std::regex::icase)"#)
+            .unwrap();
+        assert_eq!(regex.pattern, r"\w+");
+        assert!(regex.flags.case_insensitive);
+    }
+
+    #[test]
+    fn test_delimited_regex() {
+        let extractor = RegexExtractor::new();
+        let regex = extractor.get_regex(&Language::Ruby, "%r{a{2}/b}mx").unwrap();
+        assert_eq!(regex.pattern, "a{2}/b");
+        assert!(regex.flags.dot_all && regex.flags.extended && !regex.flags.multiline);
+        assert!(matches!(
+            extractor.get_regex(&Language::Ruby, "/#{prefix}\\d/"),
+            Err(Error::FormatField(_))
+        ));
+
+        let regex = extractor.get_regex(&Language::Perl, "s{(\\w+)} {<$1>}gi").unwrap();
+        assert_eq!(regex.pattern, "(\\w+)");
+        assert_eq!(regex.replacement, Some(String::from("<$1>")));
+        assert!(regex.flags.global && regex.flags.case_insensitive);
+        let regex = extractor.get_regex(&Language::Perl, "m(a(b)\\)c)").unwrap();
+        assert_eq!(regex.pattern, "a(b)\\)c");
+        let regex = extractor.get_regex(&Language::Perl, "s/a\\/b/c/").unwrap();
+        assert_eq!(regex.pattern, "a\\/b");
+        assert_eq!(regex.replacement, Some(String::from("c")));
+        assert!(matches!(extractor.get_regex(&Language::Perl, "qr/a/z"), Err(Error::InvalidFlag('z'))));
+
+        let pattern = |language: Language, text: &str| extractor.get_regex(&language, text).unwrap().pattern;
+        assert_eq!(pattern(Language::Ruby, "\"café\""), "café");
+        assert_eq!(pattern(Language::Ruby, r#""\\d+\x41\.""#), r"\d+A.");
+        assert_eq!(pattern(Language::Ruby, r"'\d+\\\''"), r"\d+\'");
+        assert!(extractor.get_regex(&Language::Ruby, "\"#{name}\"").is_err());
+        assert_eq!(pattern(Language::Perl, r"'\d+\''"), r"\d+'");
+        assert_eq!(pattern(Language::Perl, r#""\\w\x{263A}""#), "\\w\u{263A}");
+        assert!(extractor.get_regex(&Language::Perl, "\"$name\\d\"").is_err());
+
+        let regex = extractor.get_regex(&Language::Php, "preg_replace('#\\\\d+#iu', '[$0]', $s)").unwrap();
+        assert_eq!(regex.pattern, "\\d+");
+        assert_eq!(regex.replacement, Some(String::from("[$0]")));
+        assert!(regex.flags.case_insensitive && regex.flags.unicode);
+        assert!(extractor.get_regex(&Language::Php, "\"/a$name/\"").is_err());
+
+        let regex = extractor.get_regex(&Language::Vim, ":'<,'>s#\\<a\\>#b#gI").unwrap();
+        assert_eq!(regex.pattern, "\\<a\\>");
+        assert_eq!(regex.replacement, Some(String::from("b")));
+        assert!(regex.flags.global && !regex.flags.case_insensitive);
+        let regex = extractor.get_regex(&Language::Vim, "g!/^\\s*$/d").unwrap();
+        assert_eq!(regex.pattern, "^\\s*$");
+        assert_eq!(extractor.get_regex(&Language::Vim, "'it''s \\d'").unwrap().pattern, "it's \\d");
+        assert_eq!(extractor.get_regex(&Language::Vim, "\"\\\\d\\.\"").unwrap().pattern, "\\d.");
+        let regex = extractor.get_regex(&Language::Vim, "substitute(line, '\\s\\+$', '', 'g')").unwrap();
+        assert_eq!(regex.pattern, "\\s\\+$");
+        assert!(regex.flags.global);
+        // The function only takes `g`, unlike JavaScript's RegExp
+        assert!(matches!(
+            extractor.get_regex(&Language::Vim, "substitute(line, 'a', 'b', 'i')"),
+            Err(Error::InvalidFlag('i'))
+        ));
+        assert!(extractor.get_regex(&Language::Javascript, "new RegExp('a', 'gi')").unwrap().flags.case_insensitive);
+        let regex = extractor.get_regex(&Language::Lua, "vim.regex('\\v<\\d+>')").unwrap();
+        assert_eq!(regex.flavor, Some(Flavor::Vim));
+    }
+
+    #[test]
+    fn test_csharp_swift_typescript_string() {
+        let extractor = RegexExtractor::new();
+        let pattern = |language: Language, text: &str| extractor.get_regex(&language, text).unwrap().pattern;
+        assert_eq!(pattern(Language::Csharp, r#"@"\d+""\w""#), r#"\d+"\w"#);
+        assert_eq!(pattern(Language::Csharp, r#""\\d{2}\t""#), "\\d{2}\t");
+        assert_eq!(pattern(Language::Csharp, r#"$@"\d{{2}}""#), r"\d{2}");
+        assert!(matches!(
+            extractor.get_regex(&Language::Csharp, r#"$@"{prefix}\d""#),
+            Err(Error::FormatField(_))
+        ));
+        let regex = extractor
+            .get_regex(&Language::Csharp, r#"Regex.Replace(s, @"a,\\", "b", RegexOptions.IgnoreCase)"#)
+            .unwrap();
+        assert_eq!(regex.pattern, r"a,\\");
+        assert_eq!(regex.replacement, Some(String::from("b")));
+        assert!(regex.flags.case_insensitive);
+
+        assert_eq!(pattern(Language::Swift, r##"#"\d+"\#t"#"##), "\\d+\"\t");
+        assert_eq!(pattern(Language::Swift, "\"\"\"\n    a\\\\d\n    \"\"\""), "a\\d");
+        assert_eq!(pattern(Language::Swift, r"/\d+\/x/"), r"\d+\/x");
+        let regex = extractor.get_regex(&Language::Swift, "#/\n  a+  # many\n/#").unwrap();
+        assert!(regex.flags.extended);
+        assert!(matches!(
+            extractor.get_regex(&Language::Swift, r#""\(name)\d""#),
+            Err(Error::FormatField(_))
+        ));
+        let regex = extractor
+            .get_regex(&Language::Swift, r#"NSRegularExpression(pattern: "a\\d", options: [.caseInsensitive])"#)
+            .unwrap();
+        assert_eq!(regex.pattern, r"a\d");
+        assert!(regex.flags.case_insensitive);
+
+        assert_eq!(pattern(Language::Typescript, r"`\\d+$x`"), r"\d+$x");
+        assert_eq!(pattern(Language::Typescript, r"/a\/b/"), r"a\/b");
+        assert!(matches!(
+            extractor.get_regex(&Language::Typescript, r"`${prefix}\\d`"),
+            Err(Error::FormatField(_))
+        ));
+    }
+
+    #[test]
+    fn test_lua_string() {
+        let extractor = RegexExtractor::new();
+        let pattern = |text: &str| extractor.get_regex(&Language::Lua, text).unwrap().pattern;
+        assert_eq!(pattern("[[%d+\\n]]"), "%d+\\n");
+        assert_eq!(pattern("[==[\n]]%]=]]==]"), "]]%]=]");
+        assert_eq!(pattern(r#""%a\65\z
+              \t""#), "%aA\t");
+        let regex = extractor.get_regex(&Language::Lua, r#"s:gsub("%s+$", "")"#).unwrap();
+        assert_eq!(regex.pattern, "%s+$");
+        assert_eq!(regex.replacement, Some(String::new()));
+        assert_eq!(pattern("string.match(line, [[^(%w+)=(.-)$]])"), "^(%w+)=(.-)$");
+        let regex = extractor.get_regex(&Language::Lua, r"vim.fn.matchstr(line, '\v\d+')").unwrap();
+        assert_eq!(regex.flavor, Some(Flavor::Vim));
+
+        // Long strings are read as written, even with a backslash
+        let kind = |text: &str| extractor.get_regex(&Language::Lua, text).unwrap().kind;
+        assert_eq!(kind(r"[[a\d]]"), LiteralKind::RawString);
+        assert_eq!(kind(r"[==[a\d]==]"), LiteralKind::RawString);
+        assert_eq!(kind(r#""a\\d""#), LiteralKind::String);
+    }
+
+    #[test]
+    fn test_shell_regex() {
+        let extractor = RegexExtractor::new();
+        let regex = |language: Language, text: &str| extractor.get_regex(&language, text).unwrap();
+        let grep = regex(Language::Shell, r#"grep -Ei -- "^a+\$" file"#);
+        assert_eq!((grep.pattern.as_str(), grep.flavor), ("^a+$", Some(Flavor::Ere)));
+        assert!(grep.flags.case_insensitive);
+        assert_eq!(regex(Language::Shell, "grep -e 'x\\{2\\}' -n f").flavor, Some(Flavor::Bre));
+        assert_eq!(regex(Language::Shell, "fgrep 'a.b'").pattern, "a\\.b");
+        let sed = regex(Language::Shell, r"sed -n 's/\(a\)\{2\}/x/2g' f | sort");
+        assert_eq!(sed.pattern, r"\(a\)\{2\}");
+        assert_eq!(sed.replacement, Some(String::from("x")));
+        assert!(sed.flags.global);
+        assert_eq!(regex(Language::Shell, "sed -E -e '/^#/d'").flavor, Some(Flavor::Ere));
+        assert_eq!(regex(Language::Shell, "awk -F: '$1 ~ /^r/ { print }' /etc/passwd").pattern, "^r");
+        assert_eq!(regex(Language::Shell, "[[ $x =~ ^[0-9]+$ ]]").pattern, "^[0-9]+$");
+        assert_eq!(regex(Language::Shell, "LC_ALL=C expr \"$x\" : '[a-z]*'").pattern, "[a-z]*");
+        assert_eq!(regex(Language::Shell, "$'a\\tb'").pattern, "a\tb");
+        assert!(matches!(
+            extractor.get_regex(&Language::Shell, r#"grep "$pattern" file"#),
+            Err(Error::FormatField(_))
+        ));
+
+        assert_eq!(regex(Language::Sed, "1,5s|a/b|c|I").pattern, "a/b");
+        assert_eq!(regex(Language::Awk, "x = 4 / 2; gsub(/a+/, \"b\")").pattern, "a+");
+        assert_eq!(regex(Language::Awk, r#""a\\.b""#).pattern, r"a\.b");
+    }
+
+    #[test]
+    fn test_glob_regex() {
+        let extractor = RegexExtractor::new();
+        let regex = |language: Language, text: &str| extractor.get_regex(&language, text).unwrap();
+        let gitignore = regex(Language::Gitignore, "/build/  ");
+        assert_eq!((gitignore.pattern.as_str(), gitignore.flavor), ("/build/", Some(Flavor::Gitignore)));
+        assert_eq!(regex(Language::Gitignore, "a\\  ").pattern, "a\\ ");
+        assert!(extractor.get_regex(&Language::Gitignore, "# build output").is_err());
+        assert_eq!(regex(Language::Codeowners, "docs/my\\ file.md @org/docs @a").pattern, "docs/my\\ file.md");
+
+        let glob = regex(Language::Python, "glob.glob('**/*.py', recursive=True)");
+        assert_eq!((glob.pattern.as_str(), glob.flavor), ("**/*.py", Some(Flavor::Glob)));
+        assert_eq!(regex(Language::Python, "fnmatch.fnmatch(name, '*.txt')").pattern, "*.txt");
+        assert_eq!(regex(Language::Go, "filepath.Match(\"*.go\", name)").flavor, Some(Flavor::Glob));
+    }
+
+    #[test]
+    fn test_sql_regex() {
+        let extractor = RegexExtractor::new();
+        let regex = |text: &str| extractor.get_regex(&Language::Sql, text).unwrap();
+        let like = regex("name NOT LIKE 'it''s%' -- 'comment'");
+        assert_eq!((like.pattern.as_str(), like.flavor), ("it's%", Some(Flavor::Like)));
+        assert_eq!(regex("\"a~b\" ilike E'\\\\%'").pattern, "\\%");
+        assert!(regex("\"a~b\" ilike E'\\\\%'").flags.case_insensitive);
+        assert_eq!(regex("code LIKE '10!%!_%' ESCAPE '!'").pattern, "10\\%\\_%");
+        assert_eq!(regex("code SIMILAR  TO '(a|b)%'").flavor, Some(Flavor::SimilarTo));
+        let posix = regex("email !~* $$^[a-z]+@$$");
+        assert_eq!((posix.pattern.as_str(), posix.flavor), ("^[a-z]+@", Some(Flavor::Pcre)));
+        assert!(posix.flags.case_insensitive);
+        assert_eq!(regex("name REGEXP BINARY '^a'").flavor, Some(Flavor::Java));
+
+        let replace = regex("REGEXP_REPLACE(name, '\\s+', ' ', 'gi')");
+        assert_eq!(replace.pattern, "\\s+");
+        assert_eq!(replace.replacement, Some(String::from(" ")));
+        assert!(replace.flags.global && replace.flags.case_insensitive);
+        assert_eq!(regex("regexp_like(name, '^a', 'c')").pattern, "^a");
+        assert!(extractor.get_regex(&Language::Sql, "code LIKE 'a' ESCAPE '!!'").is_err());
+    }
+
+    #[test]
+    fn test_elixir_erlang_haskell_regex() {
+        let extractor = RegexExtractor::new();
+        let regex = |language: Language, text: &str| extractor.get_regex(&language, text).unwrap();
+        let sigil = regex(Language::Elixir, "Regex.match?(~r/a\\/b/iu, \"~r/no/\")");
+        assert_eq!(sigil.pattern, "a\\/b");
+        assert!(sigil.flags.case_insensitive && sigil.flags.unicode);
+        assert_eq!(regex(Language::Elixir, "~r{a{2}}s").pattern, "a{2}");
+        assert_eq!(regex(Language::Elixir, "~R|#{a}|").pattern, "#{a}");
+        assert_eq!(regex(Language::Elixir, "~r\"\"\"\n  a+ # b\n  \"\"\"x").pattern, "  a+ # b\n");
+        assert_eq!(regex(Language::Elixir, "Regex.compile!(\"\\\\d\\s\\.\", [:caseless])").pattern, "\\d .");
+        assert!(matches!(
+            extractor.get_regex(&Language::Elixir, "~r/#{name}/"),
+            Err(Error::FormatField(_))
+        ));
+        assert!(matches!(extractor.get_regex(&Language::Elixir, "~r/a/q"), Err(Error::InvalidFlag('q'))));
+
+        let run = regex(Language::Erlang, "re:run(Subject, \"^\\\\w+\\^I\\x{41}\", [caseless, dotall])");
+        assert_eq!(run.pattern, "^\\w+\tA");
+        assert!(run.flags.case_insensitive && run.flags.dot_all);
+        let replace = regex(Language::Erlang, "re:replace(S, \"a\", \"b\", [global])");
+        assert_eq!(replace.replacement, Some(String::from("b")));
+        assert!(replace.flags.global);
+
+        assert_eq!(regex(Language::Haskell, "s =~ [re|^\\d+|x|]").pattern, "^\\d+|x");
+        assert_eq!(regex(Language::Haskell, "\"\\\\d\\65\\&1\\x42\\ESC\\SOH\\   \\.\"").pattern, "\\dA1B\x1B\x01.");
+        assert!(extractor.get_regex(&Language::Haskell, "\"\\d\"").is_err());
+    }
+
+    #[test]
+    fn test_config_string() {
+        let mut extractor = RegexExtractor::new();
+        let pattern = |extractor: &RegexExtractor, language: Language, text: &str| {
+            extractor.get_regex(&language, text).unwrap().pattern
+        };
+        assert_eq!(pattern(&extractor, Language::Yaml, r"'^(a|b)''s\d+$'"), r"^(a|b)'s\d+$");
+        assert_eq!(pattern(&extractor, Language::Yaml, r#""\\d+\t\x41""#), "\\d+\tA");
+        assert_eq!(pattern(&extractor, Language::Yaml, "'a\n   b\n\n   c'"), "a b\nc");
+        assert_eq!(pattern(&extractor, Language::Yaml, "\"a\\\n   b\""), "ab");
+        assert_eq!(pattern(&extractor, Language::Yaml, "^a+ b$ # comment"), "^a+ b$");
+        assert_eq!(pattern(&extractor, Language::Yaml, "|-\n    ^a\n      b$\n"), "^a\n  b$");
+        assert_eq!(pattern(&extractor, Language::Yaml, ">\n  a\n  b\n\n  c\n"), "a b\nc\n");
+        assert!(extractor.get_regex(&Language::Yaml, r#""\d""#).is_err());
+        let kind = |text: &str| extractor.get_regex(&Language::Yaml, text).unwrap().kind;
+        assert_eq!(kind("'^a+$'"), LiteralKind::String);
+        assert_eq!(kind("\"^a+$\""), LiteralKind::String);
+        assert_eq!(kind("|\n  ^a+$\n"), LiteralKind::RawString);
+
+        assert_eq!(pattern(&extractor, Language::Toml, r#"'\d+\.'"#), r"\d+\.");
+        assert_eq!(pattern(&extractor, Language::Toml, "'''\n\\d'+'''"), "\\d'+");
+        assert_eq!(pattern(&extractor, Language::Toml, r#""\\d\u0041""#), "\\dA");
+        assert!(extractor.get_regex(&Language::Toml, r#""\d""#).is_err());
+
+        assert_eq!(pattern(&extractor, Language::Json, r#""^\\w+\/\ud83d\ude00$""#), "^\\w+/\u{1F600}$");
+        assert!(extractor.get_regex(&Language::Json, r#""\ud83d""#).is_err());
+        assert!(extractor.get_regex(&Language::Json, r#""\s""#).is_err());
+
+        let section = extractor.get_regex(&Language::Editorconfig, "[*.{js,py}]").unwrap();
+        assert_eq!((section.pattern.as_str(), section.flavor), ("*.{js,py}", Some(Flavor::Glob)));
+        assert!(extractor.get_regex(&Language::Editorconfig, "indent_size = 4").is_err());
+
+        assert_eq!(extractor.flavor(&Language::Yaml), Flavor::Pcre);
+        extractor.set_config_flavor(Flavor::Re2);
+        assert_eq!(extractor.flavor(&Language::Json), Flavor::Re2);
+        assert_eq!(extractor.flavor(&Language::Python), Flavor::Pcre);
+    }
+
+    #[test]
+    fn test_detect_language() {
+        let extractor = RegexExtractor::new();
+        assert_eq!(extractor.detect_language("", Some("python"), None), Language::Python);
+        assert_eq!(
+            extractor.detect_language("index.html", Some("html"), Some("javascript")),
+            Language::Javascript
+        );
+        assert_eq!(extractor.detect_language("stubs.pyi", Some(""), None), Language::Python);
+        assert_eq!(
+            extractor.detect_language("Makefile", Some("make"), None),
+            Language::Unknown(String::from("Makefile"))
+        );
+        assert_eq!(extractor.detect_language("a.h", Some("cs"), None), Language::Csharp);
+        assert_eq!(extractor.detect_language(".github/CODEOWNERS", None, None), Language::Codeowners);
+        assert_eq!(extractor.detect_language("web/.gitignore", None, None), Language::Gitignore);
+        assert_eq!(extractor.detect_language("mix.exs", None, None), Language::Elixir);
+        assert_eq!(extractor.detect_language(".gitlab-ci.yml", None, None), Language::Yaml);
+        assert_eq!(extractor.detect_language(".editorconfig", None, None), Language::Editorconfig);
+        assert_eq!(extractor.detect_language("Main.hs", Some("haskell"), None), Language::Haskell);
+    }
+
+    #[test]
+    fn test_define_language() {
+        let mut extractor = RegexExtractor::new();
+        let mut definition = LanguageDefinition::new("nix");
+        definition.extensions = vec![String::from(".nix")];
+        definition.delimiters = vec![String::from("\""), String::from("''")];
+        definition.flavor = Some(String::from("posix"));
+        assert!(matches!(extractor.define_language(&definition), Err(Error::InvalidDefinition(..))));
+
+        definition.flavor = Some(String::from("re2"));
+        let language = extractor.define_language(&definition).unwrap();
+        assert_eq!(extractor.detect_language("default.nix", None, None), language);
+        assert_eq!(extractor.flavor(&language), Flavor::Re2);
+        assert_eq!(extractor.get_regex(&language, r#""a\"\\d""#).unwrap().pattern, r#"a"\d"#);
+        assert_eq!(extractor.get_regex(&language, "''\\d+''").unwrap().pattern, "\\d+");
+
+        definition.raw_prefixes = vec![String::from("r\"")];
+        assert!(extractor.define_language(&definition).is_err());
+        definition.raw_suffixes = vec![String::from("\"")];
+        definition.escape = Some(String::from("^"));
+        let language = extractor.define_language(&definition).unwrap();
+        assert_eq!(extractor.get_regex(&language, r#"r"a\"#).unwrap().pattern, r"a\");
+        assert_eq!(extractor.get_regex(&language, r#""^"\d""#).unwrap().pattern, r#""\d"#);
+
+        // The definition of the README, on text which is not ASCII
+        let mut definition = LanguageDefinition::new("nix");
+        definition.extensions = vec![String::from("nix")];
+        definition.delimiters = vec![String::from("\""), String::from("''")];
+        definition.escape = Some(String::from("\\"));
+        definition.flavor = Some(String::from("PCRE"));
+        let language = extractor.define_language(&definition).unwrap();
+        let regex = extractor.get_regex(&language, "\"aé\"").unwrap();
+        assert_eq!((regex.pattern.as_str(), regex.offsets), ("aé", vec![1, 2, 4]));
+        assert_eq!(extractor.get_regex(&language, "é").unwrap().pattern, "é");
+        assert_eq!(extractor.get_regex(&language, "''é\\d''").unwrap().pattern, "é\\d");
+        assert_eq!(extractor.get_regex(&language, "\"é\\\"\"").unwrap().pattern, "é\"");
+    }
+
+    #[test]
+    fn test_source_offsets() {
+        let extractor = RegexExtractor::new();
+        let regex = extractor.get_regex(&Language::Python, "re.compile(\"a\\\\d(b)\", re.I)").unwrap();
+        assert_eq!(regex.pattern, "a\\d(b)");
+        assert_eq!(regex.kind, LiteralKind::String);
+        assert_eq!(regex.offsets, vec![12, 13, 15, 16, 17, 18, 19]);
+        assert_eq!(regex.source_range(3..6), Some(16..19));
+
+        let regex = extractor.get_regex(&Language::Perl, "s{a+}{b}g").unwrap();
+        assert_eq!(regex.kind, LiteralKind::RegexLiteral);
+        assert_eq!(regex.offsets, vec![2, 3, 4]);
+
+        let regex = extractor.get_regex(&Language::Javascript, "/a\\/b/g").unwrap();
+        assert_eq!(regex.kind, LiteralKind::RegexLiteral);
+        assert_eq!(regex.offsets, vec![1, 2, 3, 4, 5]);
+
+        let regex = extractor.get_regex(&Language::Gitignore, "build/ ").unwrap();
+        assert_eq!(regex.kind, LiteralKind::Line);
+        assert_eq!(regex.source_range(0..6), Some(0..6));
+
+        // Patterns which also appear in the delimiters or escapes are found where they are
+        // read from
+        let offsets = |language: Language, text: &str| extractor.get_regex(&language, text).unwrap().offsets;
+        assert_eq!(offsets(Language::Rust, "r\"r\""), vec![2, 3]);
+        assert_eq!(offsets(Language::Perl, "qr/q/"), vec![3, 4]);
+        assert_eq!(offsets(Language::Elixir, "~r/r/"), vec![3, 4]);
+        assert_eq!(offsets(Language::Java, "\"\\\\d\""), vec![1, 3, 4]);
+        assert_eq!(offsets(Language::Python, "re.compile(\"a\" \"b\")"), vec![12, 16, 17]);
+        assert_eq!(offsets(Language::Shell, "grep -e 'a'\\''b' file"), vec![9, 11, 14, 15]);
+        assert_eq!(offsets(Language::Sql, "name LIKE 'a''%'"), vec![11, 12, 14, 15]);
+        assert_eq!(offsets(Language::Yaml, "'a\n  b'"), vec![1, 2, 5, 6]);
     }
 }
@@ -0,0 +1,499 @@
+use crate::{extract::Language, flags::Flags, parser::Flavor};
+
+/// How the flags argument of a regular expression call is written
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum FlagSyntax {
+    /// Constants combined with `|`, such as `re.IGNORECASE | re.X`
+    Constants,
+    /// A string of single letter flags, such as `"gi"`, from those the call accepts
+    Letters(&'static str),
+    /// Builder methods called on the result, such as `.case_insensitive(true)`
+    Builder,
+}
+
+/// A function or macro which compiles a regular expression
+#[derive(Debug)]
+pub struct RegexCall {
+    /// Callee as written, matched against the end of the called path
+    pub name: &'static str,
+    /// Position of the pattern argument
+    pub pattern: usize,
+    /// Position of the flags argument, if the call takes one
+    pub flags: Option<usize>,
+    pub flag_syntax: FlagSyntax,
+    /// Position of the replacement argument of a substitution
+    pub replacement: Option<usize>,
+    /// Syntax of the pattern, when it is not the syntax of the calling language
+    pub flavor: Option<Flavor>,
+}
+
+impl RegexCall {
+    const fn new(name: &'static str, pattern: usize, flags: Option<usize>, flag_syntax: FlagSyntax) -> Self {
+        RegexCall { name, pattern, flags, flag_syntax, replacement: None, flavor: None }
+    }
+
+    const fn with_replacement(mut self, replacement: usize) -> Self {
+        self.replacement = Some(replacement);
+        self
+    }
+
+    const fn with_flavor(mut self, flavor: Flavor) -> Self {
+        self.flavor = Some(flavor);
+        self
+    }
+
+    /// Check if a called path refers to this function, ignoring any module prefix
+    fn matches(&self, callee: &str) -> bool {
+        match callee.strip_suffix(self.name) {
+            Some(prefix) => prefix.is_empty() || prefix.ends_with(['.', ':']),
+            None => false,
+        }
+    }
+}
+
+const PYTHON_CALLS: &[RegexCall] = &[
+    RegexCall::new("re.compile", 0, Some(1), FlagSyntax::Constants),
+    RegexCall::new("re.search", 0, Some(2), FlagSyntax::Constants),
+    RegexCall::new("re.match", 0, Some(2), FlagSyntax::Constants),
+    RegexCall::new("re.fullmatch", 0, Some(2), FlagSyntax::Constants),
+    RegexCall::new("re.findall", 0, Some(2), FlagSyntax::Constants),
+    RegexCall::new("re.finditer", 0, Some(2), FlagSyntax::Constants),
+    RegexCall::new("re.split", 0, Some(3), FlagSyntax::Constants),
+    RegexCall::new("re.sub", 0, Some(4), FlagSyntax::Constants).with_replacement(1),
+    RegexCall::new("re.subn", 0, Some(4), FlagSyntax::Constants).with_replacement(1),
+    // `glob` covers both `glob.glob()` and `Path.glob()`
+    RegexCall::new("glob", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+    RegexCall::new("iglob", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+    RegexCall::new("rglob", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+    RegexCall::new("fnmatch.fnmatch", 1, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+    RegexCall::new("fnmatch.fnmatchcase", 1, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+    RegexCall::new("fnmatch.filter", 1, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+];
+
+const RUST_CALLS: &[RegexCall] = &[
+    RegexCall::new("Regex::new", 0, None, FlagSyntax::Builder),
+    RegexCall::new("RegexBuilder::new", 0, None, FlagSyntax::Builder),
+    RegexCall::new("regex!", 0, None, FlagSyntax::Builder),
+    RegexCall::new("glob", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+    RegexCall::new("Pattern::new", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+];
+
+const JAVASCRIPT_CALLS: &[RegexCall] = &[
+    RegexCall::new("new RegExp", 0, Some(1), FlagSyntax::Letters("dgimsuvy")),
+    RegexCall::new("RegExp", 0, Some(1), FlagSyntax::Letters("dgimsuvy")),
+    RegexCall::new("minimatch", 1, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+];
+
+const GO_CALLS: &[RegexCall] = &[
+    RegexCall::new("regexp.Compile", 0, None, FlagSyntax::Constants),
+    RegexCall::new("regexp.MustCompile", 0, None, FlagSyntax::Constants),
+    RegexCall::new("regexp.CompilePOSIX", 0, None, FlagSyntax::Constants),
+    RegexCall::new("regexp.MustCompilePOSIX", 0, None, FlagSyntax::Constants),
+    RegexCall::new("regexp.MatchString", 0, None, FlagSyntax::Constants),
+    RegexCall::new("filepath.Glob", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+    RegexCall::new("filepath.Match", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+    RegexCall::new("path.Match", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+];
+
+const JAVA_CALLS: &[RegexCall] = &[
+    RegexCall::new("Pattern.compile", 0, Some(1), FlagSyntax::Constants),
+    RegexCall::new("Pattern.matches", 0, None, FlagSyntax::Constants),
+];
+
+const CPP_CALLS: &[RegexCall] = &[
+    RegexCall::new("std::regex", 0, Some(1), FlagSyntax::Constants),
+    RegexCall::new("std::wregex", 0, Some(1), FlagSyntax::Constants),
+    RegexCall::new("boost::regex", 0, Some(1), FlagSyntax::Constants),
+    RegexCall::new("glob", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+    RegexCall::new("fnmatch", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+];
+
+const RUBY_CALLS: &[RegexCall] = &[
+    RegexCall::new("Regexp.new", 0, None, FlagSyntax::Constants),
+    RegexCall::new("Dir.glob", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+    RegexCall::new("File.fnmatch", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+];
+
+/// PHP, where flags are written after the pattern's closing delimiter
+const PHP_CALLS: &[RegexCall] = &[
+    RegexCall::new("preg_match", 0, None, FlagSyntax::Constants),
+    RegexCall::new("preg_match_all", 0, None, FlagSyntax::Constants),
+    RegexCall::new("preg_replace", 0, None, FlagSyntax::Constants).with_replacement(1),
+    RegexCall::new("preg_replace_callback", 0, None, FlagSyntax::Constants),
+    RegexCall::new("preg_split", 0, None, FlagSyntax::Constants),
+    RegexCall::new("preg_grep", 0, None, FlagSyntax::Constants),
+    RegexCall::new("glob", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+    RegexCall::new("fnmatch", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Glob),
+];
+
+const CSHARP_CALLS: &[RegexCall] = &[
+    RegexCall::new("new Regex", 0, Some(1), FlagSyntax::Constants),
+    RegexCall::new("Regex.IsMatch", 1, Some(2), FlagSyntax::Constants),
+    RegexCall::new("Regex.Match", 1, Some(2), FlagSyntax::Constants),
+    RegexCall::new("Regex.Matches", 1, Some(2), FlagSyntax::Constants),
+    RegexCall::new("Regex.Split", 1, Some(2), FlagSyntax::Constants),
+    RegexCall::new("Regex.Replace", 1, Some(3), FlagSyntax::Constants).with_replacement(2),
+];
+
+const SWIFT_CALLS: &[RegexCall] = &[RegexCall::new("NSRegularExpression", 0, Some(1), FlagSyntax::Constants)];
+
+const KOTLIN_CALLS: &[RegexCall] = &[
+    RegexCall::new("Regex", 0, Some(1), FlagSyntax::Constants),
+    RegexCall::new("toRegex", 0, Some(1), FlagSyntax::Constants),
+];
+
+/// Lua, including the `gmatch` and `gsub` methods called on a string such as
+/// `s:gsub(...)`, and Vim's regular expressions used from Neovim's Lua API
+const LUA_CALLS: &[RegexCall] = &[
+    RegexCall::new("string.match", 1, None, FlagSyntax::Constants),
+    RegexCall::new("string.find", 1, None, FlagSyntax::Constants),
+    RegexCall::new("string.gmatch", 1, None, FlagSyntax::Constants),
+    RegexCall::new("string.gsub", 1, None, FlagSyntax::Constants).with_replacement(2),
+    RegexCall::new("gmatch", 0, None, FlagSyntax::Constants),
+    RegexCall::new("gsub", 0, None, FlagSyntax::Constants).with_replacement(1),
+    RegexCall::new("vim.regex", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Vim),
+    RegexCall::new("vim.fn.match", 1, None, FlagSyntax::Constants).with_flavor(Flavor::Vim),
+    RegexCall::new("vim.fn.search", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Vim),
+];
+
+/// Vimscript functions, which Lua also calls through `vim.fn`
+const VIM_CALLS: &[RegexCall] = &[
+    RegexCall::new("matchadd", 1, None, FlagSyntax::Constants).with_flavor(Flavor::Vim),
+    RegexCall::new("matchstr", 1, None, FlagSyntax::Constants).with_flavor(Flavor::Vim),
+    RegexCall::new("matchend", 1, None, FlagSyntax::Constants).with_flavor(Flavor::Vim),
+    RegexCall::new("matchlist", 1, None, FlagSyntax::Constants).with_flavor(Flavor::Vim),
+    RegexCall::new("matchstrpos", 1, None, FlagSyntax::Constants).with_flavor(Flavor::Vim),
+    RegexCall::new("searchpos", 0, None, FlagSyntax::Constants).with_flavor(Flavor::Vim),
+    RegexCall::new("substitute", 1, Some(3), FlagSyntax::Letters("g")).with_replacement(2).with_flavor(Flavor::Vim),
+];
+
+/// Elixir, whose sigils are read as literals
+const ELIXIR_CALLS: &[RegexCall] = &[
+    RegexCall::new("Regex.compile", 0, Some(1), FlagSyntax::Constants),
+    RegexCall::new("Regex.compile!", 0, Some(1), FlagSyntax::Constants),
+];
+
+/// Erlang, whose options are atoms such as `[caseless, global]`
+const ERLANG_CALLS: &[RegexCall] = &[
+    RegexCall::new("re:run", 1, Some(2), FlagSyntax::Constants),
+    RegexCall::new("re:compile", 0, Some(1), FlagSyntax::Constants),
+    RegexCall::new("re:replace", 1, Some(3), FlagSyntax::Constants).with_replacement(2),
+    RegexCall::new("re:split", 1, Some(2), FlagSyntax::Constants),
+];
+
+/// Known regular expression calls of each language, where a language may have several
+/// entries
+pub const REGEX_CALLS: &[(Language, &[RegexCall])] = &[
+    (Language::Python, PYTHON_CALLS),
+    (Language::Rust, RUST_CALLS),
+    (Language::Javascript, JAVASCRIPT_CALLS),
+    (Language::Typescript, JAVASCRIPT_CALLS),
+    (Language::Go, GO_CALLS),
+    (Language::Java, JAVA_CALLS),
+    (Language::Cpp, CPP_CALLS),
+    (Language::Ruby, RUBY_CALLS),
+    (Language::Php, PHP_CALLS),
+    (Language::Csharp, CSHARP_CALLS),
+    (Language::Swift, SWIFT_CALLS),
+    (Language::Kotlin, KOTLIN_CALLS),
+    (Language::Lua, LUA_CALLS),
+    (Language::Lua, VIM_CALLS),
+    (Language::Vim, VIM_CALLS),
+    (Language::Elixir, ELIXIR_CALLS),
+    (Language::Erlang, ERLANG_CALLS),
+];
+
+/// A call expression split into its callee, arguments and any chained method calls
+#[derive(Debug)]
+pub struct Call<'t> {
+    pub callee: String,
+    pub arguments: Vec<&'t str>,
+    pub methods: Vec<(&'t str, Vec<&'t str>)>,
+    /// Text following the call and its chained methods
+    pub rest: &'t str,
+}
+
+impl<'t> Call<'t> {
+    /// Find the known regular expression call of a language this is, if any
+    pub fn regex_call(&self, language: &Language) -> Option<&'static RegexCall> {
+        REGEX_CALLS
+            .iter()
+            .filter(|(x, _)| x == language)
+            .flat_map(|(_, calls)| calls.iter())
+            .find(|x| x.matches(&self.callee))
+    }
+
+    /// Positional argument, or the keyword argument of the same name, written as
+    /// `name=value` in Python or with a `name:` label in Swift and C#
+    pub fn argument(&self, position: usize, keyword: &str) -> Option<&'t str> {
+        let keyword_argument = self.arguments.iter().find_map(|x| {
+            x.split_once('=')
+                .filter(|(name, value)| name.trim() == keyword && !value.starts_with('='))
+                .map(|(_, value)| value.trim())
+                .or_else(|| label(x).filter(|(name, _)| *name == keyword).map(|(_, value)| value))
+        });
+        keyword_argument.or_else(|| {
+            self.arguments
+                .iter()
+                .filter(|x| !is_keyword_argument(x))
+                .nth(position)
+                .map(|x| label(x).map_or(*x, |(_, value)| value))
+        })
+    }
+}
+
+/// Split a `name: value` argument into its label and value
+fn label(argument: &str) -> Option<(&str, &str)> {
+    let (name, value) = argument.split_once(':')?;
+    let is_name = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    (is_name && !value.starts_with(':')).then(|| (name, value.trim()))
+}
+
+fn is_keyword_argument(argument: &str) -> bool {
+    match argument.split_once('=') {
+        Some((name, value)) => {
+            !name.is_empty()
+                && name.trim().chars().all(|c| c.is_alphanumeric() || c == '_')
+                && !value.starts_with('=')
+        }
+        None => false,
+    }
+}
+
+/// Byte length of the string or regex literal at the start of the text, if there is one
+///
+/// This only needs to be accurate enough to skip over commas and brackets within the
+/// literal, so escapes are skipped and raw strings are matched by their delimiters.
+pub fn literal_len(text: &str, regex_allowed: bool) -> Option<usize> {
+    let prefix_len = text
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '@' || c == '$'))
+        .unwrap_or(text.len());
+    let prefix = &text[..prefix_len];
+    let rest = &text[prefix_len..];
+
+    // Rust raw strings and Swift extended delimiters close with as many hashes as they
+    // open with
+    if (prefix.is_empty() || prefix.ends_with('r')) && rest.starts_with('#') {
+        let hashes = rest.len() - rest.trim_start_matches('#').len();
+        let quote = rest[hashes..].chars().next().filter(|c| ['"', '/'].contains(c))?;
+        let closing = format!("{}{}", quote, "#".repeat(hashes));
+        let body = &rest[hashes + 1..];
+        return body.find(&closing).map(|idx| prefix_len + hashes + 1 + idx + closing.len());
+    }
+
+    let quote = if rest.starts_with("\"\"\"") || rest.starts_with("'''") {
+        &rest[..3]
+    } else if rest.starts_with(['"', '\'', '`']) || (regex_allowed && prefix.is_empty() && rest.starts_with('/')) {
+        &rest[..1]
+    } else {
+        return None;
+    };
+    // C# verbatim strings escape quotes by doubling them rather than with backslashes
+    let verbatim = prefix.contains('@');
+    let mut chars = rest.char_indices().skip(quote.chars().count());
+    while let Some((idx, c)) = chars.next() {
+        let escaped = match verbatim {
+            true => rest[idx..].starts_with("\"\""),
+            false => c == '\\',
+        };
+        if escaped {
+            chars.next();
+        } else if rest[idx..].starts_with(quote) {
+            return Some(prefix_len + idx + quote.len());
+        }
+    }
+    None
+}
+
+/// Split the text within a pair of brackets at its top level commas
+///
+/// Returns the arguments and the text following the closing bracket
+fn split_arguments(text: &str) -> Option<(Vec<&str>, &str)> {
+    let mut arguments = Vec::new();
+    let mut depth = 0;
+    let mut start = 1;
+    let mut idx = 1;
+    while idx < text.len() {
+        let rest = &text[idx..];
+        let argument_start = text[start..idx].trim().is_empty();
+        if let Some(len) = literal_len(rest, argument_start) {
+            idx += len;
+            continue;
+        }
+        let c = rest.chars().next()?;
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' if depth > 0 => depth -= 1,
+            ')' => {
+                let last = text[start..idx].trim();
+                if !last.is_empty() {
+                    arguments.push(last);
+                }
+                return Some((arguments, &text[idx + 1..]));
+            }
+            ',' if depth == 0 => {
+                arguments.push(text[start..idx].trim());
+                start = idx + 1;
+            }
+            _ => (),
+        }
+        idx += c.len_utf8();
+    }
+    None
+}
+
+/// Length of the callee path at the start of the text, such as `re.compile` or `Regex::new`
+fn path_len(text: &str) -> usize {
+    text.find(|c: char| !(c.is_alphanumeric() || ['_', '.', ':', '$', '!'].contains(&c)))
+        .unwrap_or(text.len())
+}
+
+/// Parse text of the form `callee(arguments).method(arguments)...`
+///
+/// A method called on a literal, such as `"a+".toRegex()`, takes the literal as its
+/// first argument
+pub fn parse_call(text: &str) -> Option<Call<'_>> {
+    let text = text.trim();
+    if let Some(len) = literal_len(text, true) {
+        let mut call = parse_call(text[len..].trim_start().strip_prefix('.')?)?;
+        call.arguments.insert(0, &text[..len]);
+        return Some(call);
+    }
+    let (new, rest) = match text.strip_prefix("new ") {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, text),
+    };
+    let callee_len = path_len(rest);
+    if callee_len == 0 || !rest[callee_len..].trim_start().starts_with('(') {
+        return None;
+    }
+    let callee = if new {
+        format!("new {}", &rest[..callee_len])
+    } else {
+        rest[..callee_len].to_string()
+    };
+    let (arguments, mut rest) = split_arguments(rest[callee_len..].trim_start())?;
+
+    let mut methods = Vec::new();
+    while let Some(method) = rest.trim_start().strip_prefix('.') {
+        let method = method.trim_start();
+        let name_len = path_len(method);
+        // A `.` ending an Erlang expression is not followed by a method
+        if name_len == 0 {
+            break;
+        }
+        let (method_arguments, remaining) = split_arguments(method[name_len..].trim_start())?;
+        methods.push((&method[..name_len], method_arguments));
+        rest = remaining;
+    }
+    Some(Call { callee, arguments, methods, rest })
+}
+
+/// Flags set by a named constant such as `re.IGNORECASE` or `Pattern.DOTALL`
+fn named_flag(name: &str) -> Option<Flags> {
+    let name = name.rsplit(['.', ':']).next()?;
+    let mut flags = Flags::default();
+    match name {
+        "I" | "IGNORECASE" | "CASE_INSENSITIVE" | "IGNORE_CASE" | "icase" | "IgnoreCase" | "caseInsensitive" | "caseless" => {
+            flags.case_insensitive = true
+        }
+        "M" | "MULTILINE" | "multiline" | "Multiline" | "anchorsMatchLines" => flags.multiline = true,
+        "S" | "DOTALL" | "DOT_MATCHES_ALL" | "Singleline" | "dotMatchesLineSeparators" | "dotall" => flags.dot_all = true,
+        "X" | "VERBOSE" | "COMMENTS" | "IgnorePatternWhitespace" | "allowCommentsAndWhitespace" | "extended" => {
+            flags.extended = true
+        }
+        "U" | "UNICODE" | "UNICODE_CASE" | "UNICODE_CHARACTER_CLASS" | "unicode" => flags.unicode = true,
+        "global" => flags.global = true,
+        _ => return None,
+    }
+    Some(flags)
+}
+
+/// Combine flag constants such as `re.I | re.X` or `setOf(RegexOption.IGNORE_CASE)`,
+/// ignoring any which do not change how the pattern is drawn
+pub fn constant_flags(text: &str) -> Flags {
+    text.split(|c: char| !(c.is_alphanumeric() || ['_', '.', ':'].contains(&c)))
+        .filter_map(named_flag)
+        .fold(Flags::default(), |flags, x| flags.union(&x))
+}
+
+/// Flags set by builder methods such as `.case_insensitive(true)`
+pub fn builder_flags(methods: &[(&str, Vec<&str>)]) -> Flags {
+    let mut flags = Flags::default();
+    for (name, arguments) in methods.iter() {
+        let enabled = arguments.first().is_some_and(|x| *x == "true");
+        match *name {
+            "case_insensitive" => flags.case_insensitive = enabled,
+            "multi_line" => flags.multiline = enabled,
+            "dot_matches_new_line" => flags.dot_all = enabled,
+            "ignore_whitespace" => flags.extended = enabled,
+            "unicode" => flags.unicode = enabled,
+            _ => (),
+        }
+    }
+    flags
+}
+
+/// Check if an argument starts with a literal rather than a variable or expression
+pub fn is_literal(argument: &str) -> bool {
+    literal_len(argument, true).is_some()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::extract::{
+        call::{builder_flags, constant_flags, is_literal, literal_len, parse_call},
+        Language,
+    };
+
+    #[test]
+    fn test_literal_len() {
+        assert_eq!(literal_len("\"a\\\"b\", c", false), Some(6));
+        assert_eq!(literal_len("r#\"a\"b\"#)", false), Some(8));
+        assert_eq!(literal_len("@\"a\"\"b\")", false), Some(7));
+        assert_eq!(literal_len("'''a'b'''", false), Some(9));
+        assert_eq!(literal_len("\"é\"", false), Some(4));
+        assert_eq!(literal_len("/a,b/g", true), Some(5));
+        assert_eq!(literal_len("/a,b/g", false), None);
+
+        // Unterminated literals and trailing backslashes
+        assert_eq!(literal_len("\"abc", false), None);
+        assert_eq!(literal_len("\"a\\", false), None);
+        assert_eq!(literal_len("\"é\\", false), None);
+        assert_eq!(literal_len("r#\"a\"", false), None);
+        assert_eq!(literal_len("#", false), None);
+        assert!(!is_literal("pattern"));
+    }
+
+    #[test]
+    fn test_parse_call() {
+        let call = parse_call("re.compile(r'a,(b)', flags=re.I | re.M)").unwrap();
+        assert_eq!(call.callee, "re.compile");
+        assert_eq!(call.arguments, vec!["r'a,(b)'", "flags=re.I | re.M"]);
+        assert_eq!(call.argument(1, "flags"), Some("re.I | re.M"));
+        assert!(call.regex_call(&Language::Python).is_some());
+        assert!(call.regex_call(&Language::Ruby).is_none());
+
+        let call = parse_call("RegexBuilder::new(\"é\").case_insensitive(true).build()").unwrap();
+        assert_eq!(call.arguments, vec!["\"é\""]);
+        assert!(builder_flags(&call.methods).case_insensitive);
+
+        let call = parse_call("\"a+\".toRegex(RegexOption.IGNORE_CASE)").unwrap();
+        assert_eq!(call.arguments, vec!["\"a+\"", "RegexOption.IGNORE_CASE"]);
+        assert!(constant_flags(call.arguments[1]).case_insensitive);
+
+        let call = parse_call("NSRegularExpression(pattern: \"a\", options: [.caseInsensitive])").unwrap();
+        assert_eq!(call.argument(0, "pattern"), Some("\"a\""));
+
+        // Unclosed calls, arguments and literals
+        assert!(parse_call("re.compile(").is_none());
+        assert!(parse_call("re.compile('a'").is_none());
+        assert!(parse_call("re.compile('a').sub(").is_none());
+        // An unterminated literal is not skipped, and is left for the string parser to reject
+        let call = parse_call("re.compile('a\\')").unwrap();
+        assert_eq!(call.arguments, vec!["'a\\'"]);
+        assert!(!is_literal(call.arguments[0]));
+        assert!(parse_call("'a'").is_none());
+        assert!(parse_call("").is_none());
+    }
+}
@@ -0,0 +1,369 @@
+use crate::{
+    error::Error,
+    extract::{
+        decoded::{Chars, Decoded},
+        literal::decode_escapes,
+        Language, LiteralKind,
+    },
+};
+
+/// Single character escapes of YAML double quoted strings, where an escaped space or tab
+/// keeps whitespace which would otherwise be folded
+const YAML_ESCAPES: &[(char, char)] = &[
+    ('0', '\0'),
+    ('a', '\x07'),
+    ('b', '\x08'),
+    ('t', '\t'),
+    ('\t', '\t'),
+    ('n', '\n'),
+    ('v', '\x0B'),
+    ('f', '\x0C'),
+    ('r', '\r'),
+    ('e', '\x1B'),
+    (' ', ' '),
+    ('"', '"'),
+    ('/', '/'),
+    ('\\', '\\'),
+    ('N', '\u{85}'),
+    ('_', '\u{A0}'),
+    ('L', '\u{2028}'),
+    ('P', '\u{2029}'),
+];
+
+/// Single character escapes of TOML basic strings
+const TOML_ESCAPES: &[(char, char)] = &[
+    ('b', '\x08'),
+    ('t', '\t'),
+    ('n', '\n'),
+    ('f', '\x0C'),
+    ('r', '\r'),
+    ('e', '\x1B'),
+    ('"', '"'),
+    ('\\', '\\'),
+];
+
+/// Single character escapes of JSON strings
+const JSON_ESCAPES: &[(char, char)] = &[
+    ('"', '"'),
+    ('\\', '\\'),
+    ('/', '/'),
+    ('b', '\x08'),
+    ('f', '\x0C'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+];
+
+/// Whether every escape of a string body is one of `simple` or starts with one of the
+/// `numeric` characters, as configuration formats reject unknown escapes such as `\d`
+/// rather than keeping them
+fn escapes_known(body: &str, simple: &[(char, char)], numeric: &str) -> bool {
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            continue;
+        }
+        match chars.next() {
+            Some(escape) if simple.iter().any(|(x, _)| *x == escape) || numeric.contains(escape) => (),
+            _ => return false,
+        }
+    }
+    true
+}
+
+/// Whether the text ends with a backslash which is not itself escaped
+fn ends_with_escape(text: &str) -> bool {
+    let backslashes = text.len() - text.trim_end_matches('\\').len();
+    backslashes % 2 == 1
+}
+
+/// Whether the closing delimiter of a string appears within its body, outside of any
+/// escape when the string has backslash escapes
+fn contains_quote(body: &str, quote: &str, escapes: bool) -> bool {
+    let mut chars = body.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        if escapes && c == '\\' {
+            chars.next();
+        } else if body[idx..].starts_with(quote) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Fold the lines of a YAML flow scalar, where whitespace around each line break is
+/// dropped, a single line break becomes a space and each empty line a line break
+///
+/// In double quoted strings a line ending with an escaped line break keeps it, for the
+/// escape to join the line with the next.
+fn fold_lines(source: &str, body: &str, escapes: bool) -> Decoded {
+    let lines: Vec<&str> = body.split('\n').map(|x| x.strip_suffix('\r').unwrap_or(x)).collect();
+    let last = lines.len() - 1;
+    let mut folded = Decoded::slice(source, &body[..0]);
+    let mut empty = 0;
+    for (idx, line) in lines.into_iter().enumerate() {
+        let line = if idx == 0 { line } else { line.trim_start() };
+        let line = if idx == last { line } else { line.trim_end() };
+        if idx == 0 {
+            folded.append(Decoded::slice(source, line));
+            continue;
+        }
+        if line.is_empty() && idx < last {
+            empty += 1;
+            continue;
+        }
+        // Folded line breaks start where the text of the previous line ends
+        let offset = folded.end();
+        if escapes && ends_with_escape(&folded.value()) {
+            folded.push('\n', offset);
+        } else if empty == 0 {
+            folded.push_escape(' ', offset);
+        } else {
+            (0..empty).for_each(|_| folded.push_escape('\n', offset));
+        }
+        empty = 0;
+        folded.append(Decoded::slice(source, line));
+    }
+    folded
+}
+
+/// Read a YAML `|` literal or `>` folded block scalar from its header to the end of its
+/// indented lines, or return `None` if its header is invalid
+///
+/// The indentation is that of the least indented line, as the indentation of the
+/// enclosing node is not part of the text. A `-` chomping indicator drops the final line
+/// break and `+` keeps any empty lines after it.
+fn block_scalar(source: &str, text: &str) -> Option<Decoded> {
+    let (header, content) = text.split_once('\n').unwrap_or((text, &text[text.len()..]));
+    let header = header.split(" #").next()?.trim_end();
+    let indicators = &header[1..];
+    if indicators.len() > 2 || !indicators.chars().all(|c| "+-123456789".contains(c)) {
+        return None;
+    }
+
+    let indent = |line: &str| line.len() - line.trim_start_matches(' ').len();
+    let lines: Vec<&str> = content.lines().collect();
+    let least = lines.iter().filter(|x| !x.trim().is_empty()).map(|x| indent(x)).min().unwrap_or(0);
+    let lines: Vec<&str> = lines
+        .into_iter()
+        .map(|x| if x.trim().is_empty() { &x[x.len()..] } else { &x[least..] })
+        .collect();
+    let trailing = lines.iter().rev().take_while(|x| x.is_empty()).count();
+    let lines = &lines[..lines.len() - trailing];
+
+    // Line breaks added to the value start where the text of the previous line ends
+    let mut value = Decoded::slice(source, &content[..0]);
+    if header.starts_with('|') {
+        value.kind = LiteralKind::RawString;
+        for (idx, line) in lines.iter().enumerate() {
+            if idx > 0 {
+                value.push('\n', value.end());
+            }
+            value.append(Decoded::slice(source, line));
+        }
+    } else {
+        // Lines are folded unless either side of the line break is more indented
+        let mut previous: Option<&str> = None;
+        let mut empty = 0;
+        for line in lines {
+            if line.is_empty() {
+                empty += 1;
+                continue;
+            }
+            let spaced = previous.is_some_and(|x| x.starts_with([' ', '\t'])) || line.starts_with([' ', '\t']);
+            let offset = value.end();
+            match previous {
+                Some(_) if empty == 0 && !spaced => value.push_escape(' ', offset),
+                Some(_) => (0..empty + usize::from(spaced)).for_each(|_| value.push('\n', offset)),
+                None => (0..empty).for_each(|_| value.push('\n', offset)),
+            }
+            empty = 0;
+            value.append(Decoded::slice(source, line));
+            previous = Some(line);
+        }
+    }
+
+    let offset = value.end();
+    match indicators.chars().find(|c| "+-".contains(*c)) {
+        Some('-') => (),
+        Some(_) => (0..trailing + 1).for_each(|_| value.push('\n', offset)),
+        None if !value.is_empty() => value.push('\n', offset),
+        None => (),
+    }
+    Some(value)
+}
+
+/// Parse a YAML scalar, either single quoted where a quote is escaped by doubling it,
+/// double quoted with backslash escapes, a `|` or `>` block scalar, or a plain scalar
+/// ending at any comment
+///
+/// Line breaks within flow scalars are folded into spaces.
+pub fn yaml_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Yaml, text.to_string());
+    // Trailing empty lines of block scalars are kept by their `+` chomping indicator
+    let trimmed = text.trim_start();
+    if trimmed.starts_with(['|', '>']) {
+        return block_scalar(text, trimmed).ok_or_else(invalid);
+    }
+    let trimmed = trimmed.trim_end();
+    if let Some(body) = trimmed.strip_prefix('\'').and_then(|x| x.strip_suffix('\'')) {
+        return Ok(fold_lines(text, body, false).replace("''", '\''));
+    }
+    if let Some(body) = trimmed.strip_prefix('"').and_then(|x| x.strip_suffix('"')) {
+        let folded = fold_lines(text, body, true);
+        if !escapes_known(&folded.value(), YAML_ESCAPES, "xuU\n") {
+            return Err(invalid());
+        }
+        return decode_escapes(&folded, YAML_ESCAPES).ok_or_else(invalid);
+    }
+    if trimmed.starts_with(['\'', '"']) {
+        return Err(invalid());
+    }
+    let comment = trimmed.match_indices('#').find(|(idx, _)| trimmed[..*idx].ends_with([' ', '\t']));
+    let plain = comment.map_or(trimmed, |(idx, _)| trimmed[..idx].trim_end());
+    Ok(fold_lines(text, plain, false))
+}
+
+/// Parse a TOML string, either a basic `"..."` string with backslash escapes, a literal
+/// `'...'` string read as written, or a multi-line `"""` or `'''` string which drops a
+/// line break straight after its opening delimiter
+pub fn toml_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Toml, text.to_string());
+    for (quote, escapes) in [("'''", false), ("\"\"\"", true), ("'", false), ("\"", true)] {
+        let Some(body) = text.strip_prefix(quote).and_then(|x| x.strip_suffix(quote)) else {
+            continue;
+        };
+        let body = match quote.len() {
+            3 => body.strip_prefix("\r\n").or_else(|| body.strip_prefix('\n')).unwrap_or(body),
+            _ => body,
+        };
+        if contains_quote(body, quote, escapes) {
+            continue;
+        }
+        if !escapes {
+            return Ok(Decoded::raw(text, body));
+        }
+        if !escapes_known(body, TOML_ESCAPES, "xuU\n") {
+            return Err(invalid());
+        }
+        return decode_escapes(&Decoded::slice(text, body), TOML_ESCAPES).ok_or_else(invalid);
+    }
+    Err(invalid())
+}
+
+/// Read four hexadecimal digits of a `\u` escape
+fn utf16_unit(chars: &mut Chars) -> Option<u32> {
+    let digits: String = chars.by_ref().take(4).map(|(c, _)| c).collect();
+    if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(&digits, 16).ok()
+}
+
+/// Parse a JSON string, where characters outside the basic multilingual plane may be
+/// escaped as a pair of `\u` surrogates
+pub fn json_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Json, text.to_string());
+    let body = text.strip_prefix('"').and_then(|x| x.strip_suffix('"')).ok_or_else(invalid)?;
+    let body = Decoded::slice(text, body);
+    let mut value = body.cleared();
+    let mut chars = body.chars();
+    while let Some((c, offset)) = chars.next() {
+        if c != '\\' {
+            value.push(c, offset);
+            continue;
+        }
+        let (escape, _) = chars.next().ok_or_else(invalid)?;
+        if let Some((_, to)) = JSON_ESCAPES.iter().find(|(x, _)| *x == escape) {
+            value.push_escape(*to, offset);
+            continue;
+        }
+        if escape != 'u' {
+            return Err(invalid());
+        }
+        let mut code = utf16_unit(&mut chars).ok_or_else(invalid)?;
+        if (0xD800..0xDC00).contains(&code) {
+            let low = match (chars.next(), chars.next()) {
+                (Some(('\\', _)), Some(('u', _))) => utf16_unit(&mut chars).filter(|x| (0xDC00..0xE000).contains(x)),
+                _ => None,
+            };
+            code = 0x10000 + ((code - 0xD800) << 10) + (low.ok_or_else(invalid)? - 0xDC00);
+        }
+        value.push_escape(char::from_u32(code).ok_or_else(invalid)?, offset);
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::extract::{
+        config::{json_string, toml_string, yaml_string},
+        LiteralKind,
+    };
+
+    #[test]
+    fn test_yaml_string() {
+        let value = |text: &str| yaml_string(text).ok().map(|x| x.value());
+        assert_eq!(value("'it''s é'"), Some(String::from("it's é")));
+        assert_eq!(value("\"\\d\""), None);
+        assert_eq!(value("\"\\\\d\\té\""), Some(String::from("\\d\té")));
+        assert_eq!(value("a+ # comment"), Some(String::from("a+")));
+        assert_eq!(value("a#b"), Some(String::from("a#b")));
+        assert_eq!(value("'a\n\n  b\n  c'"), Some(String::from("a\nb c")));
+        assert_eq!(value("\"a\\\n  b\""), Some(String::from("ab")));
+        assert_eq!(value("|\n  é\n  ü\n"), Some(String::from("é\nü\n")));
+        assert_eq!(value(">-\n  a\n  b\n\n"), Some(String::from("a b")));
+        assert_eq!(value("|+\n  a\n\n"), Some(String::from("a\n\n")));
+        assert_eq!(yaml_string("|\n  a").unwrap().kind, LiteralKind::RawString);
+
+        // Unterminated quotes, trailing backslashes and invalid headers
+        assert_eq!(value("'a"), None);
+        assert_eq!(value("\"a"), None);
+        assert_eq!(value("\"a\\\""), None);
+        assert_eq!(value("'"), None);
+        assert_eq!(value("\""), None);
+        assert_eq!(value("|x\n  a"), None);
+        assert_eq!(value("|"), Some(String::new()));
+    }
+
+    #[test]
+    fn test_toml_string() {
+        let value = |text: &str| toml_string(text).ok().map(|x| x.value());
+        assert_eq!(value("'\\d+é'"), Some(String::from("\\d+é")));
+        assert_eq!(value("\"\\\\d\\u00e9\""), Some(String::from("\\dé")));
+        assert_eq!(value("\"\\d\""), None);
+        assert_eq!(value("'''\na'b'''"), Some(String::from("a'b")));
+        assert_eq!(value("\"\"\"\r\na\"b\"\"\""), Some(String::from("a\"b")));
+
+        // Unterminated strings and trailing backslashes
+        assert_eq!(value("'a"), None);
+        assert_eq!(value("\"a\\\""), None);
+        assert_eq!(value("\"a\\"), None);
+        assert_eq!(value("'"), None);
+        assert_eq!(value("'''"), None);
+        assert_eq!(value("'a'b'"), None);
+        assert_eq!(value("\"a\"b\""), None);
+        assert_eq!(value("\"a\\\"b\""), Some(String::from("a\"b")));
+        assert_eq!(value("\"\"\"a\"\"\"b\"\"\""), None);
+        assert_eq!(value("\"\"\""), None);
+        assert_eq!(value("a"), None);
+    }
+
+    #[test]
+    fn test_json_string() {
+        let value = |text: &str| json_string(text).ok().map(|x| x.value());
+        assert_eq!(value("\"\\\\d+é\""), Some(String::from("\\d+é")));
+        assert_eq!(value("\"\\ud83d\\ude00\""), Some(String::from("😀")));
+        assert_eq!(value("\"\\u00e9\""), Some(String::from("é")));
+
+        // Invalid escapes, lone surrogates and unterminated strings
+        assert_eq!(value("\"\\d\""), None);
+        assert_eq!(value("\"\\ud83d\""), None);
+        assert_eq!(value("\"\\ude00\""), None);
+        assert_eq!(value("\"\\u00é\""), None);
+        assert_eq!(value("\"\\u12\""), None);
+        assert_eq!(value("\"a\\\""), None);
+        assert_eq!(value("\"a"), None);
+        assert_eq!(value("\""), None);
+    }
+}
@@ -0,0 +1,177 @@
+use std::{iter::Peekable, slice::Iter};
+
+use crate::extract::LiteralKind;
+
+/// Characters of decoded text, each with its byte offset in the source
+pub type Chars<'d> = Peekable<std::iter::Copied<Iter<'d, (char, usize)>>>;
+
+/// Text read from a literal, with the byte offset in the source of each character
+///
+/// Characters decoded from an escape sequence have the offset of the start of the
+/// sequence, so the span of a part of the text includes the escapes it was written with.
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+pub struct Decoded {
+    chars: Vec<(char, usize)>,
+    /// Offset of the end of the text in the source
+    end: usize,
+    /// Whether any escape was decoded, so the text is written differently in the source
+    pub escaped: bool,
+    /// How the literal is written
+    pub kind: LiteralKind,
+}
+
+impl Decoded {
+    /// Empty text ending at an offset in the source
+    pub fn empty(end: usize) -> Decoded {
+        Decoded { end, ..Default::default() }
+    }
+
+    /// Text of a slice of the source, as written
+    pub fn slice(source: &str, slice: &str) -> Decoded {
+        let start = slice_start(source, slice).unwrap_or_default();
+        Decoded {
+            chars: slice.char_indices().map(|(idx, c)| (c, start + idx)).collect(),
+            end: start + slice.len(),
+            ..Default::default()
+        }
+    }
+
+    /// Text of a slice of the source which is read as written, such as a raw string
+    pub fn raw(source: &str, slice: &str) -> Decoded {
+        Decoded { kind: LiteralKind::RawString, ..Decoded::slice(source, slice) }
+    }
+
+    /// Empty text with the same end and properties, to be filled by another pass over
+    /// this text
+    pub fn cleared(&self) -> Decoded {
+        Decoded { chars: Vec::new(), ..self.clone() }
+    }
+
+    pub fn chars(&self) -> Chars<'_> {
+        self.chars.iter().copied().peekable()
+    }
+
+    pub fn value(&self) -> String {
+        self.chars.iter().map(|(c, _)| c).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn set_end(&mut self, end: usize) {
+        self.end = end;
+    }
+
+    /// Offset of each character followed by the offset of the end of the text
+    pub fn offsets(&self) -> Vec<usize> {
+        self.chars.iter().map(|(_, offset)| *offset).chain([self.end]).collect()
+    }
+
+    /// Add a character written at an offset in the source
+    pub fn push(&mut self, c: char, offset: usize) {
+        self.chars.push((c, offset));
+    }
+
+    /// Add a character decoded from an escape sequence starting at an offset
+    pub fn push_escape(&mut self, c: char, offset: usize) {
+        self.escaped = true;
+        self.chars.push((c, offset));
+    }
+
+    /// Add text following this text in the source, such as the next of several adjacent
+    /// literals
+    pub fn append(&mut self, other: Decoded) {
+        self.chars.extend(other.chars);
+        self.end = other.end;
+        self.escaped |= other.escaped;
+    }
+
+    /// Text with each occurrence of `from` replaced by a single character, as when a
+    /// quote is escaped by doubling it
+    pub fn replace(&self, from: &str, to: char) -> Decoded {
+        let from: Vec<char> = from.chars().collect();
+        let mut replaced = self.cleared();
+        let mut idx = 0;
+        while idx < self.chars.len() {
+            let (c, offset) = self.chars[idx];
+            let matched = self.chars[idx..].iter().map(|(c, _)| c).take(from.len()).eq(from.iter());
+            match matched && !from.is_empty() {
+                true => {
+                    replaced.push_escape(to, offset);
+                    idx += from.len();
+                }
+                false => {
+                    replaced.push(c, offset);
+                    idx += 1;
+                }
+            }
+        }
+        replaced
+    }
+
+    /// Part of the text, given as a slice of its value
+    pub fn part(&self, value: &str, part: &str) -> Decoded {
+        let start = slice_start(value, part).unwrap_or_default();
+        let first = value[..start].chars().count();
+        let count = part.chars().count();
+        Decoded {
+            chars: self.chars[first..first + count].to_vec(),
+            end: self.chars.get(first + count).map_or(self.end, |(_, offset)| *offset),
+            ..self.clone()
+        }
+    }
+
+    /// Offsets in the source of the characters of a pattern read from the value of this
+    /// text, given as offsets into the value
+    pub fn source_offsets(&self, value: &str, offsets: &[usize]) -> Vec<usize> {
+        let positions: Vec<usize> = value.char_indices().map(|(idx, _)| idx).collect();
+        offsets
+            .iter()
+            .map(|offset| match positions.binary_search(offset) {
+                Ok(idx) => self.chars[idx].1,
+                Err(_) => self.end,
+            })
+            .collect()
+    }
+}
+
+/// Byte offset of a slice within the text it was taken from, or `None` if it is not a
+/// part of it
+pub fn slice_start(text: &str, slice: &str) -> Option<usize> {
+    let start = (slice.as_ptr() as usize).checked_sub(text.as_ptr() as usize)?;
+    (start + slice.len() <= text.len()).then_some(start)
+}
+
+/// Shift offsets into a slice to be offsets into the text it was taken from, leaving them
+/// empty if the slice is not a part of it
+pub fn shift(offsets: &mut Vec<usize>, text: &str, slice: &str) {
+    match slice_start(text, slice) {
+        Some(start) => offsets.iter_mut().for_each(|x| *x += start),
+        None => offsets.clear(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::extract::decoded::Decoded;
+
+    #[test]
+    fn test_decoded() {
+        let source = "'it''s é'";
+        let decoded = Decoded::slice(source, &source[1..source.len() - 1]).replace("''", '\'');
+        assert_eq!(decoded.value(), "it's é");
+        assert!(decoded.escaped);
+        assert_eq!(decoded.offsets(), vec![1, 2, 3, 5, 6, 7, 9]);
+
+        let value = decoded.value();
+        let part = decoded.part(&value, &value[2..]);
+        assert_eq!(part.value(), "'s é");
+        assert_eq!(part.offsets(), vec![3, 5, 6, 7, 9]);
+        assert_eq!(decoded.source_offsets(&value, &[3, 5, value.len()]), vec![5, 7, 9]);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{
+    error::Error,
+    extract::{Language, StringFormat},
+    parser::Flavor,
+};
+
+/// A language defined by the user, either in the plugin's `setup()` or a languages file
+#[derive(Clone, Default, Debug)]
+pub struct LanguageDefinition {
+    pub name: String,
+    /// Neovim filetypes and tree-sitter languages using this definition, in addition
+    /// to its name
+    pub filetypes: Vec<String>,
+    /// File extensions using this definition, without the leading `.`
+    pub extensions: Vec<String>,
+    /// Characters which start and end a string, such as `"`
+    pub delimiters: Vec<String>,
+    /// Starts of raw strings, whose escapes are kept as written, such as `r"`
+    pub raw_prefixes: Vec<String>,
+    /// Ends of raw strings, such as `"`
+    pub raw_suffixes: Vec<String>,
+    pub escape: Option<String>,
+    /// Regular expression syntax, by its display name such as `PCRE` or `RE2`
+    pub flavor: Option<String>,
+}
+
+impl LanguageDefinition {
+    pub fn new(name: &str) -> LanguageDefinition {
+        LanguageDefinition {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn invalid(&self, reason: &str) -> Error {
+        Error::InvalidDefinition(self.name.clone(), reason.to_string())
+    }
+
+    /// Language this definition describes
+    pub fn language(&self) -> Language {
+        Language::Custom(self.name.clone())
+    }
+
+    /// Validate the definition and build its string format
+    pub fn string_format(&self) -> Result<StringFormat, Error> {
+        if self.name.trim().is_empty() {
+            return Err(self.invalid("name must not be empty"));
+        }
+        if self.delimiters.is_empty() {
+            return Err(self.invalid("at least one string delimiter is needed"));
+        }
+        let all = self.delimiters.iter().chain(&self.raw_prefixes).chain(&self.raw_suffixes);
+        if all.clone().any(|x| x.is_empty()) {
+            return Err(self.invalid("delimiters, raw prefixes and raw suffixes must not be empty"));
+        }
+        if self.raw_prefixes.is_empty() != self.raw_suffixes.is_empty() {
+            return Err(self.invalid("raw prefixes and raw suffixes must be given together"));
+        }
+
+        let escape_character = match self.escape.as_deref() {
+            None => '\\',
+            Some(escape) => {
+                let mut chars = escape.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => return Err(self.invalid("escape must be a single character")),
+                }
+            }
+        };
+        let flavor = match self.flavor.as_deref() {
+            None => Flavor::default(),
+            Some(name) => Flavor::from_name(name).ok_or_else(|| {
+                let names: Vec<String> = Flavor::ALL.iter().map(|x| x.to_string()).collect();
+                self.invalid(&format!("unknown flavor '{}', expected one of {}", name, names.join(", ")))
+            })?,
+        };
+
+        let raw = |x: &Vec<String>| (!x.is_empty()).then(|| x.clone());
+        Ok(StringFormat {
+            string_character: self.delimiters.clone(),
+            escape_character,
+            literal_string_start: raw(&self.raw_prefixes),
+            literal_string_end: raw(&self.raw_suffixes),
+            regex_literal_flags: None,
+            regex_parser: None,
+            literal_parser: None,
+            flavor,
+        })
+    }
+}
@@ -0,0 +1,571 @@
+use crate::{
+    error::Error,
+    extract::{
+        decoded::{self, Decoded},
+        literal, ExtractedRegex, LiteralKind,
+    },
+    flags::Flags,
+    parser::Flavor,
+};
+
+/// Ruby modifiers and the flag letters they set, where an empty string marks a
+/// modifier which does not change how the pattern is drawn
+const RUBY_MODIFIERS: &[(char, &str)] = &[
+    ('i', "i"),
+    ('m', "s"),
+    ('x', "x"),
+    ('u', "u"),
+    ('o', ""),
+    ('n', ""),
+    ('e', ""),
+    ('s', ""),
+];
+
+/// Perl match and substitution modifiers
+const PERL_MODIFIERS: &[(char, &str)] = &[
+    ('i', "i"),
+    ('m', "m"),
+    ('s', "s"),
+    ('x', "x"),
+    ('g', "g"),
+    ('u', "u"),
+    ('n', ""),
+    ('p', ""),
+    ('o', ""),
+    ('d', ""),
+    ('a', ""),
+    ('l', ""),
+    ('c', ""),
+    ('e', ""),
+    ('r', ""),
+];
+
+/// PHP PCRE pattern modifiers
+const PHP_MODIFIERS: &[(char, &str)] = &[
+    ('i', "i"),
+    ('m', "m"),
+    ('s', "s"),
+    ('x', "x"),
+    ('u', "u"),
+    ('A', ""),
+    ('D', ""),
+    ('S', ""),
+    ('U', ""),
+    ('X', ""),
+    ('J', ""),
+    ('n', ""),
+];
+
+/// Vim `:substitute` flags
+const VIM_MODIFIERS: &[(char, &str)] = &[
+    ('g', "g"),
+    ('i', "i"),
+    ('I', ""),
+    ('c', ""),
+    ('e', ""),
+    ('n', ""),
+    ('p', ""),
+    ('l', ""),
+    ('r', ""),
+    ('#', ""),
+    ('&', ""),
+];
+
+/// GNU sed `s` command flags, besides the number of the match to replace
+const SED_MODIFIERS: &[(char, &str)] = &[
+    ('g', "g"),
+    ('i', "i"),
+    ('I', "i"),
+    ('m', "m"),
+    ('M', "m"),
+    ('p', ""),
+    ('e', ""),
+];
+
+/// Elixir regex sigil modifiers, where `f` anchors the pattern to the start of the
+/// string and `U` makes quantifiers lazy
+const ELIXIR_MODIFIERS: &[(char, &str)] = &[
+    ('i', "i"),
+    ('m', "m"),
+    ('s', "s"),
+    ('x', "x"),
+    ('u', "u"),
+    ('f', ""),
+    ('U', ""),
+    ('o', ""),
+    ('E', ""),
+];
+
+/// Haskell quasi-quoters which take a PCRE pattern, as in `[re|a+|]`
+const HASKELL_QUASI_QUOTERS: &[&str] = &["re", "rex", "regex", "pcre"];
+
+/// Translate trailing modifiers into flags using a language's modifier table
+pub fn modifier_flags(modifiers: &str, table: &[(char, &str)]) -> Result<Flags, Error> {
+    let mut letters = String::new();
+    for c in modifiers.trim_end().chars() {
+        let (_, letter) = table.iter().find(|(x, _)| *x == c).ok_or(Error::InvalidFlag(c))?;
+        letters.push_str(letter);
+    }
+    Flags::from_chars(&letters, "gimsux")
+}
+
+/// Regex literal whose pattern is the given slice of the source, read as written
+fn regex_literal(source: &str, pattern: &str) -> ExtractedRegex {
+    ExtractedRegex {
+        kind: LiteralKind::RegexLiteral,
+        ..ExtractedRegex::from_literal(Decoded::slice(source, pattern))
+    }
+}
+
+/// Closing delimiter for an opening one, where brackets close with their pair
+fn closing_delimiter(open: char) -> char {
+    match open {
+        '(' => ')',
+        '[' => ']',
+        '{' => '}',
+        '<' => '>',
+        other => other,
+    }
+}
+
+/// Split text starting with a delimiter into `count` delimited parts and the text after
+/// them, so `/a/b/g` gives `a`, `b` and `g`
+///
+/// Bracket delimiters nest and close with their pair, and each part of a substitution
+/// written with brackets has its own delimiters, as in `s{a} {b}`
+fn split_delimited(text: &str, count: usize) -> Result<(Vec<&str>, &str), Error> {
+    let unterminated = || Error::UnterminatedLiteral(text.to_string());
+    let mut parts = Vec::new();
+    let mut rest = text;
+    while parts.len() < count {
+        let open = rest.chars().next().ok_or_else(unterminated)?;
+        let close = closing_delimiter(open);
+        let body = &rest[open.len_utf8()..];
+
+        let mut depth = 0;
+        let mut end = None;
+        let mut chars = body.char_indices();
+        while let Some((idx, c)) = chars.next() {
+            if c == '\\' {
+                chars.next();
+            } else if c == close && depth == 0 {
+                end = Some(idx);
+                break;
+            } else if c == close {
+                depth -= 1;
+            } else if c == open {
+                depth += 1;
+            }
+        }
+        let end = end.ok_or_else(unterminated)?;
+        parts.push(&body[..end]);
+
+        let after = &body[end + close.len_utf8()..];
+        rest = match (open == close, parts.len() < count) {
+            // The closing delimiter also opens the next part
+            (true, true) => &body[end..],
+            (false, true) => after.trim_start(),
+            (_, false) => after,
+        };
+    }
+    Ok((parts, rest))
+}
+
+/// Byte length of the delimited literal at the start of the text, with `count` parts
+/// and any trailing modifier letters
+pub fn delimited_len(text: &str, count: usize) -> Option<usize> {
+    let (_, rest) = split_delimited(text, count).ok()?;
+    let modifiers = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+    Some(text.len() - rest.len() + modifiers)
+}
+
+/// Parse a Ruby `/.../` or `%r{...}` regex literal with any delimiters and trailing
+/// modifiers, or return `None` if the text is not one
+pub fn ruby_regex(text: &str) -> Option<Result<ExtractedRegex, Error>> {
+    let trimmed = text.trim();
+    let rest = match trimmed.starts_with('/') {
+        true => trimmed,
+        false => trimmed.strip_prefix("%r")?,
+    };
+    Some(ruby_literal(text, rest))
+}
+
+fn ruby_literal(source: &str, text: &str) -> Result<ExtractedRegex, Error> {
+    let (parts, modifiers) = split_delimited(text, 1)?;
+    let flags = modifier_flags(modifiers, RUBY_MODIFIERS)?;
+    literal::templates(parts[0], false, &['#'], false)?;
+    Ok(ExtractedRegex { flags, ..regex_literal(source, parts[0]) })
+}
+
+/// Parse a Perl `m//`, `qr//` or `s///` operator, or a bare `/.../` match, with any
+/// delimiters and trailing modifiers, or return `None` if the text is not one
+pub fn perl_regex(text: &str) -> Option<Result<ExtractedRegex, Error>> {
+    let trimmed = text.trim();
+    let (operator, rest) = match trimmed.starts_with('/') {
+        true => ("m", trimmed),
+        false => {
+            let len = trimmed.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(trimmed.len());
+            (&trimmed[..len], &trimmed[len..])
+        }
+    };
+    if !["m", "qr", "s"].contains(&operator) {
+        return None;
+    }
+    // Whitespace may separate the operator from its delimiter, unless the delimiter is
+    // `#` which would instead start a comment
+    let delimiter = rest.trim_start().chars().next()?;
+    if delimiter.is_alphanumeric() || delimiter == '_' || (delimiter == '#' && rest.starts_with(char::is_whitespace)) {
+        return None;
+    }
+    Some(perl_operator(text, operator, rest.trim_start()))
+}
+
+fn perl_operator(source: &str, operator: &str, text: &str) -> Result<ExtractedRegex, Error> {
+    let count = if operator == "s" { 2 } else { 1 };
+    let (parts, modifiers) = split_delimited(text, count)?;
+    let flags = modifier_flags(modifiers, PERL_MODIFIERS)?;
+    // Single quote delimiters turn off interpolation
+    if !text.starts_with('\'') {
+        literal::templates(parts[0], false, &['$', '@'], true)?;
+    }
+    Ok(ExtractedRegex {
+        flags,
+        replacement: parts.get(1).map(|x| x.to_string()),
+        ..regex_literal(source, parts[0])
+    })
+}
+
+/// Parse a PHP string holding a PCRE pattern with delimiters and trailing modifiers,
+/// such as `'/\d+/i'`, or return `None` if the text is not a delimited pattern
+pub fn php_regex(text: &str) -> Option<Result<ExtractedRegex, Error>> {
+    let trimmed = text.trim();
+    if !trimmed.starts_with(['\'', '"']) {
+        return None;
+    }
+    let value = match literal::php_string(trimmed) {
+        Ok(value) => value,
+        Err(e) => return Some(Err(e)),
+    };
+    let value_text = value.value();
+    let pattern = value_text.trim_start();
+    let delimiter = pattern.chars().next()?;
+    if delimiter.is_alphanumeric() || delimiter == '\\' {
+        return None;
+    }
+    Some(php_pattern(&value, &value_text, pattern).map(|mut regex| {
+        decoded::shift(&mut regex.offsets, text, trimmed);
+        regex
+    }))
+}
+
+/// Pattern of the delimited `text`, which is a slice of the value of a string literal
+fn php_pattern(value: &Decoded, value_text: &str, text: &str) -> Result<ExtractedRegex, Error> {
+    let (parts, modifiers) = split_delimited(text, 1)?;
+    Ok(ExtractedRegex {
+        flags: modifier_flags(modifiers, PHP_MODIFIERS)?,
+        kind: LiteralKind::RegexLiteral,
+        ..ExtractedRegex::from_literal(value.part(value_text, parts[0]))
+    })
+}
+
+/// Parse a Vim `:s/.../.../` substitution or `:g/.../` global command with any range
+/// and delimiter, or return `None` if the text is not one
+pub fn vim_regex(text: &str) -> Option<Result<ExtractedRegex, Error>> {
+    let line = text.trim().trim_start_matches(':');
+    // Skip a range such as `%`, `1,$` or `'<,'>`, where marks are a quote and a letter
+    let mut chars = line.char_indices().peekable();
+    while let Some((_, c)) = chars.next_if(|(_, c)| "%.$,;+-'0123456789".contains(*c)) {
+        if c == '\'' {
+            chars.next();
+        }
+    }
+    let rest = &line[chars.peek()?.0..];
+    let len = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+    let (command, rest) = (&rest[..len], &rest[len..]);
+    let count = match command {
+        "s" | "substitute" => 2,
+        "g" | "global" | "v" | "vglobal" => 1,
+        _ => return None,
+    };
+    let rest = rest.strip_prefix('!').filter(|_| count == 1).unwrap_or(rest);
+    let delimiter = rest.chars().next()?;
+    if delimiter.is_alphanumeric() || delimiter.is_whitespace() || "\"|\\(".contains(delimiter) {
+        return None;
+    }
+    Some(vim_command(text, rest, count))
+}
+
+fn vim_command(source: &str, text: &str, count: usize) -> Result<ExtractedRegex, Error> {
+    let (parts, rest) = split_delimited(text, count)?;
+    // Substitution flags may be followed by a count, and a global command by the command
+    let flags = match count {
+        2 => modifier_flags(rest.split_whitespace().next().unwrap_or_default(), VIM_MODIFIERS)?,
+        _ => Flags::default(),
+    };
+    Ok(ExtractedRegex {
+        flags,
+        replacement: parts.get(1).map(|x| x.to_string()),
+        ..regex_literal(source, parts[0])
+    })
+}
+
+/// Parse the first command of a sed script which has a regular expression, either a
+/// substitution such as `s/a/b/g` or an address such as `/a/d` or `\%a%Ip`, or return
+/// `None` if there is none
+///
+/// Basic syntax is used unless `extended`, as set by `sed -E`.
+pub fn sed_script(script: &str, extended: bool) -> Option<Result<ExtractedRegex, Error>> {
+    let flavor = if extended { Flavor::Ere } else { Flavor::Bre };
+    // Skip any line number address, such as `1,5` or `$`
+    let command = script.trim_start_matches(|c: char| c.is_ascii_digit() || c.is_whitespace() || ",$!~".contains(c));
+    let substitution = command
+        .strip_prefix('s')
+        .filter(|x| x.starts_with(|c: char| !(c.is_alphanumeric() || c.is_whitespace() || c == '\\')));
+    let (count, rest) = match substitution {
+        Some(rest) => (2, rest),
+        None if command.starts_with('/') => (1, command),
+        None => (1, command.strip_prefix('\\')?),
+    };
+    if rest.is_empty() {
+        return None;
+    }
+    Some(split_delimited(rest, count).and_then(|(parts, rest)| {
+        // Substitutions take flags, and addresses only `I`
+        let modifiers = match count {
+            2 => rest.trim_start_matches(|c: char| SED_MODIFIERS.iter().any(|(x, _)| *x == c) || c.is_ascii_digit()),
+            _ => rest.trim_start_matches('I'),
+        };
+        let modifiers: String = rest[..rest.len() - modifiers.len()].chars().filter(|c| !c.is_ascii_digit()).collect();
+        Ok(ExtractedRegex {
+            flags: modifier_flags(&modifiers, SED_MODIFIERS)?,
+            replacement: parts.get(1).map(|x| x.to_string()),
+            flavor: Some(flavor),
+            ..regex_literal(script, parts[0])
+        })
+    }))
+}
+
+/// Parse a command of a sed script, such as `s/a/b/g` or `/a/d`, or return `None` if it
+/// has no regular expression
+pub fn sed_regex(text: &str) -> Option<Result<ExtractedRegex, Error>> {
+    sed_script(text, false)
+}
+
+/// Find the first regex literal in awk code, such as `/a+/ { print }` or `$1 ~ /a/`, or
+/// return `None` if there is none
+///
+/// A `/` only starts a literal at the start of the code or after an operator, so that
+/// divisions are skipped, and strings and comments are skipped.
+pub fn awk_regex(text: &str) -> Option<Result<ExtractedRegex, Error>> {
+    let mut previous = None;
+    let mut chars = text.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '"' => {
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => (),
+                    }
+                }
+                previous = Some(c);
+            }
+            '#' => {
+                chars.by_ref().find(|(_, c)| *c == '\n');
+            }
+            '/' if previous.is_none_or(|x| "~(,!&|{};".contains(x)) => {
+                return Some(split_delimited(&text[idx..], 1).map(|(parts, _)| ExtractedRegex {
+                    flavor: Some(Flavor::Ere),
+                    ..regex_literal(text, parts[0])
+                }));
+            }
+            c if !c.is_whitespace() => previous = Some(c),
+            _ => (),
+        }
+    }
+    None
+}
+
+/// Find the first Elixir `~r` or `~R` regex sigil, such as `~r/a+/i`, `~r{a+}` or a
+/// `~r"""` heredoc, or return `None` if there is none
+///
+/// Sigils are found anywhere in the text so that calls such as `Regex.match?(~r/a/, s)`
+/// are read, skipping strings and comments. `~r` sigils may not interpolate `#{...}`,
+/// while `~R` sigils are read as written.
+pub fn elixir_regex(text: &str) -> Option<Result<ExtractedRegex, Error>> {
+    let mut chars = text.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                while let Some((_, next)) = chars.next() {
+                    match next {
+                        '\\' => {
+                            chars.next();
+                        }
+                        next if next == c => break,
+                        _ => (),
+                    }
+                }
+            }
+            '#' => {
+                chars.by_ref().find(|(_, c)| *c == '\n');
+            }
+            '~' => {
+                let Some((_, sigil)) = chars.next_if(|(_, c)| ['r', 'R'].contains(c)) else {
+                    continue;
+                };
+                let rest = &text[idx + 2..];
+                if rest.starts_with(['/', '|', '"', '\'', '(', '[', '{', '<']) {
+                    return Some(elixir_sigil(text, rest, sigil == 'r'));
+                }
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+fn elixir_sigil(source: &str, text: &str, interpolates: bool) -> Result<ExtractedRegex, Error> {
+    let heredoc = ["\"\"\"", "'''"].into_iter().find(|x| text.starts_with(x));
+    let (pattern, modifiers) = match heredoc {
+        // Heredocs start on the line after the opening delimiter and end before the
+        // indentation of the closing one
+        Some(delimiter) => {
+            let body = text[3..].split_once('\n').map_or("", |(_, x)| x);
+            let end = body.find(delimiter).ok_or_else(|| Error::UnterminatedLiteral(text.to_string()))?;
+            (body[..end].trim_end_matches([' ', '\t']), &body[end + 3..])
+        }
+        None => {
+            let (parts, rest) = split_delimited(text, 1)?;
+            (parts[0], rest)
+        }
+    };
+    let modifiers = &modifiers[..modifiers.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(modifiers.len())];
+    let flags = modifier_flags(modifiers, ELIXIR_MODIFIERS)?;
+    if interpolates {
+        literal::templates(pattern, false, &['#'], false)?;
+    }
+    Ok(ExtractedRegex { flags, ..regex_literal(source, pattern) })
+}
+
+/// Body of the Haskell regex quasi-quote at the start of the text, such as `[re|a+|]`,
+/// and its byte length
+fn quasi_quote(text: &str) -> Option<(&str, usize)> {
+    let (quoter, rest) = text.strip_prefix('[')?.split_once('|')?;
+    if !HASKELL_QUASI_QUOTERS.contains(&quoter) {
+        return None;
+    }
+    let end = rest.find("|]")?;
+    Some((&rest[..end], quoter.len() + end + 4))
+}
+
+/// Byte length of the Haskell regex quasi-quote at the start of the text
+pub fn quasi_quote_len(text: &str) -> Option<usize> {
+    quasi_quote(text).map(|(_, len)| len)
+}
+
+/// Find the first Haskell regex quasi-quote, such as `[re|a+|]`, whose body is read as
+/// written up to the closing `|]`, or return `None` if there is none
+pub fn haskell_regex(text: &str) -> Option<Result<ExtractedRegex, Error>> {
+    let (body, _) = text.match_indices('[').find_map(|(idx, _)| quasi_quote(&text[idx..]))?;
+    Some(Ok(regex_literal(text, body)))
+}
+
+/// Parse a Swift `/.../` or extended `#/.../#` regex literal, or return `None` if the
+/// text is not one
+///
+/// Extended literals spanning several lines ignore whitespace, as with the `x` flag
+pub fn swift_regex(text: &str) -> Option<Result<ExtractedRegex, Error>> {
+    let trimmed = text.trim();
+    let hashes = trimmed.len() - trimmed.trim_start_matches('#').len();
+    let body = trimmed[hashes..].strip_prefix('/')?;
+    let closing = format!("/{}", "#".repeat(hashes));
+    // The closing delimiter is not escaped by an odd number of backslashes before it
+    let pattern = body
+        .strip_suffix(closing.as_str())
+        .filter(|x| (x.len() - x.trim_end_matches('\\').len()) % 2 == 0);
+    let Some(pattern) = pattern else {
+        return Some(Err(Error::UnterminatedLiteral(trimmed.to_string())));
+    };
+    Some(Ok(ExtractedRegex {
+        flags: Flags {
+            extended: hashes > 0 && pattern.contains('\n'),
+            ..Default::default()
+        },
+        ..regex_literal(text, pattern)
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        extract::{
+            delimited::{
+                awk_regex, delimited_len, elixir_regex, haskell_regex, perl_regex, php_regex, ruby_regex,
+                sed_regex, split_delimited, swift_regex, vim_regex,
+            },
+            ExtractedRegex,
+        },
+    };
+
+    fn pattern(result: Option<Result<ExtractedRegex, Error>>) -> Option<String> {
+        result.and_then(|x| x.ok()).map(|x| x.pattern)
+    }
+
+    #[test]
+    fn test_split_delimited() {
+        assert_eq!(split_delimited("/a/b/g", 2).unwrap(), (vec!["a", "b"], "g"));
+        assert_eq!(split_delimited("{a{b}} {c}x", 2).unwrap(), (vec!["a{b}", "c"], "x"));
+        assert_eq!(split_delimited("/a\\/é/", 1).unwrap(), (vec!["a\\/é"], ""));
+        assert_eq!(split_delimited("éaéb", 1).unwrap(), (vec!["a"], "b"));
+        assert_eq!(delimited_len("/a/gi;", 1), Some(5));
+
+        // Unterminated literals, including those ending in an escape
+        assert!(matches!(split_delimited("/a", 1), Err(Error::UnterminatedLiteral(_))));
+        assert!(matches!(split_delimited("/a\\/", 1), Err(Error::UnterminatedLiteral(_))));
+        assert!(matches!(split_delimited("/a/b", 2), Err(Error::UnterminatedLiteral(_))));
+        assert!(matches!(split_delimited("{a{b}", 1), Err(Error::UnterminatedLiteral(_))));
+        assert!(matches!(split_delimited("", 1), Err(Error::UnterminatedLiteral(_))));
+        assert_eq!(delimited_len("/a\\", 1), None);
+    }
+
+    #[test]
+    fn test_delimited_regex() {
+        assert_eq!(pattern(ruby_regex("%r{é+}i")), Some(String::from("é+")));
+        assert!(ruby_regex("/a\\").unwrap().is_err());
+        assert!(ruby_regex("/a/q").unwrap().is_err());
+
+        assert_eq!(pattern(perl_regex("s{a}{b}g")), Some(String::from("a")));
+        assert_eq!(pattern(perl_regex("qr#é#")), Some(String::from("é")));
+        assert!(perl_regex("m/a").unwrap().is_err());
+        assert!(perl_regex("s/a/b").unwrap().is_err());
+
+        assert_eq!(pattern(php_regex("'~é~u'")), Some(String::from("é")));
+        assert!(php_regex("'/a'").unwrap().is_err());
+
+        assert_eq!(pattern(vim_regex(":%s/é/e/g")), Some(String::from("é")));
+        assert!(vim_regex(":s/a\\").unwrap().is_err());
+
+        assert_eq!(pattern(sed_regex("s|é|e|")), Some(String::from("é")));
+        assert_eq!(pattern(sed_regex("\\%a%d")), Some(String::from("a")));
+        assert!(sed_regex("s/a/").unwrap().is_err());
+
+        assert_eq!(pattern(awk_regex("$1 ~ /é/")), Some(String::from("é")));
+        assert!(awk_regex("a / b").is_none());
+        assert!(awk_regex("/a\\/").unwrap().is_err());
+
+        assert_eq!(pattern(elixir_regex("Regex.match?(~r/é/u, s)")), Some(String::from("é")));
+        assert!(elixir_regex("~r/a").unwrap().is_err());
+
+        assert_eq!(pattern(haskell_regex("[re|é+|]")), Some(String::from("é+")));
+        assert!(haskell_regex("[re|a+").is_none());
+
+        assert_eq!(pattern(swift_regex("#/é/#")), Some(String::from("é")));
+        assert_eq!(pattern(swift_regex("/a\\\\/")), Some(String::from("a\\\\")));
+        assert!(swift_regex("/a\\/").unwrap().is_err());
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{
+    error::Error,
+    extract::{decoded::Decoded, ExtractedRegex, Language, LiteralKind},
+    parser::Flavor,
+};
+
+/// First line of the text, or an error if it has no pattern because it is blank or a
+/// comment
+fn pattern_line(language: Language, text: &str) -> Result<&str, Error> {
+    let line = text.lines().next().unwrap_or_default().trim_end_matches('\r');
+    if line.trim().is_empty() || line.starts_with('#') {
+        return Err(Error::InvalidString(language, line.to_string()));
+    }
+    Ok(line)
+}
+
+/// Parse a line of a `.gitignore` file, whose trailing spaces are ignored unless escaped
+pub fn gitignore_pattern(text: &str) -> Option<Result<ExtractedRegex, Error>> {
+    Some(pattern_line(Language::Gitignore, text).map(|line| {
+        let mut pattern = line.trim_end_matches(' ');
+        let escapes = pattern.len() - pattern.trim_end_matches('\\').len();
+        if escapes % 2 == 1 && pattern.len() < line.len() {
+            pattern = &line[..pattern.len() + 1];
+        }
+        ExtractedRegex {
+            flavor: Some(Flavor::Gitignore),
+            kind: LiteralKind::Line,
+            ..ExtractedRegex::from_literal(Decoded::slice(text, pattern))
+        }
+    }))
+}
+
+/// Parse a line of a `CODEOWNERS` file, whose pattern is followed by its owners and may
+/// escape spaces with a backslash
+pub fn codeowners_pattern(text: &str) -> Option<Result<ExtractedRegex, Error>> {
+    Some(pattern_line(Language::Codeowners, text).map(|line| {
+        let line = line.trim_start();
+        let mut end = line.len();
+        let mut escaped = false;
+        for (idx, c) in line.char_indices() {
+            if c.is_whitespace() && !escaped {
+                end = idx;
+                break;
+            }
+            escaped = c == '\\' && !escaped;
+        }
+        ExtractedRegex {
+            flavor: Some(Flavor::Gitignore),
+            kind: LiteralKind::Line,
+            ..ExtractedRegex::from_literal(Decoded::slice(text, &line[..end]))
+        }
+    }))
+}
+
+/// Parse a section header of an `.editorconfig` file, such as `[*.{js,py}]`, whose glob
+/// is written between the brackets
+pub fn editorconfig_pattern(text: &str) -> Option<Result<ExtractedRegex, Error>> {
+    Some(pattern_line(Language::Editorconfig, text).and_then(|line| {
+        let section = line.trim().strip_prefix('[').and_then(|x| x.strip_suffix(']'));
+        let pattern = section.ok_or_else(|| Error::InvalidString(Language::Editorconfig, line.to_string()))?;
+        Ok(ExtractedRegex {
+            flavor: Some(Flavor::Glob),
+            kind: LiteralKind::Line,
+            ..ExtractedRegex::from_literal(Decoded::slice(text, pattern))
+        })
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        extract::{
+            glob::{codeowners_pattern, editorconfig_pattern, gitignore_pattern},
+            ExtractedRegex,
+        },
+    };
+
+    fn pattern(result: Option<Result<ExtractedRegex, Error>>) -> Option<String> {
+        result.and_then(|x| x.ok()).map(|x| x.pattern)
+    }
+
+    #[test]
+    fn test_gitignore_pattern() {
+        assert_eq!(pattern(gitignore_pattern("build/  ")), Some(String::from("build/")));
+        assert_eq!(pattern(gitignore_pattern("a\\  \n")), Some(String::from("a\\ ")));
+        assert_eq!(pattern(gitignore_pattern("a\\\\  ")), Some(String::from("a\\\\")));
+        assert_eq!(pattern(gitignore_pattern("café/*.txt")), Some(String::from("café/*.txt")));
+
+        // Trailing backslash with nothing left to escape
+        assert_eq!(pattern(gitignore_pattern("foo\\")), Some(String::from("foo\\")));
+        assert_eq!(pattern(gitignore_pattern("foo\\\r\n")), Some(String::from("foo\\")));
+
+        // Blank lines and comments have no pattern
+        assert!(gitignore_pattern("   ").unwrap().is_err());
+        assert!(gitignore_pattern("# build").unwrap().is_err());
+    }
+
+    #[test]
+    fn test_codeowners_pattern() {
+        assert_eq!(pattern(codeowners_pattern("/docs/ @owner")), Some(String::from("/docs/")));
+        assert_eq!(pattern(codeowners_pattern("my\\ file.é @a @b")), Some(String::from("my\\ file.é")));
+        assert_eq!(pattern(codeowners_pattern("*.rs\\")), Some(String::from("*.rs\\")));
+    }
+
+    #[test]
+    fn test_editorconfig_pattern() {
+        assert_eq!(pattern(editorconfig_pattern("[*.{js,py}]")), Some(String::from("*.{js,py}")));
+        assert_eq!(pattern(editorconfig_pattern("[é/**]")), Some(String::from("é/**")));
+        assert!(editorconfig_pattern("[*.js").unwrap().is_err());
+        assert!(editorconfig_pattern("indent_style = space").unwrap().is_err());
+    }
+}
@@ -0,0 +1,1135 @@
+use crate::{
+    error::Error,
+    extract::{
+        decoded::{slice_start, Chars, Decoded},
+        Language, LiteralKind,
+    },
+};
+
+/// Single character escapes of Rust strings
+const RUST_ESCAPES: &[(char, char)] = &[
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('0', '\0'),
+    ('\\', '\\'),
+    ('\'', '\''),
+    ('"', '"'),
+];
+
+/// Single character escapes of Python strings
+const PYTHON_ESCAPES: &[(char, char)] = &[
+    ('a', '\x07'),
+    ('b', '\x08'),
+    ('f', '\x0C'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('v', '\x0B'),
+    ('\\', '\\'),
+    ('\'', '\''),
+    ('"', '"'),
+];
+
+/// Single character escapes of Go interpreted strings
+const GO_ESCAPES: &[(char, char)] = &[
+    ('a', '\x07'),
+    ('b', '\x08'),
+    ('f', '\x0C'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('v', '\x0B'),
+    ('\\', '\\'),
+    ('"', '"'),
+];
+
+/// Single character escapes of Java strings
+const JAVA_ESCAPES: &[(char, char)] = &[
+    ('b', '\x08'),
+    ('t', '\t'),
+    ('n', '\n'),
+    ('f', '\x0C'),
+    ('r', '\r'),
+    ('s', ' '),
+    ('\\', '\\'),
+    ('\'', '\''),
+    ('"', '"'),
+];
+
+/// Single character escapes of Kotlin strings
+const KOTLIN_ESCAPES: &[(char, char)] = &[
+    ('b', '\x08'),
+    ('t', '\t'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('$', '$'),
+    ('\\', '\\'),
+    ('\'', '\''),
+    ('"', '"'),
+];
+
+/// Single character escapes of C and C++ strings
+const CPP_ESCAPES: &[(char, char)] = &[
+    ('a', '\x07'),
+    ('b', '\x08'),
+    ('f', '\x0C'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('v', '\x0B'),
+    ('?', '?'),
+    ('\\', '\\'),
+    ('\'', '\''),
+    ('"', '"'),
+];
+
+/// Single character escapes of PHP double quoted strings
+const PHP_ESCAPES: &[(char, char)] = &[
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('v', '\x0B'),
+    ('e', '\x1B'),
+    ('f', '\x0C'),
+    ('$', '$'),
+    ('\\', '\\'),
+    ('"', '"'),
+];
+
+/// Single character escapes of Ruby double quoted strings
+const RUBY_ESCAPES: &[(char, char)] = &[
+    ('a', '\x07'),
+    ('b', '\x08'),
+    ('e', '\x1B'),
+    ('f', '\x0C'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('s', ' '),
+    ('t', '\t'),
+    ('v', '\x0B'),
+    ('\\', '\\'),
+    ('"', '"'),
+];
+
+/// Single character escapes of Perl double quoted strings
+const PERL_ESCAPES: &[(char, char)] = &[
+    ('a', '\x07'),
+    ('e', '\x1B'),
+    ('f', '\x0C'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('\\', '\\'),
+    ('"', '"'),
+];
+
+/// Single character escapes of JavaScript and TypeScript strings
+const JAVASCRIPT_ESCAPES: &[(char, char)] = &[
+    ('b', '\x08'),
+    ('f', '\x0C'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('v', '\x0B'),
+    ('0', '\0'),
+    ('\\', '\\'),
+    ('\'', '\''),
+    ('"', '"'),
+    ('`', '`'),
+    ('$', '$'),
+];
+
+/// Single character escapes of C# strings
+const CSHARP_ESCAPES: &[(char, char)] = &[
+    ('a', '\x07'),
+    ('b', '\x08'),
+    ('f', '\x0C'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('v', '\x0B'),
+    ('0', '\0'),
+    ('\\', '\\'),
+    ('\'', '\''),
+    ('"', '"'),
+];
+
+/// Single character escapes of Swift strings
+const SWIFT_ESCAPES: &[(char, char)] = &[
+    ('0', '\0'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('\\', '\\'),
+    ('\'', '\''),
+    ('"', '"'),
+];
+
+/// Single character escapes of Vim double quoted strings
+const VIM_ESCAPES: &[(char, char)] = &[
+    ('b', '\x08'),
+    ('e', '\x1B'),
+    ('f', '\x0C'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('\\', '\\'),
+    ('"', '"'),
+];
+
+/// Single character escapes of Lua strings, where a backslash before a line break keeps it
+const LUA_ESCAPES: &[(char, char)] = &[
+    ('a', '\x07'),
+    ('b', '\x08'),
+    ('f', '\x0C'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('v', '\x0B'),
+    ('\\', '\\'),
+    ('"', '"'),
+    ('\'', '\''),
+    ('\n', '\n'),
+];
+
+/// Single character escapes of awk strings
+const AWK_ESCAPES: &[(char, char)] = &[
+    ('a', '\x07'),
+    ('b', '\x08'),
+    ('f', '\x0C'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('v', '\x0B'),
+    ('\\', '\\'),
+    ('"', '"'),
+    ('/', '/'),
+];
+
+/// Single character escapes of PostgreSQL `E'...'` strings
+const SQL_ESCAPES: &[(char, char)] = &[
+    ('b', '\x08'),
+    ('f', '\x0C'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('\\', '\\'),
+    ('\'', '\''),
+];
+
+/// Single character escapes of Elixir strings and sigils
+const ELIXIR_ESCAPES: &[(char, char)] = &[
+    ('0', '\0'),
+    ('a', '\x07'),
+    ('b', '\x08'),
+    ('d', '\x7F'),
+    ('e', '\x1B'),
+    ('f', '\x0C'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('s', ' '),
+    ('t', '\t'),
+    ('v', '\x0B'),
+    ('\\', '\\'),
+    ('"', '"'),
+    ('\'', '\''),
+];
+
+/// Single character escapes of Erlang strings
+const ERLANG_ESCAPES: &[(char, char)] = &[
+    ('b', '\x08'),
+    ('d', '\x7F'),
+    ('e', '\x1B'),
+    ('f', '\x0C'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('s', ' '),
+    ('t', '\t'),
+    ('v', '\x0B'),
+    ('\\', '\\'),
+    ('"', '"'),
+    ('\'', '\''),
+];
+
+/// Single character escapes of Haskell strings
+const HASKELL_ESCAPES: &[(char, char)] = &[
+    ('a', '\x07'),
+    ('b', '\x08'),
+    ('f', '\x0C'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('v', '\x0B'),
+    ('\\', '\\'),
+    ('"', '"'),
+    ('\'', '\''),
+];
+
+/// Names of the control characters which Haskell strings escape as `\ESC`, in order of
+/// their code
+const HASKELL_CONTROL_NAMES: &[&str] = &[
+    "NUL", "SOH", "STX", "ETX", "EOT", "ENQ", "ACK", "BEL", "BS", "HT", "LF", "VT", "FF", "CR", "SO", "SI", "DLE",
+    "DC1", "DC2", "DC3", "DC4", "NAK", "SYN", "ETB", "CAN", "EM", "SUB", "ESC", "FS", "GS", "RS", "US", "SP",
+];
+
+/// Read exactly `count` hexadecimal digits as a character
+fn hex_char(chars: &mut Chars, count: usize) -> Option<char> {
+    let digits: String = (0..count)
+        .map_while(|_| chars.next_if(|(c, _)| c.is_ascii_hexdigit()).map(|(c, _)| c))
+        .collect();
+    if digits.len() != count {
+        return None;
+    }
+    u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32)
+}
+
+/// Decode the escapes of a non-raw string body
+///
+/// Single character escapes are looked up in `simple`, `\x`, `\u{...}`, `\u` and `\U`
+/// escapes are decoded from hexadecimal and other digits are read as up to three
+/// octal digits. A backslash before a newline continues the
+/// string on the next line without its leading whitespace. Unknown escapes are kept
+/// as written, so regular expression escapes such as `\d` pass through unchanged.
+pub fn decode_escapes(text: &Decoded, simple: &[(char, char)]) -> Option<Decoded> {
+    let mut decoded = text.cleared();
+    let mut chars = text.chars();
+    while let Some((c, offset)) = chars.next() {
+        if c != '\\' {
+            decoded.push(c, offset);
+            continue;
+        }
+        let (escape, escape_offset) = chars.next()?;
+        match simple.iter().find(|(from, _)| *from == escape) {
+            Some((_, to)) => decoded.push_escape(*to, offset),
+            None => match escape {
+                'x' => decoded.push_escape(hex_char(&mut chars, 2)?, offset),
+                'u' if chars.next_if(|(c, _)| *c == '{').is_some() => {
+                    let mut digits = String::new();
+                    loop {
+                        match chars.next()? {
+                            ('}', _) => break,
+                            (c, _) => digits.push(c),
+                        }
+                    }
+                    let code = u32::from_str_radix(&digits.replace('_', ""), 16).ok()?;
+                    decoded.push_escape(char::from_u32(code)?, offset);
+                }
+                'u' => decoded.push_escape(hex_char(&mut chars, 4)?, offset),
+                'U' => decoded.push_escape(hex_char(&mut chars, 8)?, offset),
+                '0'..='7' => {
+                    let mut code = escape.to_digit(8)?;
+                    for _ in 0..2 {
+                        match chars.next_if(|(c, _)| c.is_digit(8)) {
+                            Some((digit, _)) => code = code * 8 + digit.to_digit(8)?,
+                            None => break,
+                        }
+                    }
+                    decoded.push_escape(char::from_u32(code)?, offset);
+                }
+                '\n' => {
+                    decoded.escaped = true;
+                    while chars.next_if(|(c, _)| c.is_whitespace()).is_some() {}
+                }
+                other => {
+                    decoded.push('\\', offset);
+                    decoded.push(other, escape_offset);
+                }
+            },
+        }
+    }
+    Some(decoded)
+}
+
+/// Parse a Rust string literal, including byte strings and raw strings with any number
+/// of `#` delimiters
+pub fn rust_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Rust, text.to_string());
+
+    let rest = text.strip_prefix(['b', 'c']).unwrap_or(text);
+    let (raw, rest) = match rest.strip_prefix('r') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    let hashes = rest.len() - rest.trim_start_matches('#').len();
+    if hashes > 0 && !raw {
+        return Err(invalid());
+    }
+    let closing = format!("\"{}", "#".repeat(hashes));
+    let body = rest[hashes..]
+        .strip_prefix('"')
+        .and_then(|body| body.strip_suffix(closing.as_str()))
+        .filter(|body| !raw || !body.contains(closing.as_str()))
+        .ok_or_else(invalid)?;
+
+    if raw {
+        Ok(Decoded::raw(text, body))
+    } else {
+        decode_escapes(&Decoded::slice(text, body), RUST_ESCAPES).ok_or_else(invalid)
+    }
+}
+
+/// Parse a Go string literal, either an interpreted `"..."` string or a raw string
+/// between backticks
+pub fn go_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Go, text.to_string());
+    if let Some(body) = text.strip_prefix('`').and_then(|x| x.strip_suffix('`')) {
+        return Ok(Decoded::raw(text, body));
+    }
+    let body = text
+        .strip_prefix('"')
+        .and_then(|x| x.strip_suffix('"'))
+        .ok_or_else(invalid)?;
+    decode_escapes(&Decoded::slice(text, body), GO_ESCAPES).ok_or_else(invalid)
+}
+
+/// Parse a Java string literal or `"""` text block
+pub fn java_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Java, text.to_string());
+    let body = match text.strip_prefix("\"\"\"").and_then(|x| x.strip_suffix("\"\"\"")) {
+        // Text blocks start on the line after the opening delimiter
+        Some(body) => body.split_once('\n').map_or(body, |(_, x)| x),
+        None => text
+            .strip_prefix('"')
+            .and_then(|x| x.strip_suffix('"'))
+            .ok_or_else(invalid)?,
+    };
+    decode_escapes(&Decoded::slice(text, body), JAVA_ESCAPES).ok_or_else(invalid)
+}
+
+/// Parse a JavaScript or TypeScript string literal or template string, where templates
+/// may not contain `${expression}` substitutions
+pub fn javascript_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Javascript, text.to_string());
+    if let Some(body) = text.strip_prefix('`').and_then(|x| x.strip_suffix('`')) {
+        templates(body, false, &['$'], false)?;
+        return decode_escapes(&Decoded::slice(text, body), JAVASCRIPT_ESCAPES).ok_or_else(invalid);
+    }
+    let body = ['"', '\'']
+        .iter()
+        .find_map(|quote| text.strip_prefix(*quote).and_then(|x| x.strip_suffix(*quote)))
+        .ok_or_else(invalid)?;
+    decode_escapes(&Decoded::slice(text, body), JAVASCRIPT_ESCAPES).ok_or_else(invalid)
+}
+
+/// Parse a C# string literal, including `@"..."` verbatim strings which escape quotes by
+/// doubling them, and `$"..."` interpolated strings without any interpolated values
+pub fn csharp_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Csharp, text.to_string());
+    let (prefix, rest) = text.split_once('"').ok_or_else(invalid)?;
+    if !["", "@", "$", "$@", "@$"].contains(&prefix) {
+        return Err(invalid());
+    }
+    let body = rest.strip_suffix('"').ok_or_else(invalid)?;
+    let value = match prefix.contains('@') {
+        true => Decoded::raw(text, body).replace("\"\"", '"'),
+        false => decode_escapes(&Decoded::slice(text, body), CSHARP_ESCAPES).ok_or_else(invalid)?,
+    };
+    match prefix.contains('$') {
+        true => format_text(&value),
+        false => Ok(value),
+    }
+}
+
+/// Parse a Swift string literal, including `"""` multi-line strings and `#"..."#`
+/// extended delimiters where escapes are written as `\#n`
+pub fn swift_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Swift, text.to_string());
+    let hashes = text.len() - text.trim_start_matches('#').len();
+    let delimiter = "#".repeat(hashes);
+    let quoted = text[hashes..].strip_suffix(delimiter.as_str()).ok_or_else(invalid)?;
+
+    let body = match quoted.strip_prefix("\"\"\"").and_then(|x| x.strip_suffix("\"\"\"")) {
+        Some(body) => {
+            // Lines start after the opening delimiter and lose the indentation of the
+            // closing delimiter
+            let body = body.split_once('\n').map_or(body, |(_, x)| x);
+            let (body, indent) = body.rsplit_once('\n').unwrap_or(("", body));
+            let mut lines = Decoded::slice(text, &body[..0]);
+            for (idx, line) in body.lines().enumerate() {
+                if idx > 0 {
+                    let line_start = slice_start(text, line).unwrap_or_default();
+                    lines.push('\n', line_start - 1);
+                }
+                lines.append(Decoded::slice(text, line.strip_prefix(indent).unwrap_or(line)));
+            }
+            lines
+        }
+        None => {
+            let body = quoted
+                .strip_prefix('"')
+                .and_then(|x| x.strip_suffix('"'))
+                .ok_or_else(invalid)?;
+            Decoded::slice(text, body)
+        }
+    };
+
+    // Backslashes only start an escape when followed by the delimiter's hashes
+    let mut escaped = body.cleared();
+    let mut delimited = false;
+    let mut chars = body.chars();
+    while let Some((c, offset)) = chars.next() {
+        if c != '\\' {
+            escaped.push(c, offset);
+            continue;
+        }
+        let mut after = chars.clone();
+        match (0..hashes).all(|_| after.next().is_some_and(|(c, _)| c == '#')) {
+            true => {
+                escaped.push('\\', offset);
+                delimited = true;
+                chars = after;
+            }
+            false => {
+                escaped.push('\\', offset);
+                escaped.push('\\', offset);
+            }
+        }
+    }
+
+    // Interpolations such as `\(name)` are only known at runtime
+    let value = escaped.value();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' && chars.next() == Some('(') {
+            let field: String = chars.take_while(|c| *c != ')').collect();
+            return Err(Error::FormatField(field));
+        }
+    }
+    let mut decoded = decode_escapes(&escaped, SWIFT_ESCAPES).ok_or_else(invalid)?;
+    // Backslashes doubled above are written once in strings with extended delimiters
+    if hashes > 0 {
+        decoded.escaped = delimited;
+        decoded.kind = LiteralKind::RawString;
+    }
+    Ok(decoded)
+}
+
+/// Reject templates such as Kotlin's `$name` and `${expression}` or Ruby's
+/// `#{expression}`, whose value is only known at runtime
+///
+/// Each sigil starts a template when followed by a brace, and also when followed by a
+/// name if `names` is set
+pub fn templates(body: &str, raw: bool, sigils: &[char], names: bool) -> Result<(), Error> {
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' && !raw {
+            chars.next();
+            continue;
+        }
+        if !sigils.contains(&c) {
+            continue;
+        }
+        if chars.next_if_eq(&'{').is_some() {
+            let field: String = chars.by_ref().take_while(|c| *c != '}').collect();
+            return Err(Error::FormatField(field));
+        }
+        if names && chars.peek().is_some_and(|c| c.is_alphabetic() || *c == '_') {
+            let field: String = chars.take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+            return Err(Error::FormatField(field));
+        }
+    }
+    Ok(())
+}
+
+/// Parse a Kotlin string literal or `"""` raw string
+pub fn kotlin_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Kotlin, text.to_string());
+    if let Some(body) = text.strip_prefix("\"\"\"").and_then(|x| x.strip_suffix("\"\"\"")) {
+        // Raw strings can only contain a dollar sign through a template
+        let body = Decoded::raw(text, body).replace("${'$'}", '$');
+        templates(&body.value(), true, &['$'], true)?;
+        return Ok(body);
+    }
+    let body = text
+        .strip_prefix('"')
+        .and_then(|x| x.strip_suffix('"'))
+        .ok_or_else(invalid)?;
+    templates(body, false, &['$'], true)?;
+    decode_escapes(&Decoded::slice(text, body), KOTLIN_ESCAPES).ok_or_else(invalid)
+}
+
+/// Parse a PHP single or double quoted string, where single quoted strings only escape
+/// backslashes and quotes
+pub fn php_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Php, text.to_string());
+    if let Some(body) = text.strip_prefix('\'').and_then(|x| x.strip_suffix('\'')) {
+        return Ok(single_quoted(text, body));
+    }
+    let body = text
+        .strip_prefix('"')
+        .and_then(|x| x.strip_suffix('"'))
+        .ok_or_else(invalid)?;
+    templates(body, false, &['$'], true)?;
+    decode_escapes(&Decoded::slice(text, body), PHP_ESCAPES).ok_or_else(invalid)
+}
+
+/// Read the body of a single quoted string in which only a backslash and a quote are
+/// escaped, as in PHP, Ruby and Perl
+fn single_quoted(text: &str, body: &str) -> Decoded {
+    let body = Decoded::slice(text, body);
+    let mut value = body.cleared();
+    let mut chars = body.chars();
+    while let Some((c, offset)) = chars.next() {
+        match chars.next_if(|(x, _)| c == '\\' && ['\\', '\''].contains(x)) {
+            Some((escaped, _)) => value.push_escape(escaped, offset),
+            None => value.push(c, offset),
+        }
+    }
+    value
+}
+
+/// Parse a Ruby single or double quoted string, where double quoted strings drop the
+/// backslash of unknown escapes and may not interpolate `#{...}`
+pub fn ruby_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Ruby, text.to_string());
+    if let Some(body) = text.strip_prefix('\'').and_then(|x| x.strip_suffix('\'')) {
+        return Ok(single_quoted(text, body));
+    }
+    let body = text
+        .strip_prefix('"')
+        .and_then(|x| x.strip_suffix('"'))
+        .ok_or_else(invalid)?;
+    templates(body, false, &['#'], false)?;
+    let known = known_escapes(&Decoded::slice(text, body), RUBY_ESCAPES, "xu01234567").ok_or_else(invalid)?;
+    decode_escapes(&known, RUBY_ESCAPES).ok_or_else(invalid)
+}
+
+/// Parse a Perl single or double quoted string, where double quoted strings drop the
+/// backslash of unknown escapes, write character codes of any length as `\x{263A}` and
+/// may not interpolate variables such as `$name` or `@list`
+pub fn perl_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Perl, text.to_string());
+    if let Some(body) = text.strip_prefix('\'').and_then(|x| x.strip_suffix('\'')) {
+        return Ok(single_quoted(text, body));
+    }
+    let body = text
+        .strip_prefix('"')
+        .and_then(|x| x.strip_suffix('"'))
+        .ok_or_else(invalid)?;
+    templates(body, false, &['$', '@'], true)?;
+    // Braced character codes are resolved first, as other languages do not have them
+    let body = Decoded::slice(text, body);
+    let mut resolved = body.cleared();
+    let mut chars = body.chars();
+    while let Some((c, offset)) = chars.next() {
+        if c != '\\' {
+            resolved.push(c, offset);
+            continue;
+        }
+        let decoded = match chars.next().ok_or_else(invalid)? {
+            ('x', _) if chars.next_if(|(x, _)| *x == '{').is_some() => {
+                let digits: String = chars.by_ref().map(|(c, _)| c).take_while(|c| *c != '}').collect();
+                u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32).ok_or_else(invalid)?
+            }
+            (other, other_offset) => {
+                resolved.push('\\', offset);
+                resolved.push(other, other_offset);
+                continue;
+            }
+        };
+        // Keep a decoded backslash from starting another escape
+        if decoded == '\\' {
+            resolved.push('\\', offset);
+        }
+        resolved.push_escape(decoded, offset);
+    }
+    let known = known_escapes(&resolved, PERL_ESCAPES, "x01234567").ok_or_else(invalid)?;
+    decode_escapes(&known, PERL_ESCAPES).ok_or_else(invalid)
+}
+
+/// Parse a Vim single or double quoted string, where single quoted strings escape a quote
+/// by doubling it and double quoted strings drop the backslash of unknown escapes, so
+/// `"\\d"` is needed for the pattern `\d`
+pub fn vim_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Vim, text.to_string());
+    if let Some(body) = text.strip_prefix('\'').and_then(|x| x.strip_suffix('\'')) {
+        return Ok(Decoded::slice(text, body).replace("''", '\''));
+    }
+    let body = text
+        .strip_prefix('"')
+        .and_then(|x| x.strip_suffix('"'))
+        .ok_or_else(invalid)?;
+    let known = known_escapes(&Decoded::slice(text, body), VIM_ESCAPES, "xuU01234567").ok_or_else(invalid)?;
+    decode_escapes(&known, VIM_ESCAPES).ok_or_else(invalid)
+}
+
+/// Drop the backslash of escapes which are neither in `simple` nor start with one of the
+/// `numeric` characters, for strings which read an unknown escape as the character
+fn known_escapes(body: &Decoded, simple: &[(char, char)], numeric: &str) -> Option<Decoded> {
+    let mut known = body.cleared();
+    let mut chars = body.chars();
+    while let Some((c, offset)) = chars.next() {
+        if c != '\\' {
+            known.push(c, offset);
+            continue;
+        }
+        match chars.next()? {
+            (escape, escape_offset) if simple.iter().any(|(x, _)| *x == escape) || numeric.contains(escape) => {
+                known.push('\\', offset);
+                known.push(escape, escape_offset);
+            }
+            (other, _) => known.push_escape(other, offset),
+        }
+    }
+    Some(known)
+}
+
+/// Parse a Lua quoted string, or a long string such as `[[...]]` or `[==[...]==]` which
+/// has no escapes and drops a line break straight after its opening bracket
+pub fn lua_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Lua, text.to_string());
+    if let Some(rest) = text.strip_prefix('[') {
+        let equals = rest.len() - rest.trim_start_matches('=').len();
+        let closing = format!("]{}]", "=".repeat(equals));
+        let body = rest[equals..]
+            .strip_prefix('[')
+            .and_then(|x| x.strip_suffix(closing.as_str()))
+            .ok_or_else(invalid)?;
+        let body = body.strip_prefix("\r\n").or_else(|| body.strip_prefix('\n')).unwrap_or(body);
+        return Ok(Decoded::raw(text, body));
+    }
+
+    let body = ['"', '\'']
+        .into_iter()
+        .find_map(|quote| text.strip_prefix(quote).and_then(|x| x.strip_suffix(quote)))
+        .ok_or_else(invalid)?;
+    // Decimal escapes such as `\65` and `\z`, which skips the whitespace after it, are
+    // resolved first, as other languages do not have them
+    let body = Decoded::slice(text, body);
+    let mut known = body.cleared();
+    let mut chars = body.chars();
+    while let Some((c, offset)) = chars.next() {
+        if c != '\\' {
+            known.push(c, offset);
+            continue;
+        }
+        match chars.next().ok_or_else(invalid)? {
+            (digit @ '0'..='9', _) => {
+                let mut code = digit.to_digit(10).expect("Current char is a digit");
+                for _ in 0..2 {
+                    match chars.next_if(|(x, _)| x.is_ascii_digit()) {
+                        Some((digit, _)) => code = code * 10 + digit.to_digit(10).expect("Current char is a digit"),
+                        None => break,
+                    }
+                }
+                let decoded = char::from_u32(code).filter(|_| code < 256).ok_or_else(invalid)?;
+                // Keep a decoded backslash from starting another escape
+                if decoded == '\\' {
+                    known.push('\\', offset);
+                }
+                known.push_escape(decoded, offset);
+            }
+            ('z', _) => {
+                known.escaped = true;
+                while chars.next_if(|(x, _)| x.is_whitespace()).is_some() {}
+            }
+            (other, other_offset) => {
+                known.push('\\', offset);
+                known.push(other, other_offset);
+            }
+        }
+    }
+    decode_escapes(&known, LUA_ESCAPES).ok_or_else(invalid)
+}
+
+/// Parse an awk string, as used for dynamic regular expressions such as
+/// `match($0, "a\\.b")`
+pub fn awk_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Awk, text.to_string());
+    let body = text.strip_prefix('"').and_then(|x| x.strip_suffix('"')).ok_or_else(invalid)?;
+    decode_escapes(&Decoded::slice(text, body), AWK_ESCAPES).ok_or_else(invalid)
+}
+
+/// Parse an Elixir string, charlist or `"""` heredoc, where unknown escapes drop their
+/// backslash and `#{...}` interpolations are rejected
+pub fn elixir_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Elixir, text.to_string());
+    let body = match text.strip_prefix("\"\"\"").and_then(|x| x.strip_suffix("\"\"\"")) {
+        // Heredocs start on the line after the opening delimiter
+        Some(body) => body.split_once('\n').map_or(body, |(_, x)| x).trim_end_matches([' ', '\t']),
+        None => ['"', '\'']
+            .into_iter()
+            .find_map(|quote| text.strip_prefix(quote).and_then(|x| x.strip_suffix(quote)))
+            .ok_or_else(invalid)?,
+    };
+    templates(body, false, &['#'], false)?;
+    let known = known_escapes(&Decoded::slice(text, body), ELIXIR_ESCAPES, "xu").ok_or_else(invalid)?;
+    decode_escapes(&known, ELIXIR_ESCAPES).ok_or_else(invalid)
+}
+
+/// Parse an Erlang string, where `\^a` is a control character, `\x{...}` a character
+/// code of any length and unknown escapes drop their backslash, so a pattern passed to
+/// `re:run` needs `"\\d"` for `\d`
+pub fn erlang_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Erlang, text.to_string());
+    let body = text.strip_prefix('"').and_then(|x| x.strip_suffix('"')).ok_or_else(invalid)?;
+    // Escapes other languages do not have are resolved first
+    let body = Decoded::slice(text, body);
+    let mut resolved = body.cleared();
+    let mut chars = body.chars();
+    while let Some((c, offset)) = chars.next() {
+        if c != '\\' {
+            resolved.push(c, offset);
+            continue;
+        }
+        let decoded = match chars.next().ok_or_else(invalid)? {
+            ('^', _) => chars.next().and_then(|(x, _)| char::from_u32(x as u32 % 32)).ok_or_else(invalid)?,
+            ('x', _) if chars.next_if(|(x, _)| *x == '{').is_some() => {
+                let digits: String = chars.by_ref().map(|(c, _)| c).take_while(|c| *c != '}').collect();
+                u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32).ok_or_else(invalid)?
+            }
+            (other, other_offset) => {
+                resolved.push('\\', offset);
+                resolved.push(other, other_offset);
+                continue;
+            }
+        };
+        // Keep a decoded backslash from starting another escape
+        if decoded == '\\' {
+            resolved.push('\\', offset);
+        }
+        resolved.push_escape(decoded, offset);
+    }
+    let known = known_escapes(&resolved, ERLANG_ESCAPES, "x01234567").ok_or_else(invalid)?;
+    decode_escapes(&known, ERLANG_ESCAPES).ok_or_else(invalid)
+}
+
+/// Parse a Haskell string, with decimal `\65`, hexadecimal `\x41` and octal `\o101`
+/// escapes of any length, control characters such as `\^A` and `\ESC`, the empty escape
+/// `\&` and gaps of whitespace between two backslashes
+///
+/// Unknown escapes are invalid, so patterns are written as `"\\d+"`.
+pub fn haskell_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Haskell, text.to_string());
+    let mut rest = text.strip_prefix('"').and_then(|x| x.strip_suffix('"')).ok_or_else(invalid)?;
+    let mut value = Decoded::slice(text, &rest[..0]);
+    while let Some(idx) = rest.find('\\') {
+        value.append(Decoded::slice(text, &rest[..idx]));
+        let offset = slice_start(text, rest).ok_or_else(invalid)? + idx;
+        // Every backslash starts an escape, as unknown escapes are invalid
+        value.escaped = true;
+        rest = &rest[idx + 1..];
+        let escape = rest.chars().next().ok_or_else(invalid)?;
+        if let Some((_, to)) = HASKELL_ESCAPES.iter().find(|(x, _)| *x == escape) {
+            value.push(*to, offset);
+            rest = &rest[1..];
+            continue;
+        }
+        let radix = match escape {
+            'x' => 16,
+            'o' => 8,
+            '0'..='9' => 10,
+            '&' => {
+                rest = &rest[1..];
+                continue;
+            }
+            '^' => {
+                let control = rest[1..].chars().next().filter(|x| ('@'..='_').contains(x)).ok_or_else(invalid)?;
+                value.push(char::from_u32(control as u32 - 64).ok_or_else(invalid)?, offset);
+                rest = &rest[2..];
+                continue;
+            }
+            // A gap continues the string after the next backslash
+            c if c.is_whitespace() => {
+                rest = rest.trim_start().strip_prefix('\\').ok_or_else(invalid)?;
+                continue;
+            }
+            _ => {
+                let (code, name) = HASKELL_CONTROL_NAMES
+                    .iter()
+                    .enumerate()
+                    .chain([(127, &"DEL")])
+                    .filter(|(_, name)| rest.starts_with(**name))
+                    .max_by_key(|(_, name)| name.len())
+                    .ok_or_else(invalid)?;
+                value.push(char::from_u32(code as u32).ok_or_else(invalid)?, offset);
+                rest = &rest[name.len()..];
+                continue;
+            }
+        };
+        let digits = if radix == 10 { rest } else { &rest[1..] };
+        let len = digits.find(|c: char| !c.is_digit(radix)).unwrap_or(digits.len());
+        let code = u32::from_str_radix(&digits[..len], radix).ok().and_then(char::from_u32).ok_or_else(invalid)?;
+        value.push(code, offset);
+        rest = &digits[len..];
+    }
+    value.append(Decoded::slice(text, rest));
+    Ok(value)
+}
+
+/// Parse an SQL string, where a quote is escaped by doubling it, including PostgreSQL's
+/// `E'...'` strings with backslash escapes and dollar quoted `$tag$...$tag$` strings
+pub fn sql_string(text: &str) -> Result<Decoded, Error> {
+    let invalid = || Error::InvalidString(Language::Sql, text.to_string());
+    if let Some(rest) = text.strip_prefix('$') {
+        let tag = &text[..rest.find('$').ok_or_else(invalid)? + 2];
+        let body = text[tag.len()..].strip_suffix(tag).ok_or_else(invalid)?;
+        return Ok(Decoded::raw(text, body));
+    }
+    let (escapes, rest) = match text.strip_prefix(['E', 'e']) {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix(['N', 'n']).unwrap_or(text)),
+    };
+    let body = rest
+        .strip_prefix('\'')
+        .and_then(|x| x.strip_suffix('\''))
+        .ok_or_else(invalid)?;
+    let body = Decoded::slice(text, body).replace("''", '\'');
+    match escapes {
+        true => decode_escapes(&body, SQL_ESCAPES).ok_or_else(invalid),
+        false => Ok(body),
+    }
+}
+
+/// Split the first C++ string literal from `text`, a part of the source, returning its
+/// decoded value and the remaining text
+fn cpp_literal<'t>(source: &str, text: &'t str) -> Result<(Decoded, &'t str), Error> {
+    let invalid = || Error::InvalidString(Language::Cpp, text.to_string());
+
+    let rest = ["u8", "u", "U", "L"]
+        .iter()
+        .find_map(|prefix| text.strip_prefix(prefix))
+        .unwrap_or(text);
+
+    // Raw strings end at the first `)delimiter"`
+    if let Some(rest) = rest.strip_prefix("R\"") {
+        let (delimiter, body) = rest.split_once('(').ok_or_else(invalid)?;
+        let closing = format!("){}\"", delimiter);
+        let end = body.find(&closing).ok_or_else(invalid)?;
+        return Ok((Decoded::raw(source, &body[..end]), &body[end + closing.len()..]));
+    }
+
+    let body = rest.strip_prefix('"').ok_or_else(invalid)?;
+    let mut chars = body.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '"' => {
+                let value = decode_escapes(&Decoded::slice(source, &body[..idx]), CPP_ESCAPES).ok_or_else(invalid)?;
+                return Ok((value, &body[idx + 1..]));
+            }
+            _ => (),
+        }
+    }
+    Err(invalid())
+}
+
+/// Add a literal to the value of adjacent literals, which are a raw string only if all of
+/// them are
+fn concatenate(value: &mut Option<Decoded>, literal: Decoded) {
+    match value {
+        Some(value) => {
+            if literal.kind != value.kind {
+                value.kind = LiteralKind::String;
+            }
+            value.append(literal);
+        }
+        None => *value = Some(literal),
+    }
+}
+
+/// Parse a C or C++ string literal with any encoding prefix, including raw strings
+/// with custom delimiters and adjacent literals such as `"a" "b"`
+pub fn cpp_string(text: &str) -> Result<Decoded, Error> {
+    let mut value = None;
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        let (literal, remaining) = cpp_literal(text, rest)?;
+        concatenate(&mut value, literal);
+        rest = remaining.trim_start();
+        // Comments may separate the literals
+        loop {
+            if rest.starts_with("//") {
+                rest = rest.split_once('\n').map_or("", |(_, x)| x).trim_start();
+            } else if let Some(comment) = rest.strip_prefix("/*") {
+                rest = comment.split_once("*/").map_or("", |(_, x)| x).trim_start();
+            } else {
+                break;
+            }
+        }
+    }
+    value.ok_or_else(|| Error::InvalidString(Language::Cpp, text.to_string()))
+}
+
+/// Split the first Python string literal from `text`, a part of the source, returning its
+/// decoded value and the remaining text
+fn python_literal<'t>(source: &str, text: &'t str) -> Result<(Decoded, &'t str), Error> {
+    let invalid = || Error::InvalidString(Language::Python, text.to_string());
+
+    let prefix_len = text.find(['"', '\'']).ok_or_else(invalid)?;
+    let prefix = text[..prefix_len].to_lowercase();
+    if !["", "r", "u", "b", "f", "br", "rb", "fr", "rf"].contains(&prefix.as_str()) {
+        return Err(invalid());
+    }
+    let raw = prefix.contains('r');
+    let format = prefix.contains('f');
+
+    // Find the closing quote, skipping escaped characters which never end a string
+    let rest = &text[prefix_len..];
+    let quote = if rest.starts_with("\"\"\"") || rest.starts_with("'''") {
+        &rest[..3]
+    } else {
+        &rest[..1]
+    };
+    let body_start = prefix_len + quote.len();
+    let mut body_end = None;
+    let mut chars = text[body_start..].char_indices();
+    while let Some((idx, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if text[body_start + idx..].starts_with(quote) {
+            body_end = Some(body_start + idx);
+            break;
+        }
+    }
+    let body_end = body_end.ok_or_else(invalid)?;
+    let body = &text[body_start..body_end];
+
+    let mut value = if raw {
+        Decoded::raw(source, body)
+    } else {
+        decode_escapes(&Decoded::slice(source, body), PYTHON_ESCAPES).ok_or_else(invalid)?
+    };
+    if format {
+        value = format_text(&value)?;
+    }
+    Ok((value, &text[body_end + quote.len()..]))
+}
+
+/// Unescape the doubled braces of a Python f-string or C# interpolated string, rejecting
+/// replacement fields since their value is not known until runtime
+fn format_text(text: &Decoded) -> Result<Decoded, Error> {
+    let mut unescaped = text.cleared();
+    let mut chars = text.chars();
+    while let Some((c, offset)) = chars.next() {
+        match c {
+            '{' | '}' if chars.next_if(|(x, _)| *x == c).is_some() => unescaped.push_escape(c, offset),
+            '{' => {
+                let field: String = chars.by_ref().map(|(c, _)| c).take_while(|c| *c != '}').collect();
+                return Err(Error::FormatField(field));
+            }
+            c => unescaped.push(c, offset),
+        }
+    }
+    Ok(unescaped)
+}
+
+/// Parse a Python string literal with any prefix and quote style, joining implicitly
+/// concatenated literals such as `"a" "b"`
+pub fn python_string(text: &str) -> Result<Decoded, Error> {
+    let mut value = None;
+    let mut rest = text.trim();
+    // Parenthesised concatenations are sent with their brackets
+    if let Some(inner) = rest.strip_prefix('(').and_then(|x| x.strip_suffix(')')) {
+        rest = inner.trim();
+    }
+    while !rest.is_empty() {
+        let (literal, remaining) = python_literal(text, rest)?;
+        concatenate(&mut value, literal);
+        rest = remaining.trim_start();
+        // Comments and line continuations may separate the literals
+        loop {
+            if rest.starts_with('#') {
+                rest = rest.split_once('\n').map_or("", |(_, x)| x).trim_start();
+            } else if let Some(remaining) = rest.strip_prefix('\\') {
+                rest = remaining.trim_start();
+            } else {
+                break;
+            }
+        }
+    }
+    value.ok_or_else(|| Error::InvalidString(Language::Python, text.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::extract::{
+        decoded::Decoded,
+        literal::{
+            awk_string, cpp_string, csharp_string, decode_escapes, elixir_string, erlang_string, go_string,
+            haskell_string, java_string, javascript_string, kotlin_string, lua_string, perl_string, php_string,
+            python_string, ruby_string, rust_string, sql_string, swift_string, vim_string, RUST_ESCAPES,
+        },
+        LiteralParser,
+    };
+
+    fn value(parser: LiteralParser, text: &str) -> Option<String> {
+        parser(text).ok().map(|x| x.value())
+    }
+
+    #[test]
+    fn test_decode_escapes() {
+        let decode = |text: &str| decode_escapes(&Decoded::slice(text, text), RUST_ESCAPES).map(|x| x.value());
+        assert_eq!(decode("a\\tb\\d"), Some(String::from("a\tb\\d")));
+        assert_eq!(decode("\\x41\\u{e9}\\u00e9\\101"), Some(String::from("AééA")));
+        assert_eq!(decode("é\\\n   é"), Some(String::from("éé")));
+
+        // Escapes cut short by the end of the body
+        assert_eq!(decode("a\\"), None);
+        assert_eq!(decode("\\x4"), None);
+        assert_eq!(decode("\\u{e9"), None);
+        assert_eq!(decode("\\u{110000}"), None);
+        assert_eq!(decode("\\xé"), None);
+    }
+
+    #[test]
+    fn test_unterminated() {
+        let parsers: &[LiteralParser] = &[
+            rust_string, go_string, java_string, javascript_string, csharp_string, swift_string, kotlin_string,
+            php_string, ruby_string, perl_string, vim_string, lua_string, awk_string, elixir_string,
+            erlang_string, haskell_string, sql_string, cpp_string, python_string,
+        ];
+        for (i, parser) in parsers.iter().enumerate() {
+            for text in ["", "\"", "'", "\"a", "'a", "\"a\\\"", "\"é", "\"a\\", "\\"] {
+                assert!(parser(text).is_err(), "{} {:?}", i, text);
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_ascii() {
+        let parsers: &[LiteralParser] = &[
+            rust_string, go_string, java_string, javascript_string, csharp_string, swift_string, kotlin_string,
+            php_string, ruby_string, perl_string, vim_string, lua_string, awk_string, elixir_string,
+            erlang_string, haskell_string, cpp_string, python_string,
+        ];
+        for parser in parsers {
+            let decoded = parser("\"é\\tü\"").unwrap();
+            assert_eq!(decoded.value(), "é\tü");
+            assert_eq!(decoded.offsets(), vec![1, 3, 5, 7]);
+        }
+        assert_eq!(value(sql_string, "'é''ü'"), Some(String::from("é'ü")));
+    }
+
+    #[test]
+    fn test_raw_strings() {
+        assert_eq!(value(rust_string, "r#\"a\"b\\\"#"), Some(String::from("a\"b\\")));
+        assert_eq!(value(rust_string, "r#\"a\"#\"#"), None);
+        assert_eq!(value(rust_string, "#\"a\"#"), None);
+        assert_eq!(value(python_string, "r'a\\'b'"), Some(String::from("a\\'b")));
+        assert_eq!(value(python_string, "r'a\\'"), None);
+        assert_eq!(value(go_string, "`a\\`"), Some(String::from("a\\")));
+        assert_eq!(value(cpp_string, "R\"x(a)\")x\""), Some(String::from("a)\"")));
+        assert_eq!(value(cpp_string, "R\"x(a)y\""), None);
+        assert_eq!(value(csharp_string, "@\"a\\\"\"\""), Some(String::from("a\\\"")));
+        assert_eq!(value(swift_string, "#\"a\\d\"#"), Some(String::from("a\\d")));
+        assert_eq!(value(lua_string, "[==[a]]b]==]"), Some(String::from("a]]b")));
+        assert_eq!(value(lua_string, "[==[a]=]"), None);
+    }
+
+    #[test]
+    fn test_templates() {
+        assert_eq!(value(javascript_string, "`a${b}`"), None);
+        assert_eq!(value(javascript_string, "`a\\${b}`"), Some(String::from("a${b}")));
+        assert_eq!(value(kotlin_string, "\"a${b}\""), None);
+        assert_eq!(value(kotlin_string, "\"a$\""), Some(String::from("a$")));
+        assert_eq!(value(ruby_string, "\"a#{b}\""), None);
+        assert_eq!(value(ruby_string, "'a#{b}'"), Some(String::from("a#{b}")));
+        assert_eq!(value(python_string, "f'{a}'"), None);
+        assert_eq!(value(python_string, "f'{{a}}'"), Some(String::from("{a}")));
+        assert_eq!(value(csharp_string, "$\"{{a}}\""), Some(String::from("{a}")));
+    }
+}
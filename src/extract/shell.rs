@@ -0,0 +1,383 @@
+use crate::{
+    error::Error,
+    extract::{
+        decoded::{Chars, Decoded},
+        delimited, literal, ExtractedRegex, Language,
+    },
+    flags::Flags,
+    parser::{posix, Flavor},
+};
+
+/// Single character escapes of `$'...'` strings
+const ANSI_C_ESCAPES: &[(char, char)] = &[
+    ('a', '\x07'),
+    ('b', '\x08'),
+    ('e', '\x1B'),
+    ('E', '\x1B'),
+    ('f', '\x0C'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('v', '\x0B'),
+    ('\\', '\\'),
+    ('\'', '\''),
+    ('"', '"'),
+    ('?', '?'),
+];
+
+/// A word of a shell command, with its quotes removed
+#[derive(Clone, Default, Debug)]
+struct Word {
+    text: Decoded,
+    /// First parameter expansion or command substitution in the word, whose value is
+    /// only known when the script runs
+    expansion: Option<String>,
+}
+
+impl Word {
+    /// Text of the word, which is an error if it has an expansion
+    fn text(&self) -> Result<Decoded, Error> {
+        match &self.expansion {
+            Some(field) => Err(Error::FormatField(field.clone())),
+            None => Ok(self.text.clone()),
+        }
+    }
+}
+
+/// Read what follows a `$` outside of single quotes, recording an expansion such as
+/// `$name`, `${name}` or `$(command)`
+fn dollar(chars: &mut Chars, word: &mut Word, offset: usize) {
+    word.text.push('$', offset);
+    word.text.set_end(offset + 1);
+    let field = match chars.peek() {
+        Some((open @ ('{' | '('), _)) => {
+            let close = if *open == '{' { '}' } else { ')' };
+            chars.next();
+            chars.by_ref().map(|(c, _)| c).take_while(|c| *c != close).collect()
+        }
+        Some((c, _)) if c.is_alphabetic() || *c == '_' => {
+            let mut name = String::new();
+            while let Some((c, _)) = chars.next_if(|(c, _)| c.is_alphanumeric() || *c == '_') {
+                name.push(c);
+            }
+            name
+        }
+        Some((c, _)) if c.is_ascii_digit() || "@*#?$!".contains(*c) => {
+            chars.next().map(|(c, _)| String::from(c)).unwrap_or_default()
+        }
+        // A `$` before anything else matches itself, as in `'a$'` or `"a$"`
+        _ => return,
+    };
+    word.expansion.get_or_insert(field);
+}
+
+/// Split the first command of a command line into words, removing their quotes
+///
+/// Offsets of the words are into the text, and each word ends after its last character
+/// other than a closing quote.
+fn words(text: &str) -> Result<Vec<Word>, Error> {
+    let invalid = || Error::InvalidString(Language::Shell, text.to_string());
+    let mut words = Vec::new();
+    let mut word: Option<Word> = None;
+    let line = Decoded::slice(text, text.trim());
+    let mut chars = line.chars();
+    while let Some((c, offset)) = chars.next() {
+        if ['\n', '|', ';', '&'].contains(&c) {
+            break;
+        } else if c.is_whitespace() {
+            words.extend(word.take());
+            continue;
+        }
+        let word = word.get_or_insert_with(|| Word { text: Decoded::empty(offset), expansion: None });
+        match c {
+            '\'' => loop {
+                match chars.next().ok_or_else(invalid)? {
+                    ('\'', end) => break word.text.set_end(end),
+                    (c, offset) => word.text.push(c, offset),
+                }
+            },
+            '"' => loop {
+                match chars.next().ok_or_else(invalid)? {
+                    ('"', end) => break word.text.set_end(end),
+                    ('\\', offset) => match chars.next().ok_or_else(invalid)? {
+                        (c @ ('$' | '`' | '"' | '\\'), _) => word.text.push_escape(c, offset),
+                        ('\n', _) => word.text.escaped = true,
+                        (c, escape_offset) => {
+                            word.text.push('\\', offset);
+                            word.text.push(c, escape_offset);
+                        }
+                    },
+                    ('$', offset) => dollar(&mut chars, word, offset),
+                    ('`', _) => {
+                        let command: String = chars.by_ref().map(|(c, _)| c).take_while(|c| *c != '`').collect();
+                        word.expansion.get_or_insert(command);
+                    }
+                    (c, offset) => word.text.push(c, offset),
+                }
+            },
+            '$' if chars.next_if(|(c, _)| *c == '\'').is_some() => {
+                let mut body = Decoded::empty(offset);
+                loop {
+                    match chars.next().ok_or_else(invalid)? {
+                        ('\'', end) => break body.set_end(end),
+                        ('\\', offset) => {
+                            body.push('\\', offset);
+                            let (c, escape_offset) = chars.next().ok_or_else(invalid)?;
+                            body.push(c, escape_offset);
+                        }
+                        (c, offset) => body.push(c, offset),
+                    }
+                }
+                word.text.append(literal::decode_escapes(&body, ANSI_C_ESCAPES).ok_or_else(invalid)?);
+            }
+            '$' => dollar(&mut chars, word, offset),
+            '`' => {
+                let command: String = chars.by_ref().map(|(c, _)| c).take_while(|c| *c != '`').collect();
+                word.expansion.get_or_insert(command);
+            }
+            '\\' => match chars.next() {
+                Some(('\n', _)) => word.text.escaped = true,
+                Some((c, escape_offset)) => {
+                    word.text.push_escape(c, offset);
+                    word.text.set_end(escape_offset + c.len_utf8());
+                }
+                None => (),
+            },
+            c => {
+                word.text.push(c, offset);
+                word.text.set_end(offset + c.len_utf8());
+            }
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Parse a shell word such as `'a+'`, `"a\$"` or `$'a\t'`
+pub fn shell_string(text: &str) -> Result<Decoded, Error> {
+    match words(text)?.as_slice() {
+        [word] => word.text(),
+        _ => Err(Error::InvalidString(Language::Shell, text.to_string())),
+    }
+}
+
+/// Options of a command and its other arguments
+///
+/// Options are named without their dashes, so `-e` is `e` and `--regexp=a` is
+/// `regexp`, and those named in `with_value` take the following word as their value
+/// unless it is attached, as in `-ea` or `--regexp=a`.
+fn options(arguments: &[Word], with_value: &[&str]) -> (Vec<(String, Option<Word>)>, Vec<Word>) {
+    let mut options = Vec::new();
+    let mut operands = Vec::new();
+    let mut arguments = arguments.iter();
+    while let Some(argument) = arguments.next() {
+        let value = argument.text.value();
+        let text = value.as_str();
+        if text == "--" {
+            operands.extend(arguments.cloned());
+            break;
+        }
+        if let Some(long) = text.strip_prefix("--") {
+            let (name, value) = match long.split_once('=') {
+                Some((name, attached)) => (name, Some(Word { text: argument.text.part(text, attached), ..argument.clone() })),
+                None => (long, None),
+            };
+            let value = value.or_else(|| with_value.contains(&name).then(|| arguments.next().cloned()).flatten());
+            options.push((name.to_string(), value));
+        } else if let Some(short) = text.strip_prefix('-').filter(|x| !x.is_empty()) {
+            for (idx, c) in short.char_indices() {
+                let name = c.to_string();
+                if !with_value.contains(&name.as_str()) {
+                    options.push((name, None));
+                    continue;
+                }
+                let attached = &short[idx + c.len_utf8()..];
+                let value = match attached.is_empty() {
+                    true => arguments.next().cloned(),
+                    false => Some(Word { text: argument.text.part(text, attached), ..argument.clone() }),
+                };
+                options.push((name, value));
+                break;
+            }
+        } else {
+            operands.push(argument.clone());
+        }
+    }
+    (options, operands)
+}
+
+/// Pattern of a `grep`, `egrep` or `fgrep` command, whose options choose the syntax
+fn grep(command: &str, arguments: &[Word]) -> Result<ExtractedRegex, Error> {
+    let (options, operands) = options(
+        arguments,
+        &["e", "f", "m", "A", "B", "C", "d", "D", "regexp", "file", "max-count", "context"],
+    );
+    let mut flavor = if command == "egrep" { Flavor::Ere } else { Flavor::Bre };
+    let mut fixed = command == "fgrep";
+    let mut flags = Flags::default();
+    let mut pattern = None;
+    for (name, value) in options.iter() {
+        match name.as_str() {
+            "E" | "extended-regexp" => (flavor, fixed) = (Flavor::Ere, false),
+            "G" | "basic-regexp" => (flavor, fixed) = (Flavor::Bre, false),
+            "P" | "perl-regexp" => (flavor, fixed) = (Flavor::Pcre, false),
+            "F" | "fixed-strings" => fixed = true,
+            "i" | "ignore-case" => flags.case_insensitive = true,
+            "e" | "regexp" => pattern = pattern.or(value.as_ref()),
+            _ => (),
+        }
+    }
+    let pattern = pattern.or(operands.first()).ok_or_else(|| Error::MissingPattern(command.to_string()))?;
+    let mut pattern = pattern.text()?;
+    // Fixed strings are drawn as extended patterns which match them exactly
+    if fixed {
+        let mut escaped = pattern.cleared();
+        for (c, offset) in pattern.chars() {
+            if posix::escape(&c.to_string()).len() > c.len_utf8() {
+                escaped.push_escape('\\', offset);
+            }
+            escaped.push(c, offset);
+        }
+        pattern = escaped;
+        flavor = Flavor::Ere;
+    }
+    Ok(ExtractedRegex {
+        flags,
+        flavor: Some(flavor),
+        ..ExtractedRegex::from_literal(pattern)
+    })
+}
+
+/// First regular expression of the script of a `sed` command
+fn sed(arguments: &[Word]) -> Result<ExtractedRegex, Error> {
+    let (options, operands) = options(arguments, &["e", "f", "l", "expression", "file", "line-length"]);
+    let extended = options.iter().any(|(name, _)| ["E", "r", "regexp-extended"].contains(&name.as_str()));
+    let missing = || Error::MissingPattern(String::from("sed"));
+    let script = options
+        .iter()
+        .find(|(name, _)| name == "e" || name == "expression")
+        .and_then(|(_, value)| value.as_ref())
+        .or(operands.first())
+        .ok_or_else(missing)?;
+    let script = script.text()?;
+    let value = script.value();
+    let regex = delimited::sed_script(&value, extended).unwrap_or_else(|| Err(missing()))?;
+    Ok(ExtractedRegex {
+        decoded: script.escaped,
+        offsets: script.source_offsets(&value, &regex.offsets),
+        ..regex
+    })
+}
+
+/// First regex literal of the program of an `awk` command
+fn awk(arguments: &[Word]) -> Result<ExtractedRegex, Error> {
+    let (_, operands) = options(arguments, &["F", "v", "f", "field-separator", "assign", "file"]);
+    let missing = || Error::MissingPattern(String::from("awk"));
+    let program = operands.first().ok_or_else(missing)?;
+    let program = program.text()?;
+    let value = program.value();
+    let regex = delimited::awk_regex(&value).unwrap_or_else(|| Err(missing()))?;
+    Ok(ExtractedRegex {
+        decoded: program.escaped,
+        offsets: program.source_offsets(&value, &regex.offsets),
+        ..regex
+    })
+}
+
+/// Parse a command which takes a regular expression, choosing basic or extended syntax
+/// from the command and its options, or return `None` if the text is not one
+///
+/// Commands are `grep`, `sed`, `awk`, `expr STRING : REGEX` and
+/// `[[ STRING =~ REGEX ]]`, following any variable assignments or `sudo`.
+pub fn shell_regex(text: &str) -> Option<Result<ExtractedRegex, Error>> {
+    let words = words(text).ok()?;
+    let start = words.iter().position(|x| {
+        let text = x.text.value();
+        let assignment = text.split_once('=').is_some_and(|(name, _)| !name.is_empty() && !name.contains('-'));
+        !(assignment || ["sudo", "command", "env", "exec", "time", "!"].contains(&text.as_str()))
+    })?;
+    let (command, arguments) = words[start..].split_first()?;
+    let command = command.text.value();
+    let command = command.rsplit('/').next()?;
+    let extracted = |pattern: &Word, flavor| {
+        pattern.text().map(|pattern| ExtractedRegex {
+            flavor: Some(flavor),
+            ..ExtractedRegex::from_literal(pattern)
+        })
+    };
+    Some(match command {
+        "grep" | "egrep" | "fgrep" | "zgrep" => grep(command, arguments),
+        "sed" | "gsed" => sed(arguments),
+        "awk" | "gawk" | "mawk" | "nawk" => awk(arguments),
+        "expr" => match arguments {
+            [_, colon, pattern, ..] if colon.text.value() == ":" => extracted(pattern, Flavor::Bre),
+            [name, _, pattern, ..] if name.text.value() == "match" => extracted(pattern, Flavor::Bre),
+            _ => return None,
+        },
+        "[[" => {
+            let operator = arguments.iter().position(|x| x.text.value() == "=~")?;
+            extracted(arguments.get(operator + 1)?, Flavor::Ere)
+        }
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        extract::{
+            shell::{shell_regex, shell_string, words},
+            ExtractedRegex,
+        },
+        parser::Flavor,
+    };
+
+    fn pattern(result: Option<Result<ExtractedRegex, Error>>) -> Option<String> {
+        result.and_then(|x| x.ok()).map(|x| x.pattern)
+    }
+
+    #[test]
+    fn test_words() {
+        let values = |text: &str| words(text).unwrap().iter().map(|x| x.text.value()).collect::<Vec<_>>();
+        assert_eq!(values("grep -e 'a b' \"c\\$\"d | wc"), vec!["grep", "-e", "a b", "c$d"]);
+        assert_eq!(values("echo é\\ ü; ls"), vec!["echo", "é ü"]);
+        assert_eq!(values("a\\\nb"), vec!["ab"]);
+
+        // Unterminated quotes, and a trailing backslash escaping nothing
+        assert!(words("grep 'a").is_err());
+        assert!(words("grep \"a\\\"").is_err());
+        assert!(words("grep $'a\\").is_err());
+        assert_eq!(values("grep a\\"), vec!["grep", "a"]);
+    }
+
+    #[test]
+    fn test_shell_string() {
+        assert_eq!(shell_string("'é+'").unwrap().value(), "é+");
+        assert_eq!(shell_string("$'\\té'").unwrap().value(), "\té");
+        assert_eq!(shell_string("\"a\\d\"").unwrap().value(), "a\\d");
+        assert!(matches!(shell_string("\"$HOME\""), Err(Error::FormatField(_))));
+        assert!(shell_string("'a' 'b'").is_err());
+        assert!(shell_string("'a").is_err());
+        assert!(shell_string("").is_err());
+    }
+
+    #[test]
+    fn test_shell_regex() {
+        let regex = shell_regex("grep -iE 'é+|ü'").unwrap().unwrap();
+        assert_eq!((regex.pattern.as_str(), regex.flavor), ("é+|ü", Some(Flavor::Ere)));
+        assert!(regex.flags.case_insensitive);
+        assert_eq!(pattern(shell_regex("fgrep 'a.b'")), Some(String::from("a\\.b")));
+        assert_eq!(pattern(shell_regex("sudo sed -n 's/é/e/p' f")), Some(String::from("é")));
+        assert_eq!(pattern(shell_regex("awk '/ü+/'")), Some(String::from("ü+")));
+        assert_eq!(pattern(shell_regex("expr \"$a\" : 'x*'")), Some(String::from("x*")));
+        assert_eq!(pattern(shell_regex("[[ $a =~ ^é$ ]]")), Some(String::from("^é$")));
+
+        // Missing or unterminated patterns
+        assert!(matches!(shell_regex("grep -e"), Some(Err(Error::MissingPattern(_)))));
+        assert!(shell_regex("sed 's/a/b'").unwrap().is_err());
+        assert!(shell_regex("grep 'a").is_none());
+        assert!(shell_regex("ls -la").is_none());
+        assert!(shell_regex("").is_none());
+    }
+}
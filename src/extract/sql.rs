@@ -0,0 +1,329 @@
+use crate::{
+    error::Error,
+    extract::{
+        call,
+        decoded::{self, Decoded},
+        delimited, literal, ExtractedRegex, Language,
+    },
+    parser::Flavor,
+};
+
+/// Operators which match a pattern, with its syntax and whether they ignore case
+///
+/// PostgreSQL's `~` takes POSIX advanced regular expressions, which are drawn as PCRE,
+/// and MySQL's `REGEXP` takes ICU regular expressions, which are drawn as Java's. Longer
+/// operators come first, so that `!~*` is not read as `!~`.
+const OPERATORS: &[(&str, Flavor, bool)] = &[
+    ("NOT SIMILAR TO", Flavor::SimilarTo, false),
+    ("SIMILAR TO", Flavor::SimilarTo, false),
+    ("NOT ILIKE", Flavor::Like, true),
+    ("NOT LIKE", Flavor::Like, false),
+    ("ILIKE", Flavor::Like, true),
+    ("LIKE", Flavor::Like, false),
+    ("NOT REGEXP", Flavor::Java, false),
+    ("NOT RLIKE", Flavor::Java, false),
+    ("REGEXP", Flavor::Java, false),
+    ("RLIKE", Flavor::Java, false),
+    ("!~~*", Flavor::Like, true),
+    ("!~~", Flavor::Like, false),
+    ("~~*", Flavor::Like, true),
+    ("~~", Flavor::Like, false),
+    ("!~*", Flavor::Pcre, true),
+    ("!~", Flavor::Pcre, false),
+    ("~*", Flavor::Pcre, true),
+    ("~", Flavor::Pcre, false),
+];
+
+/// Functions which take a regular expression as their second argument
+const REGEX_FUNCTIONS: &[&str] = &[
+    "regexp_like",
+    "regexp_replace",
+    "regexp_substr",
+    "regexp_instr",
+    "regexp_count",
+    "regexp_match",
+    "regexp_matches",
+    "regexp_split_to_table",
+    "regexp_split_to_array",
+];
+
+/// Match type letters of PostgreSQL, MySQL and Oracle regular expression functions,
+/// where `n` lets `.` match a newline as in MySQL and Oracle
+const SQL_MODIFIERS: &[(char, &str)] = &[
+    ('i', "i"),
+    ('c', ""),
+    ('g', "g"),
+    ('m', "m"),
+    ('n', "s"),
+    ('x', "x"),
+    ('u', ""),
+    ('b', ""),
+    ('e', ""),
+    ('p', ""),
+    ('q', ""),
+    ('s', ""),
+    ('t', ""),
+    ('w', ""),
+];
+
+fn is_identifier(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Byte length of the string literal at the start of the text, if there is one
+fn literal_len(text: &str) -> Option<usize> {
+    if let Some(rest) = text.strip_prefix('$') {
+        let tag = &text[..rest.find('$')? + 2];
+        if !tag[1..tag.len() - 1].chars().all(is_identifier) {
+            return None;
+        }
+        return text[tag.len()..].find(tag).map(|idx| 2 * tag.len() + idx);
+    }
+    let prefix = text.find('\'')?;
+    if !(prefix == 0 || (prefix == 1 && text.starts_with(['E', 'e', 'N', 'n']))) {
+        return None;
+    }
+    let escapes = prefix == 1 && text.starts_with(['E', 'e']);
+    let mut chars = text[prefix + 1..].char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\\' if escapes => {
+                chars.next();
+            }
+            // A doubled quote is a quote within the string
+            '\'' if chars.next_if(|(_, c)| *c == '\'').is_none() => return Some(prefix + idx + 2),
+            _ => (),
+        }
+    }
+    None
+}
+
+/// Byte length of an operator at the start of the text, whose words are matched
+/// ignoring case and may be separated by any whitespace
+fn operator_len(operator: &str, text: &str) -> Option<usize> {
+    let mut len = 0;
+    for (idx, word) in operator.split(' ').enumerate() {
+        let rest = &text[len..];
+        let trimmed = rest.trim_start();
+        if idx > 0 && trimmed.len() == rest.len() {
+            return None;
+        }
+        len = text.len() - trimmed.len();
+        if !trimmed.get(..word.len())?.eq_ignore_ascii_case(word) {
+            return None;
+        }
+        len += word.len();
+    }
+    // Keywords must not run into the following word
+    let keyword = operator.ends_with(char::is_alphabetic);
+    match keyword && text[len..].starts_with(is_identifier) {
+        true => None,
+        false => Some(len),
+    }
+}
+
+/// Parse the operator at the start of the text and the string literal pattern after
+/// it, with any `ESCAPE` clause, returning the pattern and the length of the text read
+fn operation(text: &str) -> Option<Result<(ExtractedRegex, usize), Error>> {
+    let (len, flavor, case_insensitive) = OPERATORS
+        .iter()
+        .find_map(|(operator, flavor, ignore_case)| operator_len(operator, text).map(|len| (len, *flavor, *ignore_case)))?;
+    let rest = text[len..].trim_start();
+    // MySQL's `REGEXP BINARY` is case sensitive, which patterns are anyway
+    let rest = operator_len("BINARY", rest).map_or(rest, |len| rest[len..].trim_start());
+    let pattern_len = literal_len(rest)?;
+    let start = text.len() - rest.len();
+    let operand = operand(rest, pattern_len, flavor);
+    Some(operand.map(|(mut regex, len)| {
+        regex.flags.case_insensitive = case_insensitive;
+        decoded::shift(&mut regex.offsets, text, rest);
+        (regex, start + len)
+    }))
+}
+
+/// Read the pattern literal of `pattern_len` bytes at the start of the text and any
+/// `ESCAPE` clause after it, returning the pattern and the length of the text read
+fn operand(text: &str, pattern_len: usize, flavor: Flavor) -> Result<(ExtractedRegex, usize), Error> {
+    let mut pattern = literal::sql_string(&text[..pattern_len])?;
+    let mut len = pattern_len;
+    let after = text[len..].trim_start();
+    if let (Flavor::Like | Flavor::SimilarTo, Some(keyword_len)) = (flavor, operator_len("ESCAPE", after)) {
+        let clause = after[keyword_len..].trim_start();
+        let escape_len = literal_len(clause).ok_or_else(|| Error::MissingPattern(String::from("ESCAPE")))?;
+        let escape = literal::sql_string(&clause[..escape_len])?.value();
+        if escape.chars().count() > 1 {
+            return Err(Error::InvalidString(Language::Sql, clause[..escape_len].to_string()));
+        }
+        pattern = backslash_escapes(&pattern, escape.chars().next());
+        len = text.len() - clause.len() + escape_len;
+    }
+    let regex = ExtractedRegex {
+        flavor: Some(flavor),
+        ..ExtractedRegex::from_literal(pattern)
+    };
+    Ok((regex, len))
+}
+
+/// Rewrite a pattern whose escape character is set by an `ESCAPE` clause to escape with
+/// a backslash, or with nothing if the clause is empty
+fn backslash_escapes(pattern: &Decoded, escape: Option<char>) -> Decoded {
+    let mut out = pattern.cleared();
+    let mut chars = pattern.chars();
+    while let Some((c, offset)) = chars.next() {
+        if Some(c) == escape {
+            out.push_escape('\\', offset);
+            if let Some((escaped, escaped_offset)) = chars.next() {
+                out.push(escaped, escaped_offset);
+            }
+        } else if c == '\\' {
+            out.push_escape('\\', offset);
+            out.push('\\', offset);
+        } else {
+            out.push(c, offset);
+        }
+    }
+    out
+}
+
+/// Pattern of a call to a regular expression function such as
+/// `regexp_replace(name, 'a+', 'b', 'gi')`, whose name is matched ignoring case
+fn function_regex(text: &str) -> Option<Result<ExtractedRegex, Error>> {
+    let call = call::parse_call(text)?;
+    let name = call.callee.to_ascii_lowercase();
+    if !REGEX_FUNCTIONS.contains(&name.as_str()) {
+        return None;
+    }
+    let pattern = call.arguments.get(1).filter(|x| literal_len(x).is_some())?;
+    let replacement = match name.as_str() {
+        "regexp_replace" => call.arguments.get(2).filter(|x| literal_len(x).is_some()),
+        _ => None,
+    };
+    // Match types are the last argument, after any positions and occurrences
+    let skipped = if replacement.is_some() { 3 } else { 2 };
+    let letters = call.arguments.iter().skip(skipped).last().filter(|x| literal_len(x).is_some());
+    let regex = function_arguments(pattern, replacement.copied(), letters.copied());
+    Some(regex.map(|mut regex| {
+        decoded::shift(&mut regex.offsets, text, pattern);
+        regex
+    }))
+}
+
+/// Read the pattern, replacement and match type literals of a function call
+fn function_arguments(pattern: &str, replacement: Option<&str>, letters: Option<&str>) -> Result<ExtractedRegex, Error> {
+    let mut regex = ExtractedRegex::from_literal(literal::sql_string(pattern)?);
+    regex.replacement = replacement.map(|x| literal::sql_string(x).map(|x| x.value())).transpose()?;
+    if let Some(letters) = letters {
+        regex.flags = delimited::modifier_flags(&literal::sql_string(letters)?.value(), SQL_MODIFIERS)?;
+    }
+    Ok(regex)
+}
+
+/// Parse a comparison with a pattern, such as `name LIKE 'a%'`, `name SIMILAR TO
+/// '(a|b)%'` or `name ~* '^a'`, or a call such as `regexp_like(name, '^a')`, or return
+/// `None` if the text has neither
+///
+/// Strings, quoted identifiers and comments are skipped when looking for the operator.
+pub fn sql_regex(text: &str) -> Option<Result<ExtractedRegex, Error>> {
+    if let Some(regex) = function_regex(text) {
+        return Some(regex);
+    }
+    let mut idx = 0;
+    while idx < text.len() {
+        let rest = &text[idx..];
+        let word_start = !text[..idx].ends_with(is_identifier);
+        if let Some(len) = word_start.then(|| literal_len(rest)).flatten() {
+            idx += len;
+            continue;
+        }
+        if rest.starts_with("--") {
+            idx += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        }
+        if let Some(quoted) = rest.strip_prefix('"') {
+            idx += quoted.find('"').map_or(rest.len(), |x| x + 2);
+            continue;
+        }
+        if word_start || !rest.starts_with(is_identifier) {
+            if let Some(regex) = operation(rest) {
+                return Some(regex.map(|(mut regex, _)| {
+                    decoded::shift(&mut regex.offsets, text, rest);
+                    regex
+                }));
+            }
+        }
+        idx += rest.chars().next().map_or(1, char::len_utf8);
+    }
+    None
+}
+
+/// Byte length of the pattern operator with its pattern and any `ESCAPE` clause, such as
+/// `LIKE 'a%'`, or the regular expression function call at the start of the text
+pub fn regex_len(text: &str) -> Option<usize> {
+    if let Some(operation) = operation(text) {
+        return operation.ok().map(|(_, len)| len);
+    }
+    function_regex(text)?.ok()?;
+    let call = call::parse_call(text)?;
+    Some(text.trim_end().len() - call.rest.len())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        extract::{
+            sql::{literal_len, operator_len, regex_len, sql_regex},
+            ExtractedRegex,
+        },
+        parser::Flavor,
+    };
+
+    fn pattern(result: Option<Result<ExtractedRegex, Error>>) -> Option<String> {
+        result.and_then(|x| x.ok()).map(|x| x.pattern)
+    }
+
+    #[test]
+    fn test_literal_len() {
+        assert_eq!(literal_len("'it''s' x"), Some(7));
+        assert_eq!(literal_len("E'a\\'b' x"), Some(7));
+        assert_eq!(literal_len("'é' x"), Some(4));
+        assert_eq!(literal_len("$tag$a'$b$tag$ x"), Some(14));
+        assert_eq!(literal_len("$$é$$"), Some(6));
+
+        // Unterminated strings, including a trailing backslash escaping the quote
+        assert_eq!(literal_len("'abc"), None);
+        assert_eq!(literal_len("'a''"), None);
+        assert_eq!(literal_len("E'a\\'"), None);
+        assert_eq!(literal_len("$tag$a"), None);
+        assert_eq!(literal_len("$"), None);
+        assert_eq!(literal_len("x'a'"), None);
+    }
+
+    #[test]
+    fn test_operator_len() {
+        assert_eq!(operator_len("NOT LIKE", "not\n  like 'a'"), Some(10));
+        assert_eq!(operator_len("LIKE", "LIKELY"), None);
+        assert_eq!(operator_len("LIKE", "LIK"), None);
+        assert_eq!(operator_len("LIKE", "LIKé"), None);
+        assert_eq!(operator_len("!~*", "!~* 'a'"), Some(3));
+    }
+
+    #[test]
+    fn test_sql_regex() {
+        let regex = sql_regex("name ILIKE 'é%'").unwrap().unwrap();
+        assert_eq!((regex.pattern.as_str(), regex.flavor), ("é%", Some(Flavor::Like)));
+        assert!(regex.flags.case_insensitive);
+        assert_eq!(pattern(sql_regex("a LIKE 'x!%' ESCAPE '!'")), Some(String::from("x\\%")));
+        assert_eq!(pattern(sql_regex("'a LIKE b' = c AND d ~ '^ü'")), Some(String::from("^ü")));
+        assert_eq!(pattern(sql_regex("regexp_replace(a, 'é+', 'e', 'gi')")), Some(String::from("é+")));
+        assert_eq!(regex_len("LIKE 'a%' ESCAPE '\\' AND b"), Some(20));
+
+        // Unterminated patterns and invalid escape clauses
+        assert!(sql_regex("name LIKE 'a").is_none());
+        assert!(sql_regex("name LIKE E'a\\'").is_none());
+        assert!(sql_regex("a LIKE 'x' ESCAPE").unwrap().is_err());
+        assert!(sql_regex("a LIKE 'x' ESCAPE 'ab'").unwrap().is_err());
+        assert!(sql_regex("-- a LIKE 'b'").is_none());
+        assert!(sql_regex("\"a LIKE 'b'").is_none());
+    }
+}
@@ -0,0 +1,70 @@
+use crate::error::Error;
+
+/// Modifiers which change how a regular expression is parsed or matched
+#[derive(Clone, Copy, Default, Eq, PartialEq, Debug)]
+pub struct Flags {
+    pub case_insensitive: bool,
+    pub global: bool,
+    pub multiline: bool,
+    pub dot_all: bool,
+    pub unicode: bool,
+    pub sticky: bool,
+    pub indices: bool,
+    pub extended: bool,
+}
+
+impl Flags {
+    /// Parse single letter flags such as `gi`, rejecting any letter not in `allowed`
+    pub fn from_chars(flags: &str, allowed: &str) -> Result<Flags, Error> {
+        let mut result = Flags::default();
+        for c in flags.chars() {
+            if !allowed.contains(c) {
+                return Err(Error::InvalidFlag(c));
+            }
+            match c {
+                'i' => result.case_insensitive = true,
+                'g' => result.global = true,
+                'm' => result.multiline = true,
+                's' => result.dot_all = true,
+                'u' | 'v' => result.unicode = true,
+                'y' => result.sticky = true,
+                'd' => result.indices = true,
+                'x' => result.extended = true,
+                other => return Err(Error::InvalidFlag(other)),
+            }
+        }
+        Ok(result)
+    }
+
+    /// Combine the flags set in either
+    pub fn union(&self, other: &Flags) -> Flags {
+        Flags {
+            case_insensitive: self.case_insensitive || other.case_insensitive,
+            global: self.global || other.global,
+            multiline: self.multiline || other.multiline,
+            dot_all: self.dot_all || other.dot_all,
+            unicode: self.unicode || other.unicode,
+            sticky: self.sticky || other.sticky,
+            indices: self.indices || other.indices,
+            extended: self.extended || other.extended,
+        }
+    }
+
+    /// Human readable names of the flags which are set
+    pub fn describe(&self) -> Vec<&'static str> {
+        [
+            (self.case_insensitive, "ignore case"),
+            (self.global, "global"),
+            (self.multiline, "multiline"),
+            (self.dot_all, "dot matches newline"),
+            (self.unicode, "unicode"),
+            (self.sticky, "sticky"),
+            (self.indices, "match indices"),
+            (self.extended, "extended"),
+        ]
+        .iter()
+        .filter(|(set, _)| *set)
+        .map(|(_, name)| *name)
+        .collect()
+    }
+}
@@ -0,0 +1,215 @@
+use std::iter;
+
+use crate::{
+    parser::{RegEx, RepetitionType},
+    railroad::sym,
+};
+
+// Repeat character n times
+fn repeat(character: char, n: usize) -> String {
+    iter::repeat_n(character, n).collect::<String>()
+}
+
+/// Description of a single capture group
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct CaptureGroup {
+    pub index: usize,
+    pub name: Option<String>,
+    /// Index of the innermost enclosing capture group
+    pub parent: Option<usize>,
+    /// Text of the group in the original pattern
+    pub pattern: String,
+    /// The group may not take part in a match, so can be None
+    pub optional: bool,
+    /// The group can match more than once, so only holds the last repetition
+    pub repeated: bool,
+}
+
+/// Every capture group of a regular expression, ordered by index
+#[derive(Debug)]
+pub struct GroupTable {
+    pub groups: Vec<CaptureGroup>,
+}
+
+impl GroupTable {
+    /// Collect the capture groups of a parsed regular expression, using the group spans
+    /// recorded by the parser to recover each sub-pattern
+    pub fn new(tree: &RegEx, text: &str, spans: &[(usize, usize)]) -> GroupTable {
+        let mut groups = Vec::new();
+        Self::collect(tree, text, spans, None, false, false, &mut groups);
+        groups.sort_by_key(|g| g.index);
+        GroupTable { groups }
+    }
+
+    fn collect(
+        tree: &RegEx,
+        text: &str,
+        spans: &[(usize, usize)],
+        parent: Option<usize>,
+        optional: bool,
+        repeated: bool,
+        groups: &mut Vec<CaptureGroup>,
+    ) {
+        match tree {
+            RegEx::Element(v) => {
+                for i in v.iter() {
+                    Self::collect(i, text, spans, parent, optional, repeated, groups);
+                }
+            }
+            RegEx::Alternation(v) => {
+                // Only one branch of an alternation takes part in a match
+                for i in v.iter() {
+                    Self::collect(i, text, spans, parent, true, repeated, groups);
+                }
+            }
+            RegEx::Repetition(repetition, a) => {
+                let (min, max) = match repetition {
+                    RepetitionType::OrMore(n) => (*n, None),
+                    RepetitionType::ZeroOrOne => (0, Some(1)),
+                    RepetitionType::Exactly(n) => (*n, Some(*n)),
+                    RepetitionType::Between(n, m) => (*n, Some(*m)),
+                };
+                Self::collect(
+                    a,
+                    text,
+                    spans,
+                    parent,
+                    optional || min == 0,
+                    repeated || max.is_none_or(|max| max > 1),
+                    groups,
+                );
+            }
+            RegEx::Capture(name, index, a) => {
+                let pattern = match spans.get(index - 1) {
+                    Some((start, end)) => text.chars().skip(*start).take(end - start).collect(),
+                    None => String::new(),
+                };
+                groups.push(CaptureGroup {
+                    index: *index,
+                    name: name.clone(),
+                    parent,
+                    pattern,
+                    optional,
+                    repeated,
+                });
+                Self::collect(a, text, spans, Some(*index), optional, repeated, groups);
+            }
+            RegEx::Lookaround(_, a) | RegEx::Quantifier(_, a) => {
+                Self::collect(a, text, spans, parent, optional, repeated, groups);
+            }
+            RegEx::OptionalSequence(v) => {
+                for a in v.iter() {
+                    Self::collect(a, text, spans, parent, true, repeated, groups);
+                }
+            }
+            RegEx::Character(_)
+            | RegEx::Anchor(_)
+            | RegEx::Terminal(_)
+            | RegEx::Backreference(..)
+            | RegEx::Balanced(..)
+            | RegEx::Frontier(_) => (),
+        }
+    }
+
+    /// Render the groups as a text table
+    ///
+    ///   # │ Name │ Parent │ Optional │ Repeated │ Pattern
+    ///  ───┼──────┼────────┼──────────┼──────────┼──────────
+    ///   1 │ year │        │          │          │ (?<year>\d{4})
+    ///
+    pub fn render(&self) -> Vec<String> {
+        if self.groups.is_empty() {
+            return vec![String::from("No capture groups")];
+        }
+        let header = ["#", "Name", "Parent", "Optional", "Repeated", "Pattern"];
+        let flag = |x: bool| String::from(if x { "yes" } else { "" });
+        let rows: Vec<[String; 6]> = self
+            .groups
+            .iter()
+            .map(|g| {
+                [
+                    format!("{}", g.index),
+                    g.name.clone().unwrap_or_default(),
+                    g.parent.map(|p| format!("{}", p)).unwrap_or_default(),
+                    flag(g.optional),
+                    flag(g.repeated),
+                    g.pattern.clone(),
+                ]
+            })
+            .collect();
+
+        let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+        for row in rows.iter() {
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = std::cmp::max(widths[i], cell.chars().count());
+            }
+        }
+        let format_row = |cells: Vec<String>| {
+            cells
+                .iter()
+                .enumerate()
+                .map(|(i, cell)| format!(" {}{} ", cell, repeat(' ', widths[i] - cell.chars().count())))
+                .collect::<Vec<String>>()
+                .join(&sym::L_VERT.to_string())
+        };
+
+        let mut text = vec![format_row(header.iter().map(|h| h.to_string()).collect())];
+        text.push(
+            widths
+                .iter()
+                .map(|w| repeat(sym::L_HORZ, w + 2))
+                .collect::<Vec<String>>()
+                .join(&sym::CROSS.to_string()),
+        );
+        for row in rows {
+            text.push(format_row(row.to_vec()));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        extract::Language,
+        groups::{CaptureGroup, GroupTable},
+        parser::RegExParser,
+    };
+
+    #[test]
+    fn test_group_table() {
+        let text = "(?:a)(b(?<name>c)?)|(d)+".to_string();
+        let mut parser = RegExParser::new(Language::Rust, &text);
+        let tree = parser.parse().unwrap();
+        let table = GroupTable::new(&tree, &text, parser.group_spans());
+        assert_eq!(
+            table.groups,
+            vec![
+                CaptureGroup {
+                    index: 1,
+                    name: None,
+                    parent: None,
+                    pattern: String::from("(b(?<name>c)?)"),
+                    optional: true,
+                    repeated: false,
+                },
+                CaptureGroup {
+                    index: 2,
+                    name: Some(String::from("name")),
+                    parent: Some(1),
+                    pattern: String::from("(?<name>c)"),
+                    optional: true,
+                    repeated: false,
+                },
+                CaptureGroup {
+                    index: 3,
+                    name: None,
+                    parent: None,
+                    pattern: String::from("(d)"),
+                    optional: true,
+                    repeated: true,
+                },
+            ]
+        );
+    }
+}
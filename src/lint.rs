@@ -0,0 +1,179 @@
+use std::fmt::Display;
+
+use crate::parser::{CharacterType, QuantifierMode, RegEx, RepetitionType};
+
+/// How likely a lint finding is to be a mistake
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Severity {
+    /// The pattern probably doesn't do what was intended
+    Warning,
+    /// The pattern could be written more simply
+    Hint,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Hint => write!(f, "hint"),
+        }
+    }
+}
+
+/// A likely mistake or possible simplification in a regular expression
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Lint {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Lint {
+    fn new(severity: Severity, message: &str) -> Lint {
+        Lint {
+            severity,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Check a parsed regular expression for likely mistakes and simplifications
+pub fn lint(tree: &RegEx) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    check(tree, false, &mut lints);
+    // Keep the first of each finding, in the order they were found
+    let mut seen = Vec::new();
+    lints.retain(|x| {
+        let new = !seen.contains(x);
+        seen.push(x.clone());
+        new
+    });
+    lints
+}
+
+/// Character matched by a node, if it only matches a single character
+fn single_char(tree: &RegEx) -> Option<char> {
+    match tree {
+        RegEx::Terminal(s) if s.chars().count() == 1 => s.chars().next(),
+        RegEx::Character(CharacterType::Terminal(c)) => Some(*c),
+        RegEx::Element(v) if v.len() == 1 => single_char(&v[0]),
+        _ => None,
+    }
+}
+
+fn is_empty(tree: &RegEx) -> bool {
+    match tree {
+        RegEx::Element(v) => v.iter().all(|x| is_empty(x)),
+        RegEx::Terminal(s) => s.is_empty(),
+        _ => false,
+    }
+}
+
+/// First character included more than once in a character class
+fn repeated_class_char(members: &[Box<CharacterType>]) -> Option<char> {
+    let mut ranges: Vec<(char, char)> = members
+        .iter()
+        .filter_map(|x| match x.as_ref() {
+            CharacterType::Terminal(c) => Some((*c, *c)),
+            CharacterType::Between(a, b) => match (a.as_ref(), b.as_ref()) {
+                (CharacterType::Terminal(a), CharacterType::Terminal(b)) => Some((*a, *b)),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    ranges.sort();
+    ranges.windows(2).find(|x| x[1].0 <= x[0].1).map(|x| x[1].0)
+}
+
+/// Check a node, where `repeated` is set inside a repetition without an upper bound
+fn check(tree: &RegEx, repeated: bool, lints: &mut Vec<Lint>) {
+    match tree {
+        RegEx::Element(v) | RegEx::OptionalSequence(v) => v.iter().for_each(|x| check(x, repeated, lints)),
+        RegEx::Alternation(v) => {
+            if v.iter().any(|x| is_empty(x)) {
+                lints.push(Lint::new(
+                    Severity::Warning,
+                    "Empty alternative matches the empty string, use ? to make the rest optional",
+                ));
+            }
+            if v.iter().enumerate().any(|(i, x)| v[..i].contains(x)) {
+                lints.push(Lint::new(Severity::Warning, "Alternation contains the same alternative more than once"));
+            }
+            // Characters special in a class are escaped differently by each flavor, so are
+            // left out of the hint
+            let chars: Option<String> = v.iter().map(|x| single_char(x)).collect();
+            let class_safe = |x: &String| x.chars().count() > 1 && !x.contains([']', '[', '\\', '^', '-']);
+            if let Some(chars) = chars.filter(class_safe) {
+                let message = format!("Alternation of single characters can be written as [{}]", chars);
+                lints.push(Lint::new(Severity::Hint, &message));
+            }
+            v.iter().for_each(|x| check(x, repeated, lints));
+        }
+        RegEx::Repetition(repetition, a) => {
+            match repetition {
+                RepetitionType::Exactly(1) => lints.push(Lint::new(Severity::Hint, "Quantifier {1} has no effect")),
+                RepetitionType::Between(0, 1) => {
+                    lints.push(Lint::new(Severity::Hint, "Quantifier {0,1} can be written as ?"))
+                }
+                RepetitionType::Between(n, m) if n > m => {
+                    lints.push(Lint::new(Severity::Warning, "Quantifier minimum is greater than its maximum"))
+                }
+                _ => (),
+            }
+            let unbounded = matches!(repetition, RepetitionType::OrMore(_));
+            if unbounded && repeated {
+                lints.push(Lint::new(
+                    Severity::Warning,
+                    "Nested quantifiers such as (a+)+ may cause catastrophic backtracking",
+                ));
+            }
+            check(a, repeated || unbounded, lints);
+        }
+        // Possessive repetitions never backtrack, so cannot backtrack catastrophically
+        RegEx::Quantifier(QuantifierMode::Possessive, a) => check(a, false, lints),
+        RegEx::Quantifier(_, a) | RegEx::Capture(_, _, a) | RegEx::Lookaround(_, a) => check(a, repeated, lints),
+        RegEx::Character(CharacterType::Any(v) | CharacterType::Not(v)) => {
+            if let Some(c) = repeated_class_char(v) {
+                let message = format!("Character class contains '{}' more than once", c.escape_debug());
+                lints.push(Lint::new(Severity::Warning, &message));
+            }
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        extract::Language,
+        lint::{lint, Severity},
+        parser::RegExParser,
+    };
+
+    fn messages(regex: &str) -> Vec<String> {
+        let tree = RegExParser::new(Language::Rust, &regex.to_string()).parse().unwrap();
+        lint(&tree).into_iter().map(|x| x.message).collect()
+    }
+
+    #[test]
+    fn test_lint() {
+        assert!(messages("a+b?[a-z]").is_empty());
+        assert!(messages("(a+)+")[0].contains("catastrophic"));
+        assert!(messages("(a++)+").is_empty());
+        assert!(messages("(a|)b")[0].contains("Empty alternative"));
+        assert!(messages("(ab|cd|ab)")[0].contains("more than once"));
+        assert_eq!(messages("[a-fc]"), vec!["Character class contains 'c' more than once"]);
+        assert_eq!(messages("a|b|c"), vec!["Alternation of single characters can be written as [abc]"]);
+        assert!(messages("a|]|b").is_empty());
+        assert!(messages("a|-|b").is_empty());
+        assert!(messages("\\^|b").is_empty());
+        assert!(messages("\\\\|b").is_empty());
+
+        // Findings repeated anywhere in the pattern are only listed once
+        assert_eq!(messages("(a+)+x(b+)+").len(), 1);
+        assert_eq!(messages("x{1}(a+)+y{1}").len(), 2);
+
+        let tree = RegExParser::new(Language::Rust, &"x{1}".to_string()).parse().unwrap();
+        assert_eq!(lint(&tree)[0].severity, Severity::Hint);
+    }
+}
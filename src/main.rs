@@ -1,62 +1,186 @@
 use rmpv::Value;
 use rsnvim::{api::Nvim, handler::RequestHandler};
-use std::{fs::File, sync::Arc, thread::sleep};
+use std::{
+    fs::File,
+    sync::{Arc, PoisonError, RwLock, RwLockReadGuard},
+    thread::sleep,
+};
 use tracing::{info, warn};
 use tracing_subscriber::{self, layer::SubscriberExt};
 
 use crate::{
+    automaton::{compare::Comparison, AutomatonKind},
+    diff::DiffTree,
     error::Error,
-    extract::{Language, RegexExtractor},
-    parser::RegExParser,
+    extract::{definition::LanguageDefinition, ExtractedRegex, Language, RegexExtractor},
+    groups::GroupTable,
+    lint::lint,
+    matcher::Matcher,
+    parser::{Flavor, RegExParser},
     railroad::renderer::RailroadRenderer,
     text::TextRenderer
 };
 
+pub mod automaton;
+pub mod diff;
 pub mod error;
 pub mod extract;
+pub mod flags;
+pub mod groups;
+pub mod lint;
+pub mod matcher;
 pub mod parser;
 pub mod railroad;
+pub mod scan;
 pub mod text;
 
 
+/// Response sent in place of a command's result when it fails
+fn error_response(error: Error) -> Value {
+    Value::Map(vec![(Value::from("error"), Value::from(format!("{}", error)))])
+}
+
+/// Prefix rendered output with the decoded pattern, flags and any replacement of the
+/// regular expression, padding every line to the same width
+fn add_header(regex: &ExtractedRegex, text: Vec<String>) -> Vec<String> {
+    let mut lines = Vec::new();
+    if regex.decoded {
+        lines.push(format!("Pattern: {}", regex.pattern.replace('\n', "\\n").replace('\t', "\\t")));
+    }
+    let names = regex.flags.describe();
+    if !names.is_empty() {
+        lines.push(format!("Flags: {}", names.join(", ")));
+    }
+    if let Some(replacement) = &regex.replacement {
+        lines.push(format!("Replacement: {}", replacement.replace('\n', "\\n").replace('\t', "\\t")));
+    }
+    if lines.is_empty() {
+        return text;
+    }
+    lines.push(String::new());
+    lines.extend(text);
+    let width = lines.iter().map(|x| x.chars().count()).max().unwrap_or(0);
+    lines
+        .into_iter()
+        .map(|x| {
+            let padding = width - x.chars().count();
+            x + &" ".repeat(padding)
+        })
+        .collect()
+}
+
+/// Zero-indexed line and byte column of an offset into the text of a node, where the
+/// column of the first line is relative to the start of the node
+fn source_position(text: &str, offset: usize) -> Value {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |x| x + 1);
+    Value::Map(vec![
+        (Value::from("line"), Value::from(before.matches('\n').count())),
+        (Value::from("column"), Value::from(offset - line_start))
+    ])
+}
+
+/// Read a language definition from a table passed over RPC
+fn language_definition(value: &Value) -> Result<LanguageDefinition, Error> {
+    let name = value["name"].as_str().ok_or_else(|| {
+        Error::InvalidDefinition(format!("{}", value["name"]), String::from("name must be a string"))
+    })?;
+    let invalid = |field: &str, kind: &str| {
+        Error::InvalidDefinition(name.to_string(), format!("{} must be {}", field, kind))
+    };
+    // Lists with a single item may be given as just that item
+    let strings = |field: &str| match &value[field] {
+        Value::Nil => Ok(Vec::new()),
+        Value::Array(items) => items
+            .iter()
+            .map(|x| x.as_str().map(String::from).ok_or_else(|| invalid(field, "a list of strings")))
+            .collect(),
+        other => match other.as_str() {
+            Some(x) => Ok(vec![x.to_string()]),
+            None => Err(invalid(field, "a list of strings")),
+        },
+    };
+    let string = |field: &str| match &value[field] {
+        Value::Nil => Ok(None),
+        other => other.as_str().map(|x| Some(x.to_string())).ok_or_else(|| invalid(field, "a string")),
+    };
+
+    let mut definition = LanguageDefinition::new(name);
+    definition.filetypes = strings("filetypes")?;
+    definition.extensions = strings("extensions")?;
+    definition.delimiters = strings("delimiters")?;
+    definition.raw_prefixes = strings("raw_prefixes")?;
+    definition.raw_suffixes = strings("raw_suffixes")?;
+    definition.escape = string("escape")?;
+    definition.flavor = string("flavor")?;
+    Ok(definition)
+}
+
 struct ReqHandler {
-    regex_railroad: RegexExtractor
+    regex_railroad: RwLock<RegexExtractor>
 }
 
 impl ReqHandler {
     pub fn new() -> ReqHandler {
-        let regex_railroad = RegexExtractor::new();
+        let regex_railroad = RwLock::new(RegexExtractor::new());
         ReqHandler { regex_railroad }
     }
 
-    /// Retrieve filename and node text from RPC arguments
-    fn parse_rpc_args(&self, value: Vec<Value>) -> Result<(String, String), Error> {
+    fn extractor(&self) -> RwLockReadGuard<'_, RegexExtractor> {
+        self.regex_railroad.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Create a parser for an extracted regular expression using the syntax of its language
+    fn parser(&self, language: &Language, regex: &ExtractedRegex) -> RegExParser {
+        let extractor = self.extractor();
+        let flavor = regex.flavor.unwrap_or_else(|| extractor.flavor(language));
+        let mut parser = RegExParser::new(language.clone(), &regex.pattern);
+        if let Ok(string_format) = extractor.get_string_format(language) {
+            parser = parser.with_string_format(string_format);
+        }
+        parser.with_flavor(flavor).with_flags(regex.flags)
+    }
+
+    /// Detect the language from the first RPC argument, which is either the filename or a
+    /// map of the buffer's `filename`, `filetype` and the tree-sitter `lang` of the node
+    fn source_language(&self, source: &Value) -> Language {
+        match source.as_str() {
+            Some(filename) => self.extractor().detect_language(filename, None, None),
+            None => self.extractor().detect_language(
+                source["filename"].as_str().unwrap_or_default(),
+                source["filetype"].as_str(),
+                source["lang"].as_str(),
+            ),
+        }
+    }
+
+    /// Retrieve language and node text from RPC arguments
+    fn parse_rpc_args(&self, value: Vec<Value>) -> Result<(Language, String), Error> {
         let msg = &value[0];
-        let filename = msg[0].as_str().expect("Filename is the first argument of the Lua RPC");
+        let language = self.source_language(&msg[0]);
         let node = msg[1].as_str().expect("Node is the second argument of the Lua RPC");
         info!("Received message: {}", node);
 
-        Ok((filename.to_string(), node.to_string()))
+        Ok((language, node.to_string()))
     }
 
     /// Generate railroad diagram from regular expression
     fn regexrailroad(&self, params: Vec<Value>) -> Result<Value, Error> {
         // Handle RPC arguments
-        let (filename, node) = self.parse_rpc_args(params)?;
+        let (language, node) = self.parse_rpc_args(params)?;
 
         // Obtain regular expression from received text
-        let language = Language::from_filename(&filename);
-        let regex = self.regex_railroad.get_regex(&language, &node)?;
+        let regex = self.extractor().get_regex(&language, &node)?;
 
         // Parse and render regular expression
-        let mut parser = RegExParser::new(language, &regex);
+        let mut parser = self.parser(&language, &regex);
         let parsed_regex = parser.parse()?;
         info!("Parsed regular expression: {:?}", parsed_regex);
 
         // Generate and render diagram
         let diagram = RailroadRenderer::generate_diagram(&parsed_regex)?;
         info!("Successfully generated diagram: {:?}", diagram);
-        let text = RailroadRenderer::render_diagram(&diagram)?;
+        let text = add_header(&regex, RailroadRenderer::render_diagram(&diagram)?);
         info!("Successfully rendered diagram");
 
         Ok(Value::Map(vec![
@@ -72,17 +196,17 @@ impl ReqHandler {
     /// Generate text description from regular expression
     fn railroadtext(&self, params: Vec<Value>) -> Result<Value, Error> {
         // Handle RPC arguments
-        let (filename, node) = self.parse_rpc_args(params)?;
+        let (language, node) = self.parse_rpc_args(params)?;
 
         // Obtain regular expression from received text
-        let language = Language::from_filename(&filename);
-        let regex = self.regex_railroad.get_regex(&language, &node)?;
+        let regex = self.extractor().get_regex(&language, &node)?;
 
         // Parse and render regular expression
-        let mut parser = RegExParser::new(language, &regex);
+        let mut parser = self.parser(&language, &regex);
         let parsed_regex = parser.parse()?;
         info!("Parsed regular expression: {:?}", parsed_regex);
         let (text, _highlight) = TextRenderer::render_text(&parsed_regex)?;
+        let text = add_header(&regex, text);
         info!("Successfully rendered text");
 
         Ok(Value::Map(vec![
@@ -94,6 +218,266 @@ impl ReqHandler {
             (Value::from("height"), Value::from(text.len()))
         ]))
     }
+
+    /// Generate state graph of the automaton for a regular expression
+    fn regexautomaton(&self, params: Vec<Value>) -> Result<Value, Error> {
+        // Automaton kind is an optional third argument
+        let kind = AutomatonKind::from_name(params[0][2].as_str().unwrap_or("dfa"));
+        let (language, node) = self.parse_rpc_args(params)?;
+
+        // Obtain regular expression from received text
+        let regex = self.extractor().get_regex(&language, &node)?;
+
+        // Parse and compile regular expression
+        let mut parser = self.parser(&language, &regex);
+        let parsed_regex = parser.parse()?;
+        info!("Parsed regular expression: {:?}", parsed_regex);
+        let text = automaton::render_state_graph(&parsed_regex, &regex.flags, kind)?;
+        info!("Successfully rendered {:?} state graph", kind);
+
+        Ok(Value::Map(vec![
+            (
+                Value::from("text"),
+                Value::from(text.iter().map(|x| Value::from(x.as_str())).collect::<Vec<Value>>())
+            ),
+            (Value::from("width"), Value::from(text[0].chars().count())),
+            (Value::from("height"), Value::from(text.len()))
+        ]))
+    }
+
+    /// Compare the strings matched by two regular expressions
+    fn regexcompare(&self, params: Vec<Value>) -> Result<Value, Error> {
+        // Handle RPC arguments
+        let msg = &params[0];
+        let left = msg[1].as_str().expect("First pattern is the second argument of the Lua RPC");
+        let right = msg[2].as_str().expect("Second pattern is the third argument of the Lua RPC");
+        info!("Received patterns: {} and {}", left, right);
+
+        // Patterns are typed bare, in the syntax of the buffer's language
+        let language = self.source_language(&msg[0]);
+        let left = ExtractedRegex::new(left.to_string());
+        let right = ExtractedRegex::new(right.to_string());
+
+        // Parse and compare regular expressions
+        let left_regex = self.parser(&language, &left).parse()?;
+        let right_regex = self.parser(&language, &right).parse()?;
+        let comparison = Comparison::new((&left_regex, &left.flags), (&right_regex, &right.flags))?;
+        let text = comparison.render(&left.pattern, &right.pattern);
+        let width = text.iter().map(|x| x.chars().count()).max().unwrap_or(0);
+
+        let example = |x: &Option<String>| match x {
+            Some(x) => Value::from(x.as_str()),
+            None => Value::Nil,
+        };
+        Ok(Value::Map(vec![
+            (Value::from("relation"), Value::from(format!("{}", comparison.relation))),
+            (Value::from("only_left"), example(&comparison.only_left)),
+            (Value::from("only_right"), example(&comparison.only_right)),
+            (
+                Value::from("text"),
+                Value::from(text.iter().map(|x| Value::from(x.as_str())).collect::<Vec<Value>>())
+            ),
+            (Value::from("width"), Value::from(width)),
+            (Value::from("height"), Value::from(text.len()))
+        ]))
+    }
+
+    /// Generate railroad diagram of the changes between two regular expressions
+    fn regexdiff(&self, params: Vec<Value>) -> Result<Value, Error> {
+        // Handle RPC arguments
+        let msg = &params[0];
+        let old = msg[1].as_str().expect("Old pattern is the second argument of the Lua RPC");
+        let new = msg[2].as_str().expect("New pattern is the third argument of the Lua RPC");
+        info!("Received patterns: {} and {}", old, new);
+
+        // Patterns are typed bare, in the syntax of the buffer's language
+        let language = self.source_language(&msg[0]);
+        let old = ExtractedRegex::new(old.to_string());
+        let new = ExtractedRegex::new(new.to_string());
+
+        // Parse and diff regular expressions
+        let old_regex = self.parser(&language, &old).parse()?;
+        let new_regex = self.parser(&language, &new).parse()?;
+        let diff = DiffTree::new(&old_regex, &new_regex);
+        let (inserted, deleted, modified) = diff.count();
+        info!("Diff has {} insertions, {} deletions, {} modifications", inserted, deleted, modified);
+
+        // Generate and render diagram
+        let diagram = RailroadRenderer::generate_diff_diagram(&diff)?;
+        let text = RailroadRenderer::render_diagram(&diagram)?;
+        info!("Successfully rendered diff diagram");
+
+        Ok(Value::Map(vec![
+            (Value::from("inserted"), Value::from(inserted)),
+            (Value::from("deleted"), Value::from(deleted)),
+            (Value::from("modified"), Value::from(modified)),
+            (
+                Value::from("text"),
+                Value::from(text.iter().map(|x| Value::from(x.as_str())).collect::<Vec<Value>>())
+            ),
+            (Value::from("width"), Value::from(text[0].chars().count())),
+            (Value::from("height"), Value::from(text.len()))
+        ]))
+    }
+
+    /// List the capture groups of a regular expression
+    fn regexgroups(&self, params: Vec<Value>) -> Result<Value, Error> {
+        // Handle RPC arguments
+        let (language, node) = self.parse_rpc_args(params)?;
+
+        // Obtain regular expression from received text
+        let regex = self.extractor().get_regex(&language, &node)?;
+
+        // Parse regular expression and collect groups
+        let mut parser = self.parser(&language, &regex);
+        let parsed_regex = parser.parse()?;
+        info!("Parsed regular expression: {:?}", parsed_regex);
+        let table = GroupTable::new(&parsed_regex, parser.text(), parser.group_spans());
+        let text = table.render();
+
+        // Group spans can only be found in the source when the parser read the pattern
+        // without rewriting it
+        let rewritten = parser.text() != regex.pattern;
+        let groups = table.groups.iter().map(|g| {
+            let range = parser
+                .group_spans()
+                .get(g.index - 1)
+                .filter(|_| !rewritten)
+                .and_then(|(start, end)| regex.source_range(*start..*end));
+            Value::Map(vec![
+                (Value::from("index"), Value::from(g.index)),
+                (Value::from("name"), g.name.as_ref().map_or(Value::Nil, |n| Value::from(n.as_str()))),
+                (Value::from("parent"), g.parent.map_or(Value::Nil, Value::from)),
+                (Value::from("pattern"), Value::from(g.pattern.as_str())),
+                (Value::from("optional"), Value::from(g.optional)),
+                (Value::from("repeated"), Value::from(g.repeated)),
+                (Value::from("start"), range.as_ref().map_or(Value::Nil, |x| source_position(&node, x.start))),
+                (Value::from("end"), range.as_ref().map_or(Value::Nil, |x| source_position(&node, x.end)))
+            ])
+        }).collect::<Vec<Value>>();
+
+        Ok(Value::Map(vec![
+            (Value::from("kind"), Value::from(regex.kind.to_string())),
+            (Value::from("groups"), Value::from(groups)),
+            (
+                Value::from("text"),
+                Value::from(text.iter().map(|x| Value::from(x.as_str())).collect::<Vec<Value>>())
+            ),
+            (Value::from("width"), Value::from(text[0].chars().count())),
+            (Value::from("height"), Value::from(text.len()))
+        ]))
+    }
+
+    /// Find every match of a regular expression in a range of buffer lines
+    fn regexmatch(&self, params: Vec<Value>) -> Result<Value, Error> {
+        // Lines and the number of the first line follow the source and node
+        let lines: Vec<String> = params[0][2]
+            .as_array()
+            .expect("Lines are the third argument of the Lua RPC")
+            .iter()
+            .map(|x| x.as_str().unwrap_or_default().to_string())
+            .collect();
+        let first_line = params[0][3].as_u64().unwrap_or(0);
+        let (language, node) = self.parse_rpc_args(params)?;
+
+        // Obtain regular expression from received text
+        let regex = self.extractor().get_regex(&language, &node)?;
+
+        // Parse regular expression and match each line
+        let mut parser = self.parser(&language, &regex);
+        let parsed_regex = parser.parse()?;
+        info!("Parsed regular expression: {:?}", parsed_regex);
+        let matcher = Matcher::with_flags(&parsed_regex, &regex.flags)?;
+
+        let mut matches = Vec::new();
+        for (n, line) in lines.iter().enumerate() {
+            for m in matcher.find_all(line)? {
+                let groups = m.groups.iter().enumerate().filter_map(|(i, g)| {
+                    g.map(|(start, end)| Value::Map(vec![
+                        (Value::from("index"), Value::from(i + 1)),
+                        (
+                            Value::from("name"),
+                            matcher.group_names()[i].as_ref().map_or(Value::Nil, |x| Value::from(x.as_str()))
+                        ),
+                        (Value::from("start"), Value::from(start)),
+                        (Value::from("end"), Value::from(end))
+                    ]))
+                }).collect::<Vec<Value>>();
+                matches.push(Value::Map(vec![
+                    (Value::from("line"), Value::from(first_line + n as u64)),
+                    (Value::from("start"), Value::from(m.start)),
+                    (Value::from("end"), Value::from(m.end)),
+                    (Value::from("groups"), Value::from(groups))
+                ]));
+            }
+        }
+        info!("Found {} matches", matches.len());
+
+        Ok(Value::Map(vec![(Value::from("matches"), Value::from(matches))]))
+    }
+
+    /// Find every regular expression in the text of a buffer, with whether it parses and
+    /// any lint findings
+    fn regexscan(&self, params: Vec<Value>) -> Result<Value, Error> {
+        let language = self.source_language(&params[0][0]);
+        let text = params[0][1].as_str().expect("Buffer text is the second argument of the Lua RPC");
+
+        let mut regexes = Vec::new();
+        for location in scan::find_regexes(&language, text) {
+            let regex = self.extractor().get_regex(&language, &location.text);
+            let parsed = regex.clone().and_then(|x| self.parser(&language, &x).parse());
+            let (error, lints) = match parsed {
+                Ok(tree) => (Value::Nil, lint(&tree)),
+                Err(e) => (Value::from(e.to_string()), Vec::new()),
+            };
+            let lints = lints.iter().map(|x| Value::Map(vec![
+                (Value::from("severity"), Value::from(x.severity.to_string())),
+                (Value::from("message"), Value::from(x.message.as_str()))
+            ])).collect::<Vec<Value>>();
+            regexes.push(Value::Map(vec![
+                (Value::from("line"), Value::from(location.line)),
+                (Value::from("column"), Value::from(location.column)),
+                (Value::from("end_line"), Value::from(location.end_line)),
+                (Value::from("end_column"), Value::from(location.end_column)),
+                (Value::from("text"), Value::from(location.text.as_str())),
+                (Value::from("kind"), regex.as_ref().map_or(Value::Nil, |x| Value::from(x.kind.to_string()))),
+                (Value::from("pattern"), regex.map_or(Value::Nil, |x| Value::from(x.pattern))),
+                (Value::from("error"), error),
+                (Value::from("lints"), Value::from(lints))
+            ]));
+        }
+        info!("Found {} regular expressions", regexes.len());
+
+        Ok(Value::Map(vec![(Value::from("regexes"), Value::from(regexes))]))
+    }
+
+    /// Define languages from the tables passed to the plugin's `setup()` or read from its
+    /// languages file, returning their names
+    fn regexlanguages(&self, params: Vec<Value>) -> Result<Value, Error> {
+        let definitions = params[0][0].as_array().cloned().unwrap_or_default();
+        let mut extractor = self.regex_railroad.write().unwrap_or_else(PoisonError::into_inner);
+        let mut names = Vec::new();
+        for value in definitions.iter() {
+            let language = extractor.define_language(&language_definition(value)?)?;
+            names.push(Value::from(language.to_string()));
+        }
+        info!("Defined {} languages", names.len());
+
+        Ok(Value::Map(vec![(Value::from("languages"), Value::from(names))]))
+    }
+
+    /// Apply options from the plugin's `setup()` which change how patterns are read, such
+    /// as the `config_flavor` of patterns in YAML, TOML and JSON files
+    fn regexconfig(&self, params: Vec<Value>) -> Result<Value, Error> {
+        let options = &params[0][0];
+        let mut extractor = self.regex_railroad.write().unwrap_or_else(PoisonError::into_inner);
+        if let Some(name) = options["config_flavor"].as_str() {
+            let flavor = Flavor::from_name(name).ok_or_else(|| Error::UnknownFlavor(name.to_string()))?;
+            extractor.set_config_flavor(flavor);
+        }
+
+        Ok(Value::Map(Vec::new()))
+    }
 }
 
 impl RequestHandler for ReqHandler {
@@ -106,17 +490,44 @@ impl RequestHandler for ReqHandler {
         match method.as_str() {
             "regexrailroad" => {
                 info!("RegexRailroad command received");
-                match self.regexrailroad(params) {
-                    Ok(x) => Ok(x),
-                    Err(e) => Ok(
-                        Value::Map(vec![(Value::from("error"), Value::from(format!("{}", e)))])
-                    )
-                }
+                Ok(self.regexrailroad(params).unwrap_or_else(error_response))
             },
             "regextext" => {
                 info!("RegexText command received");
                 Ok(self.railroadtext(params).unwrap())
             }, 
+            "regexcompare" => {
+                info!("RegexCompare command received");
+                Ok(self.regexcompare(params).unwrap_or_else(error_response))
+            },
+            "regexdiff" => {
+                info!("RegexDiff command received");
+                Ok(self.regexdiff(params).unwrap_or_else(error_response))
+            },
+            "regexgroups" => {
+                info!("RegexGroups command received");
+                Ok(self.regexgroups(params).unwrap_or_else(error_response))
+            },
+            "regexmatch" => {
+                info!("RegexMatch command received");
+                Ok(self.regexmatch(params).unwrap_or_else(error_response))
+            },
+            "regexscan" => {
+                info!("RegexScan command received");
+                Ok(self.regexscan(params).unwrap_or_else(error_response))
+            },
+            "regexlanguages" => {
+                info!("RegexLanguages command received");
+                Ok(self.regexlanguages(params).unwrap_or_else(error_response))
+            },
+            "regexconfig" => {
+                info!("RegexConfig command received");
+                Ok(self.regexconfig(params).unwrap_or_else(error_response))
+            },
+            "regexautomaton" => {
+                info!("RegexAutomaton command received");
+                Ok(self.regexautomaton(params).unwrap_or_else(error_response))
+            },
 
             unknown => {
                 warn!("Unknown command: {}", unknown);
@@ -151,3 +562,62 @@ fn main() {
         sleep(std::time::Duration::from_secs(1))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use rmpv::Value;
+
+    use crate::ReqHandler;
+
+    #[test]
+    fn test_regexscan() {
+        let handler = ReqHandler::new();
+        let scan = |filename: &str, text: &str| -> Vec<Value> {
+            let params = vec![Value::from(vec![Value::from(filename), Value::from(text)])];
+            let result = handler.regexscan(params).unwrap();
+            result["regexes"].as_array().cloned().unwrap_or_default()
+        };
+
+        // Patterns which fail to parse are listed with their error
+        let regexes = scan("a.py", "a = re.compile(r'[')\nb = re.compile('b+')\n");
+        assert_eq!(regexes.len(), 2);
+        assert!(regexes[0]["error"].as_str().is_some());
+        assert_eq!(regexes[1]["error"], Value::Nil);
+        assert_eq!(regexes[1]["pattern"].as_str(), Some("b+"));
+
+        // Calls are only recognised in the languages which have them
+        assert!(scan("a.rb", "s.gsub(\"(\", \"\")\n").is_empty());
+        assert_eq!(scan("a.lua", "s:gsub(\"%s+\", \"\")\n").len(), 1);
+    }
+
+    #[test]
+    fn test_regexcompare() {
+        let handler = ReqHandler::new();
+        let compare = |filename: &str, left: &str, right: &str| -> Value {
+            let params = vec![Value::from(vec![Value::from(filename), Value::from(left), Value::from(right)])];
+            handler.regexcompare(params).unwrap()
+        };
+
+        // Arguments are bare patterns rather than string literals
+        let result = compare("a.py", "a+b", "aa*b");
+        assert_eq!(result["relation"], Value::from("equal"));
+        let result = compare("a.py", "a+b", "a*b");
+        assert_eq!(result["only_right"].as_str(), Some("b"));
+    }
+
+    #[test]
+    fn test_regexdiff() {
+        let handler = ReqHandler::new();
+        let diff = |filename: &str, old: &str, new: &str| -> Value {
+            let params = vec![Value::from(vec![Value::from(filename), Value::from(old), Value::from(new)])];
+            handler.regexdiff(params).unwrap()
+        };
+
+        // Arguments are bare patterns rather than string literals
+        let result = diff("a.py", "a+b", "a+b");
+        assert_eq!(result["modified"], Value::from(0));
+        assert_eq!(result["inserted"], Value::from(0));
+        let result = diff("a.py", "a+b", "a+b[0-9]");
+        assert_eq!(result["inserted"], Value::from(1));
+    }
+}
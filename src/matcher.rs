@@ -0,0 +1,664 @@
+use tracing::info;
+
+use crate::{
+    automaton::charset::CharSet,
+    error::Error,
+    flags::Flags,
+    parser::{AnchorType, LookaroundType, QuantifierMode, RegEx, RepetitionType},
+};
+
+/// Maximum number of backtracking steps when matching from a single position
+pub const STEP_LIMIT: usize = 1_000_000;
+
+/// Maximum number of instructions a regular expression may compile to, as counted
+/// repetitions are written out in full
+pub const PROGRAM_LIMIT: usize = 100_000;
+
+/// A regular expression prepared for matching, with character classes resolved
+#[derive(Debug)]
+enum Node {
+    Sequence(Vec<Node>),
+    Alternation(Vec<Node>),
+    Repeat(u32, Option<u32>, QuantifierMode, Box<Node>),
+    Set(CharSet),
+    Literal(Vec<char>),
+    Assert(AnchorType),
+    Group(usize, Box<Node>),
+    Look(LookaroundType, Box<Node>),
+    /// Text of an earlier group, compared case insensitively if set
+    Backreference(usize, bool),
+    /// Text from the opening character to its matching closing character
+    Balanced(char, char),
+    /// Position where the previous character is not in the set and the next one is
+    Frontier(CharSet),
+}
+
+/// A match within a line, with byte offsets for the match and each capture group
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Match {
+    pub start: usize,
+    pub end: usize,
+    /// Span of each capture group, indexed by group number - 1
+    pub groups: Vec<Option<(usize, usize)>>,
+}
+
+/// A step of a compiled regular expression
+#[derive(Debug)]
+enum Inst {
+    Set(CharSet),
+    Literal(Vec<char>),
+    Assert(AnchorType),
+    /// Record the position of Vim's `\zs` or `\ze`
+    Mark(AnchorType),
+    /// Record the position in a slot, the start and end of each group
+    Save(usize),
+    /// Continue at the first instruction, backtracking to the second
+    Split(usize, usize),
+    Jump(usize),
+    /// Record the position at the start of a repetition in a register
+    Enter(usize),
+    /// Fail if the repetition started at the position in the register matched nothing,
+    /// as repeating it would loop forever
+    Progress(usize),
+    Backreference(usize, bool),
+    Balanced(char, char),
+    Frontier(CharSet),
+    /// Match the program starting at an instruction once, never backtracking into it
+    Atomic(usize),
+    /// Check the program starting at an instruction matches around the position
+    Look(LookaroundType, usize),
+    Match,
+}
+
+/// Undo record of the backtracking stack
+enum Frame {
+    /// Another way to continue matching, from an instruction and position
+    Choice(usize, usize),
+    Slot(usize, Option<usize>),
+    Register(usize, usize),
+    Marks((Option<usize>, Option<usize>)),
+    /// Everything changed by an atomic program or lookaround
+    Restore(Box<Captures>),
+}
+
+/// Positions recorded while matching, which are restored when backtracking
+#[derive(Clone)]
+struct Captures {
+    slots: Vec<Option<usize>>,
+    registers: Vec<usize>,
+    /// Positions of Vim's `\zs` and `\ze`, which move the start and end of the match
+    marks: (Option<usize>, Option<usize>),
+}
+
+/// Mutable state of a single match attempt
+struct State<'t> {
+    text: &'t [char],
+    captures: Captures,
+    steps: usize,
+}
+
+/// A backtracking matcher for parsed regular expressions
+///
+/// Backtracking uses an explicit stack, so long lines do not overflow the call stack,
+/// and each start position has its own budget of `STEP_LIMIT` steps.
+#[derive(Debug)]
+pub struct Matcher {
+    program: Vec<Inst>,
+    group_count: usize,
+    register_count: usize,
+    group_names: Vec<Option<String>>,
+}
+
+impl Matcher {
+    pub fn new(tree: &RegEx) -> Result<Matcher, Error> {
+        Self::with_flags(tree, &Flags::default())
+    }
+
+    /// Prepare a parsed regular expression, applying the case insensitive and dot
+    /// matches newline flags
+    pub fn with_flags(tree: &RegEx, flags: &Flags) -> Result<Matcher, Error> {
+        let mut group_names = Vec::new();
+        let node = Self::compile(tree, flags, &mut group_names)?;
+        let mut matcher = Matcher {
+            program: Vec::new(),
+            group_count: group_names.len(),
+            register_count: 0,
+            group_names,
+        };
+        matcher.emit(&node)?;
+        matcher.program.push(Inst::Match);
+        Ok(matcher)
+    }
+
+    /// Name of each capture group, indexed by group number - 1
+    pub fn group_names(&self) -> &[Option<String>] {
+        &self.group_names
+    }
+
+    fn compile(tree: &RegEx, flags: &Flags, group_names: &mut Vec<Option<String>>) -> Result<Node, Error> {
+        Ok(match tree {
+            RegEx::Element(v) => Node::Sequence(
+                v.iter().map(|x| Self::compile(x, flags, group_names)).collect::<Result<_, _>>()?,
+            ),
+            RegEx::Alternation(v) => Node::Alternation(
+                v.iter().map(|x| Self::compile(x, flags, group_names)).collect::<Result<_, _>>()?,
+            ),
+            RegEx::Repetition(repetition, a) => {
+                let (min, max) = match repetition {
+                    RepetitionType::OrMore(n) => (*n, None),
+                    RepetitionType::ZeroOrOne => (0, Some(1)),
+                    RepetitionType::Exactly(n) => (*n, Some(*n)),
+                    RepetitionType::Between(n, m) => (*n, Some(*m)),
+                };
+                Node::Repeat(min, max, QuantifierMode::Greedy, Box::new(Self::compile(a, flags, group_names)?))
+            }
+            RegEx::Quantifier(mode, a) => match Self::compile(a, flags, group_names)? {
+                Node::Repeat(min, max, _, node) => Node::Repeat(min, max, *mode, node),
+                other => other,
+            },
+            RegEx::Character(a) => Node::Set(CharSet::from_character_with_flags(a, flags)?),
+            RegEx::Anchor(a) => Node::Assert(*a),
+            RegEx::Terminal(a) if flags.case_insensitive => Node::Sequence(
+                a.chars().map(|c| Node::Set(CharSet::single(c).case_folded())).collect(),
+            ),
+            RegEx::Terminal(a) => Node::Literal(a.chars().collect()),
+            RegEx::Capture(name, group, a) => {
+                if group_names.len() < *group {
+                    group_names.resize(*group, None);
+                }
+                group_names[group - 1] = name.clone();
+                Node::Group(group - 1, Box::new(Self::compile(a, flags, group_names)?))
+            }
+            RegEx::Lookaround(kind, a) => Node::Look(*kind, Box::new(Self::compile(a, flags, group_names)?)),
+            RegEx::Backreference(_, group) => Node::Backreference(group - 1, flags.case_insensitive),
+            // Each atom is only tried if all those before it matched
+            RegEx::OptionalSequence(v) => {
+                let mut node = Node::Sequence(Vec::new());
+                for atom in v.iter().rev() {
+                    let sequence = Node::Sequence(vec![Self::compile(atom, flags, group_names)?, node]);
+                    node = Node::Repeat(0, Some(1), QuantifierMode::Greedy, Box::new(sequence));
+                }
+                node
+            }
+            RegEx::Balanced(open, close) => Node::Balanced(*open, *close),
+            RegEx::Frontier(a) => match Self::compile(a, flags, group_names)? {
+                Node::Set(set) => Node::Frontier(set),
+                _ => return Err(Error::InvalidParsing),
+            },
+        })
+    }
+
+    /// Append the instructions of a node to the program
+    fn emit(&mut self, node: &Node) -> Result<(), Error> {
+        if self.program.len() > PROGRAM_LIMIT {
+            return Err(Error::ProgramLimit(PROGRAM_LIMIT));
+        }
+        match node {
+            Node::Sequence(v) => {
+                for x in v.iter() {
+                    self.emit(x)?;
+                }
+            }
+            Node::Alternation(v) => {
+                // Each branch but the last is tried before backtracking to the next
+                let mut jumps = Vec::new();
+                for (idx, x) in v.iter().enumerate() {
+                    if idx + 1 == v.len() {
+                        self.emit(x)?;
+                        break;
+                    }
+                    let split = self.placeholder();
+                    self.emit(x)?;
+                    jumps.push(self.placeholder());
+                    self.program[split] = Inst::Split(split + 1, self.program.len());
+                }
+                for jump in jumps {
+                    self.program[jump] = Inst::Jump(self.program.len());
+                }
+            }
+            Node::Repeat(min, max, QuantifierMode::Possessive, a) => {
+                // Take the greedy number of repetitions and never give any back
+                let atomic = self.placeholder();
+                let skip = self.placeholder();
+                self.emit_repeat(a, *min, *max, QuantifierMode::Greedy)?;
+                self.program.push(Inst::Match);
+                self.program[atomic] = Inst::Atomic(skip + 1);
+                self.program[skip] = Inst::Jump(self.program.len());
+            }
+            Node::Repeat(min, max, mode, a) => self.emit_repeat(a, *min, *max, *mode)?,
+            Node::Set(set) => self.program.push(Inst::Set(set.clone())),
+            Node::Literal(chars) => self.program.push(Inst::Literal(chars.clone())),
+            Node::Assert(anchor @ (AnchorType::MatchStart | AnchorType::MatchEnd)) => {
+                self.program.push(Inst::Mark(*anchor))
+            }
+            Node::Assert(anchor) => self.program.push(Inst::Assert(*anchor)),
+            Node::Group(group, a) => {
+                self.program.push(Inst::Save(group * 2));
+                self.emit(a)?;
+                self.program.push(Inst::Save(group * 2 + 1));
+            }
+            Node::Look(kind, a) => {
+                let look = self.placeholder();
+                let skip = self.placeholder();
+                self.emit(a)?;
+                self.program.push(Inst::Match);
+                self.program[look] = Inst::Look(*kind, skip + 1);
+                self.program[skip] = Inst::Jump(self.program.len());
+            }
+            Node::Backreference(group, case_insensitive) => {
+                self.program.push(Inst::Backreference(*group, *case_insensitive))
+            }
+            Node::Balanced(open, close) => self.program.push(Inst::Balanced(*open, *close)),
+            Node::Frontier(set) => self.program.push(Inst::Frontier(set.clone())),
+        }
+        Ok(())
+    }
+
+    /// Append the instructions of `min` to `max` repetitions of a node, trying more
+    /// repetitions first when greedy and fewer first when lazy
+    fn emit_repeat(&mut self, node: &Node, min: u32, max: Option<u32>, mode: QuantifierMode) -> Result<(), Error> {
+        for _ in 0..min {
+            self.emit(node)?;
+        }
+        let register = self.register_count;
+        self.register_count += 1;
+        let optional = max.map(|max| max.saturating_sub(min));
+        let mut splits = Vec::new();
+        for _ in 0..optional.unwrap_or(1) {
+            let split = self.placeholder();
+            splits.push(split);
+            self.program.push(Inst::Enter(register));
+            self.emit(node)?;
+            // An empty repetition after the minimum would loop forever
+            self.program.push(Inst::Progress(register));
+            if optional.is_none() {
+                self.program.push(Inst::Jump(split));
+            }
+        }
+        let exit = self.program.len();
+        for split in splits {
+            self.program[split] = match mode {
+                QuantifierMode::Lazy => Inst::Split(exit, split + 1),
+                _ => Inst::Split(split + 1, exit),
+            };
+        }
+        Ok(())
+    }
+
+    /// Reserve an instruction to be filled in once the position it jumps to is known
+    fn placeholder(&mut self) -> usize {
+        self.program.push(Inst::Match);
+        self.program.len() - 1
+    }
+
+    /// Find every non-overlapping match in a line, from left to right
+    pub fn find_all(&self, line: &str) -> Result<Vec<Match>, Error> {
+        let text: Vec<char> = line.chars().collect();
+        // Byte offset of each character, plus the end of the line
+        let offsets: Vec<usize> = line
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(line.len()))
+            .collect();
+
+        let mut matches = Vec::new();
+        let mut pos = 0;
+        while pos <= text.len() {
+            let mut state = State {
+                text: &text,
+                captures: Captures {
+                    slots: vec![None; self.group_count * 2],
+                    registers: vec![0; self.register_count],
+                    marks: (None, None),
+                },
+                steps: 0,
+            };
+            match self.run(0, pos, None, &mut state)? {
+                Some(end) => {
+                    let captures = &state.captures;
+                    matches.push(Match {
+                        start: offsets[captures.marks.0.unwrap_or(pos)],
+                        end: offsets[captures.marks.1.unwrap_or(end)],
+                        groups: captures
+                            .slots
+                            .chunks(2)
+                            .map(|g| match (g[0], g[1]) {
+                                (Some(a), Some(b)) if a <= b => Some((offsets[a], offsets[b])),
+                                _ => None,
+                            })
+                            .collect(),
+                    });
+                    // Empty matches must still advance the search
+                    pos = if end > pos { end } else { pos + 1 };
+                }
+                None => pos += 1,
+            }
+        }
+        info!("Found {} matches", matches.len());
+        Ok(matches)
+    }
+
+    /// Run the program from an instruction and position, returning where the first way
+    /// of matching ends, which must be `end` if given
+    ///
+    /// Changes to the captures are kept if a match is found, and undone otherwise.
+    fn run(&self, pc: usize, pos: usize, end: Option<usize>, state: &mut State) -> Result<Option<usize>, Error> {
+        let mut stack = vec![Frame::Choice(pc, pos)];
+        'backtrack: while let Some(frame) = stack.pop() {
+            let (mut pc, mut pos) = match frame {
+                Frame::Choice(pc, pos) => (pc, pos),
+                Frame::Slot(slot, old) => {
+                    state.captures.slots[slot] = old;
+                    continue;
+                }
+                Frame::Register(register, old) => {
+                    state.captures.registers[register] = old;
+                    continue;
+                }
+                Frame::Marks(old) => {
+                    state.captures.marks = old;
+                    continue;
+                }
+                Frame::Restore(old) => {
+                    state.captures = *old;
+                    continue;
+                }
+            };
+            loop {
+                state.steps += 1;
+                if state.steps > STEP_LIMIT {
+                    return Err(Error::MatchLimit(STEP_LIMIT));
+                }
+                let text = state.text;
+                match &self.program[pc] {
+                    Inst::Match if end.is_none_or(|end| end == pos) => return Ok(Some(pos)),
+                    Inst::Match => continue 'backtrack,
+                    Inst::Set(set) => match text.get(pos) {
+                        Some(c) if set.contains(*c) => pos += 1,
+                        _ => continue 'backtrack,
+                    },
+                    Inst::Literal(chars) => match text[pos..].starts_with(chars) {
+                        true => pos += chars.len(),
+                        false => continue 'backtrack,
+                    },
+                    Inst::Mark(anchor) => {
+                        stack.push(Frame::Marks(state.captures.marks));
+                        match anchor {
+                            AnchorType::MatchStart => state.captures.marks.0 = Some(pos),
+                            _ => state.captures.marks.1 = Some(pos),
+                        }
+                    }
+                    Inst::Assert(anchor) => {
+                        let is_word = |c: Option<&char>| c.is_some_and(|c| c.is_alphanumeric() || *c == '_');
+                        let before = is_word(pos.checked_sub(1).and_then(|p| text.get(p)));
+                        let after = is_word(text.get(pos));
+                        let matched = match anchor {
+                            AnchorType::Start | AnchorType::PathStart => pos == 0,
+                            AnchorType::End => pos == text.len(),
+                            AnchorType::WordBoundary => before != after,
+                            AnchorType::NotWordBoundary => before == after,
+                            AnchorType::WordStart => !before && after,
+                            AnchorType::WordEnd => before && !after,
+                            // A directory is followed by the `/` before its contents
+                            AnchorType::DirectoryOnly => text.get(pos) == Some(&'/'),
+                            // Negation changes which paths are kept rather than which match
+                            AnchorType::MatchStart | AnchorType::MatchEnd | AnchorType::Negated => true,
+                        };
+                        if !matched {
+                            continue 'backtrack;
+                        }
+                    }
+                    Inst::Save(slot) => {
+                        stack.push(Frame::Slot(*slot, state.captures.slots[*slot]));
+                        state.captures.slots[*slot] = Some(pos);
+                    }
+                    Inst::Split(first, second) => {
+                        stack.push(Frame::Choice(*second, pos));
+                        pc = *first;
+                        continue;
+                    }
+                    Inst::Jump(target) => {
+                        pc = *target;
+                        continue;
+                    }
+                    Inst::Enter(register) => {
+                        stack.push(Frame::Register(*register, state.captures.registers[*register]));
+                        state.captures.registers[*register] = pos;
+                    }
+                    Inst::Progress(register) => {
+                        if state.captures.registers[*register] == pos {
+                            continue 'backtrack;
+                        }
+                    }
+                    Inst::Backreference(group, case_insensitive) => {
+                        // References to groups which have not matched fail
+                        let slots = &state.captures.slots;
+                        let (Some(start), Some(end)) = (slots[group * 2], slots[group * 2 + 1]) else {
+                            continue 'backtrack;
+                        };
+                        let len = end.saturating_sub(start);
+                        let matched = text.get(pos..pos + len).is_some_and(|x| {
+                            x.iter().zip(&text[start..start + len]).all(|(a, b)| {
+                                a == b || (*case_insensitive && a.to_lowercase().eq(b.to_lowercase()))
+                            })
+                        });
+                        match matched {
+                            true => pos += len,
+                            false => continue 'backtrack,
+                        }
+                    }
+                    Inst::Balanced(open, close) => {
+                        if text.get(pos) != Some(open) {
+                            continue 'backtrack;
+                        }
+                        let mut depth = 0;
+                        let closing = text.iter().enumerate().skip(pos + 1).find(|(_, c)| {
+                            if *c == close && depth == 0 {
+                                return true;
+                            } else if *c == close {
+                                depth -= 1;
+                            } else if *c == open {
+                                depth += 1;
+                            }
+                            false
+                        });
+                        match closing {
+                            Some((idx, _)) => pos = idx + 1,
+                            None => continue 'backtrack,
+                        }
+                    }
+                    // The ends of the line count as the null character, as in Lua
+                    Inst::Frontier(set) => {
+                        let before = pos.checked_sub(1).and_then(|p| text.get(p)).copied().unwrap_or('\0');
+                        let after = text.get(pos).copied().unwrap_or('\0');
+                        if set.contains(before) || !set.contains(after) {
+                            continue 'backtrack;
+                        }
+                    }
+                    Inst::Atomic(start) => {
+                        let saved = state.captures.clone();
+                        match self.run(*start, pos, None, state)? {
+                            Some(end) => {
+                                stack.push(Frame::Restore(Box::new(saved)));
+                                pos = end;
+                            }
+                            None => continue 'backtrack,
+                        }
+                    }
+                    Inst::Look(kind, start) => {
+                        // Lookarounds are atomic, so only the first way of matching is tried
+                        let saved = state.captures.clone();
+                        let found = match kind {
+                            LookaroundType::Ahead | LookaroundType::NotAhead => {
+                                self.run(*start, pos, None, state)?.is_some()
+                            }
+                            LookaroundType::Behind | LookaroundType::NotBehind => {
+                                let mut found = false;
+                                for from in (0..=pos).rev() {
+                                    if self.run(*start, from, Some(pos), state)?.is_some() {
+                                        found = true;
+                                        break;
+                                    }
+                                }
+                                found
+                            }
+                        };
+                        let negated = matches!(kind, LookaroundType::NotAhead | LookaroundType::NotBehind);
+                        if found == negated {
+                            state.captures = saved;
+                            continue 'backtrack;
+                        }
+                        stack.push(Frame::Restore(Box::new(saved)));
+                    }
+                }
+                pc += 1;
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        extract::Language,
+        flags::Flags,
+        matcher::{Match, Matcher, PROGRAM_LIMIT, STEP_LIMIT},
+        parser::{Flavor, RegExParser},
+    };
+
+    fn find_all(regex: &str, line: &str) -> Vec<Match> {
+        let tree = RegExParser::new(Language::Rust, &regex.to_string()).parse().unwrap();
+        Matcher::new(&tree).unwrap().find_all(line).unwrap()
+    }
+
+    #[test]
+    fn test_match_spans() {
+        let matches = find_all("[0-9]+", "a1 22 333");
+        let spans: Vec<(usize, usize)> = matches.iter().map(|m| (m.start, m.end)).collect();
+        assert_eq!(spans, vec![(1, 2), (3, 5), (6, 9)]);
+
+        // Backtracking out of a greedy repetition
+        let matches = find_all("a.*b", "xaxbxbx");
+        assert_eq!((matches[0].start, matches[0].end), (1, 6));
+
+        // Anchors and word boundaries
+        assert_eq!(find_all("^a", "aa").len(), 1);
+        assert_eq!(find_all("\\bcat\\b", "cat concat cat").len(), 2);
+    }
+
+    #[test]
+    fn test_match_groups() {
+        let matches = find_all("(\\w+)@(?<host>\\w+)(\\.com)?", "me@example.org");
+        assert_eq!(matches[0].groups, vec![Some((0, 2)), Some((3, 10)), None]);
+
+        // Repeated groups hold the last repetition, with byte offsets for non-ASCII text
+        let matches = find_all("(é|b)+", "éb");
+        assert_eq!((matches[0].start, matches[0].end), (0, 3));
+        assert_eq!(matches[0].groups, vec![Some((2, 3))]);
+    }
+
+    #[test]
+    fn test_match_vim() {
+        let spans = |regex: &str, line: &str| -> Vec<(usize, usize)> {
+            let tree = RegExParser::new(Language::Rust, &regex.to_string()).with_flavor(Flavor::Vim).parse().unwrap();
+            let matches = Matcher::new(&tree).unwrap().find_all(line).unwrap();
+            matches.iter().map(|m| (m.start, m.end)).collect()
+        };
+        assert_eq!(spans(r"foo\zsbar\zebaz", "foobar foobarbaz"), vec![(10, 13)]);
+        assert_eq!(spans(r"\<at\>", "cat at"), vec![(4, 6)]);
+        assert_eq!(spans(r"\<fu\%[nction]\>", "fu func funcx function"), vec![(0, 2), (3, 7), (14, 22)]);
+    }
+
+    #[test]
+    fn test_match_lua() {
+        let spans = |regex: &str, line: &str| -> Vec<(usize, usize)> {
+            let tree = RegExParser::new(Language::Lua, &regex.to_string()).parse().unwrap();
+            let matches = Matcher::new(&tree).unwrap().find_all(line).unwrap();
+            matches.iter().map(|m| (m.start, m.end)).collect()
+        };
+        assert_eq!(spans("%b()", "f(a(b)c) (d"), vec![(1, 8)]);
+        assert_eq!(spans("%f[%a]%a+", "THE (quick) fox"), vec![(0, 3), (5, 10), (12, 15)]);
+        assert_eq!(spans("<.->", "<a><b>"), vec![(0, 3), (3, 6)]);
+    }
+
+    #[test]
+    fn test_match_wildcards() {
+        let spans = |regex: &str, flavor: Flavor, line: &str| -> Vec<(usize, usize)> {
+            let tree = RegExParser::new(Language::Gitignore, &regex.to_string()).with_flavor(flavor).parse().unwrap();
+            let matches = Matcher::new(&tree).unwrap().find_all(line).unwrap();
+            matches.iter().map(|m| (m.start, m.end)).collect()
+        };
+        assert_eq!(spans("src/**/*.rs", Flavor::Glob, "src/a/b/c.rs"), vec![(0, 12)]);
+        assert_eq!(spans("*.{c,h}", Flavor::Glob, "a/b.h"), vec![(2, 5)]);
+        assert_eq!(spans("/build/", Flavor::Gitignore, "build/out.o"), vec![(0, 5)]);
+        assert!(spans("/build/", Flavor::Gitignore, "src/build/a").is_empty());
+        assert!(spans("build/", Flavor::Gitignore, "build.rs").is_empty());
+
+        // LIKE and SIMILAR TO patterns match the whole value
+        assert_eq!(spans("a%_", Flavor::Like, "abc"), vec![(0, 3)]);
+        assert!(spans("a%_", Flavor::Like, "ca").is_empty());
+        assert_eq!(spans("(ab|c)+.", Flavor::SimilarTo, "abc."), vec![(0, 4)]);
+    }
+
+    #[test]
+    fn test_match_flags() {
+        let tree = RegExParser::new(Language::Javascript, &String::from("ab[c-d]."))
+            .parse()
+            .unwrap();
+        let flags = Flags::from_chars("is", "is").unwrap();
+        let matcher = Matcher::with_flags(&tree, &flags).unwrap();
+        assert_eq!(matcher.find_all("ABD\n").unwrap().len(), 1);
+
+        let matcher = Matcher::new(&tree).unwrap();
+        assert_eq!(matcher.find_all("ABDx").unwrap().len(), 0);
+        assert_eq!(matcher.find_all("abd\n").unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_match_lookaround() {
+        let spans = |regex: &str, line: &str| -> Vec<(usize, usize)> {
+            find_all(regex, line).iter().map(|m| (m.start, m.end)).collect()
+        };
+        assert_eq!(spans("\\w+(?=!)", "hi there!"), vec![(3, 8)]);
+        assert_eq!(spans("(?<!\\$)\\b\\d+", "$10 20"), vec![(4, 6)]);
+        assert_eq!(spans("(\\w)\\1", "abccd"), vec![(2, 4)]);
+
+        // Lazy and possessive repetitions
+        assert_eq!(spans("<.+?>", "<a><b>"), vec![(0, 3), (3, 6)]);
+        assert_eq!(spans("a++a", "aaa"), vec![]);
+    }
+
+    #[test]
+    fn test_match_property() {
+        let tree = RegExParser::new(Language::Java, &String::from(r"\p{javaLowerCase}++[\P{L}]"))
+            .parse()
+            .unwrap();
+        let matches = Matcher::new(&tree).unwrap().find_all("ABcdé1").unwrap();
+        assert_eq!((matches[0].start, matches[0].end), (2, 7));
+    }
+
+    #[test]
+    fn test_match_long_line() {
+        // Backtracking does not grow the call stack with the length of the line
+        let line = "a".repeat(20_000);
+        assert_eq!(find_all("a*", &line)[0].end, 20_000);
+        assert_eq!(find_all("(a|b)*c?", &line)[0].groups, vec![Some((19_999, 20_000))]);
+
+        // Each start position has its own budget of steps
+        assert!(find_all(".*x", &"a".repeat(1_000)).is_empty());
+        assert_eq!(find_all("a{2}(a{0,3}?)b", "aaaab")[0].groups, vec![Some((2, 4))]);
+    }
+
+    #[test]
+    fn test_match_limits() {
+        let tree = RegExParser::new(Language::Rust, &String::from("(a{1000}){1000}")).parse().unwrap();
+        assert!(matches!(Matcher::new(&tree), Err(Error::ProgramLimit(PROGRAM_LIMIT))));
+
+        // Catastrophic backtracking stops at the step limit
+        let tree = RegExParser::new(Language::Rust, &String::from("(a*)*b")).parse().unwrap();
+        let matched = Matcher::new(&tree).unwrap().find_all(&"a".repeat(30));
+        assert!(matches!(matched, Err(Error::MatchLimit(STEP_LIMIT))));
+    }
+}
@@ -1,9 +1,19 @@
-use crate::{error::Error, extract::{Language, STRING_FORMAT}};
+use crate::{error::Error, extract::{Language, StringFormat, STRING_FORMAT}, flags::Flags};
 use lazy_static::lazy_static;
+use std::fmt::Display;
 use tracing::info;
 
+pub mod glob;
+pub mod lua;
+pub mod posix;
+pub mod sql;
+pub mod vim;
+
 lazy_static! {
-    static ref SPECIAL_CHARS: Vec<char> = vec!['(', ')', '[', ']', '+', '*', '$', '|', '^', '{', '}'];
+    static ref SPECIAL_CHARS: Vec<char> = vec!['(', ')', '[', ']', '+', '*', '?', '.', '\\', '$', '|', '^', '{', '}'];
+
+    /// Characters which quantify the preceding atom
+    static ref QUANTIFIER_CHARS: Vec<char> = vec!['*', '+', '?', '{'];
 }
 
 #[derive(Eq, PartialEq, Debug)]
@@ -14,7 +24,17 @@ pub enum RegEx {
     Character(CharacterType),
     Anchor(AnchorType),
     Terminal(String),
-    Capture(Option<String>, usize, Box<RegEx>)
+    Capture(Option<String>, usize, Box<RegEx>),
+    Lookaround(LookaroundType, Box<RegEx>),
+    Backreference(Option<String>, usize),
+    /// A lazy or possessive version of the `Repetition` it wraps
+    Quantifier(QuantifierMode, Box<RegEx>),
+    /// Vim's `\%[...]`, a sequence of atoms matched in order for as long as they match
+    OptionalSequence(Vec<Box<RegEx>>),
+    /// Lua's `%bxy`, text from `x` to its matching `y`, counting nested pairs
+    Balanced(char, char),
+    /// Lua's `%f[set]`, the position between a character not in the set and one in it
+    Frontier(Box<RegEx>)
 }
 
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
@@ -39,48 +59,368 @@ pub enum MetaCharacter {
     Word(bool),
     Digit(bool),
     Whitespace(bool),
-    Any
+    Any,
+    /// Unicode or Java character property such as `\p{Lu}` or `\p{javaLowerCase}`
+    Property(String, bool)
 }
 
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
 pub enum AnchorType {
     Start,
     End,
     WordBoundary,
-    NotWordBoundary
+    NotWordBoundary,
+    /// Vim's `\<`, the start of a word
+    WordStart,
+    /// Vim's `\>`, the end of a word
+    WordEnd,
+    /// Vim's `\zs`, where the reported match starts
+    MatchStart,
+    /// Vim's `\ze`, where the reported match ends
+    MatchEnd,
+    /// A `.gitignore` pattern containing a `/`, which only matches paths relative to the
+    /// directory of the file
+    PathStart,
+    /// A `.gitignore` pattern's trailing `/`, which only matches directories
+    DirectoryOnly,
+    /// A glob's leading `!`, which excludes the paths it matches
+    Negated
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum LookaroundType {
+    Ahead,
+    NotAhead,
+    Behind,
+    NotBehind
+}
+
+/// How a repetition chooses the number of times to repeat
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum QuantifierMode {
+    /// As many times as possible, backtracking to fewer
+    Greedy,
+    /// As few times as possible, backtracking to more
+    Lazy,
+    /// As many times as possible, never backtracking
+    Possessive
+}
+
+/// Regular expression syntax accepted by an engine
+#[derive(Clone, Copy, Default, Eq, Hash, PartialEq, Debug)]
+pub enum Flavor {
+    #[default]
+    Pcre,
+    Re2,
+    Java,
+    /// The ECMAScript grammar of C++ `std::regex`
+    Ecmascript,
+    /// Vim patterns, with their magic levels
+    Vim,
+    /// Lua patterns, which have no alternation and escape with `%`
+    Lua,
+    /// POSIX basic regular expressions, as used by `grep` and `sed`
+    Bre,
+    /// POSIX extended regular expressions, as used by `grep -E` and `awk`
+    Ere,
+    /// Shell and build system globs, with `**` and `{a,b}`
+    Glob,
+    /// `.gitignore` patterns, which are globs anchored by their slashes
+    Gitignore,
+    /// SQL `LIKE` patterns, with the `%` and `_` wildcards
+    Like,
+    /// SQL `SIMILAR TO` patterns, which add groups, alternation and quantifiers to `LIKE`
+    SimilarTo,
+}
+
+impl Flavor {
+    /// Every flavor, in the order they are listed to users
+    pub const ALL: &'static [Flavor] = &[
+        Flavor::Pcre,
+        Flavor::Re2,
+        Flavor::Java,
+        Flavor::Ecmascript,
+        Flavor::Vim,
+        Flavor::Lua,
+        Flavor::Bre,
+        Flavor::Ere,
+        Flavor::Glob,
+        Flavor::Gitignore,
+        Flavor::Like,
+        Flavor::SimilarTo,
+    ];
+
+    /// Flavor with the given display name, ignoring case
+    pub fn from_name(name: &str) -> Option<Flavor> {
+        Flavor::ALL.iter().copied().find(|x| x.to_string().eq_ignore_ascii_case(name))
+    }
+
+    /// Whether the engine supports `(?=...)` and `(?!...)`
+    pub fn supports_lookahead(&self) -> bool {
+        !matches!(self, Flavor::Re2 | Flavor::Bre | Flavor::Ere | Flavor::Like | Flavor::SimilarTo)
+    }
+
+    /// Whether the engine supports `(?<=...)` and `(?<!...)`
+    pub fn supports_lookbehind(&self) -> bool {
+        !matches!(
+            self,
+            Flavor::Re2 | Flavor::Ecmascript | Flavor::Bre | Flavor::Ere | Flavor::Like | Flavor::SimilarTo
+        )
+    }
+
+    /// Whether the engine supports `(?<name>...)` and `(?P<name>...)`
+    pub fn supports_named_groups(&self) -> bool {
+        !matches!(
+            self,
+            Flavor::Ecmascript | Flavor::Vim | Flavor::Bre | Flavor::Ere | Flavor::Like | Flavor::SimilarTo
+        )
+    }
+
+    /// Whether the engine supports groups starting with `(?`, such as `(?:...)`
+    pub fn supports_group_extensions(&self) -> bool {
+        !matches!(self, Flavor::Bre | Flavor::Ere | Flavor::Like | Flavor::SimilarTo)
+    }
+
+    /// Whether the engine supports `\1` and `\k<name>`
+    pub fn supports_backreferences(&self) -> bool {
+        !matches!(self, Flavor::Re2)
+    }
+
+    /// Whether the engine supports possessive quantifiers such as `a*+`
+    pub fn supports_possessive(&self) -> bool {
+        matches!(self, Flavor::Pcre | Flavor::Java)
+    }
+
+    /// Whether the engine supports lazy quantifiers such as `a*?`
+    pub fn supports_lazy(&self) -> bool {
+        !matches!(self, Flavor::Bre | Flavor::Ere | Flavor::Like | Flavor::SimilarTo)
+    }
+
+    /// Whether a backslash in a character class escapes the next character, rather than
+    /// matching itself as in POSIX bracket expressions
+    pub fn supports_class_escapes(&self) -> bool {
+        !matches!(self, Flavor::Bre | Flavor::Ere | Flavor::Like | Flavor::SimilarTo)
+    }
+
+    /// Whether the engine supports GNU's `\<` and `\>` word anchors
+    pub fn supports_word_anchors(&self) -> bool {
+        matches!(self, Flavor::Bre | Flavor::Ere)
+    }
+}
+
+impl Display for Flavor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Flavor::Pcre => write!(f, "PCRE"),
+            Flavor::Re2 => write!(f, "RE2"),
+            Flavor::Java => write!(f, "Java"),
+            Flavor::Ecmascript => write!(f, "ECMAScript"),
+            Flavor::Vim => write!(f, "Vim"),
+            Flavor::Lua => write!(f, "Lua"),
+            Flavor::Bre => write!(f, "BRE"),
+            Flavor::Ere => write!(f, "ERE"),
+            Flavor::Glob => write!(f, "Glob"),
+            Flavor::Gitignore => write!(f, "Gitignore"),
+            Flavor::Like => write!(f, "LIKE"),
+            Flavor::SimilarTo => write!(f, "SIMILAR TO"),
+        }
+    }
+}
+
+/// Character represented by an escape sequence such as `\n`
+fn escaped_char(c: char) -> char {
+    match c {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        'f' => '\x0c',
+        'v' => '\x0b',
+        '0' => '\0',
+        other => other,
+    }
+}
+
+/// Remove the whitespace and `#` comments ignored by extended mode, keeping any which
+/// are escaped or inside a character class
+fn strip_extended(text: &str) -> String {
+    let mut stripped = String::new();
+    let mut chars = text.chars();
+    let mut in_class = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                stripped.push(c);
+                if let Some(next) = chars.next() {
+                    stripped.push(next);
+                }
+            }
+            '[' => {
+                in_class = true;
+                stripped.push(c);
+            }
+            ']' => {
+                in_class = false;
+                stripped.push(c);
+            }
+            '#' if !in_class => {
+                for comment in chars.by_ref() {
+                    if comment == '\n' {
+                        break;
+                    }
+                }
+            }
+            c if c.is_whitespace() && !in_class => (),
+            c => stripped.push(c),
+        }
+    }
+    stripped
 }
 
 pub struct RegExParser {
-    language: Language,
+    flavor: Flavor,
+    /// Escape character of the language's strings, which also escapes literal characters
+    escape: char,
     text: String,
     idx: usize,
-    capture_group: usize
+    capture_group: usize,
+    group_spans: Vec<(usize, usize)>,
+    group_names: Vec<Option<String>>
 }
 
 impl RegExParser {
-    /// Create new instance of RegExParser
+    /// Create new instance of RegExParser, using the language's default flavor
     pub fn new(language: Language, text: &String) -> RegExParser {
+        let string_format = STRING_FORMAT.get(&language);
         RegExParser {
-            language,
+            flavor: string_format.map(|fmt| fmt.flavor()).unwrap_or_default(),
+            escape: string_format.map(|fmt| fmt.escape_char()).unwrap_or('\\'),
             text: text.to_string(),
             idx: 0,
-            capture_group: 0
+            capture_group: 0,
+            group_spans: Vec::new(),
+            group_names: Vec::new()
+        }
+    }
+
+    /// Use the flavor and escape character of a string format, such as that of a language
+    /// defined by the user, rather than those of the built-in language
+    #[must_use]
+    pub fn with_string_format(mut self, string_format: &StringFormat) -> Self {
+        self.flavor = string_format.flavor();
+        self.escape = string_format.escape_char();
+        self
+    }
+
+    /// Parse the syntax of a specific regular expression engine
+    #[must_use]
+    pub fn with_flavor(mut self, flavor: Flavor) -> Self {
+        self.flavor = flavor;
+        self
+    }
+
+    /// Apply flags which change how the text is parsed
+    #[must_use]
+    pub fn with_flags(mut self, flags: Flags) -> Self {
+        if flags.extended {
+            self.text = strip_extended(&self.text);
+        }
+        self
+    }
+
+    /// Text being parsed, after any flags have been applied
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Character span of each capture group in the text, indexed by group number - 1
+    pub fn group_spans(&self) -> &[(usize, usize)] {
+        &self.group_spans
+    }
+
+    /// Number the next capture group, which starts at character `start`
+    fn open_capture(&mut self, start: usize, name: Option<&str>) -> usize {
+        self.capture_group += 1;
+        self.group_spans.push((start, start));
+        self.group_names.push(name.map(String::from));
+        self.capture_group
+    }
+
+    /// Reject syntax the flavor does not support
+    fn require(&self, supported: bool, construct: &str) -> Result<(), Error> {
+        if supported {
+            Ok(())
+        } else {
+            Err(Error::UnsupportedSyntax(self.flavor, construct.to_string()))
+        }
+    }
+
+    /// Read a group name up to and including the closing character
+    fn group_name(&mut self, close: char) -> Result<String, Error> {
+        let mut name = String::new();
+        while self.more() && self.peek()? != close {
+            name.push(self.next()?);
+        }
+        self.close(close)?;
+        Ok(name)
+    }
+
+    /// Read the name of a `\p` character property, either braced or a single letter
+    fn property_name(&mut self) -> Result<String, Error> {
+        if self.more() && self.peek()? == '{' {
+            self.consume('{')?;
+            self.group_name('}')
+        } else {
+            Ok(self.next()?.to_string())
+        }
+    }
+
+    /// Backreference to a named group which has already been opened
+    fn named_backreference(&self, name: String) -> Result<RegEx, Error> {
+        self.require(self.flavor.supports_backreferences(), "backreferences")?;
+        match self.group_names.iter().position(|x| x.as_deref() == Some(name.as_str())) {
+            Some(idx) => Ok(RegEx::Backreference(Some(name), idx + 1)),
+            None => Err(Error::UnknownGroup(name)),
+        }
+    }
+
+    /// Backreference to a numbered group which has already been opened
+    fn numbered_backreference(&self, digits: String) -> Result<RegEx, Error> {
+        match digits.parse::<usize>() {
+            Ok(group) if group <= self.capture_group => Ok(RegEx::Backreference(None, group)),
+            _ => Err(Error::UnknownGroup(digits)),
         }
     }
 
     pub fn parse(&mut self) -> Result<RegEx, Error> {
+        // Vim patterns are rewritten as very magic, so that only one magic level is parsed
+        if self.flavor == Flavor::Vim {
+            self.text = vim::very_magic(&self.text);
+        }
+        if self.flavor == Flavor::Lua {
+            return self.lua_pattern();
+        }
+        if matches!(self.flavor, Flavor::Glob | Flavor::Gitignore) {
+            return self.glob_pattern();
+        }
+        if matches!(self.flavor, Flavor::Like | Flavor::SimilarTo) {
+            return self.sql_pattern();
+        }
+        // Basic patterns are rewritten as extended ones, which are parsed like other flavors
+        if self.flavor == Flavor::Bre {
+            self.text = posix::extended(&self.text);
+        }
         self.alternation()
     }
 
     fn alternation(&mut self) -> Result<RegEx, Error> {
         let elem1 = self.element()?;
-        if !self.more() || self.peek() != '|' {
+        if !self.more() || self.peek()? != '|' {
             Ok(elem1)
         } else {
             // Check for OR
             let mut v = vec![Box::new(elem1)];
-            while self.more() && self.peek() == '|' {
-                self.consume('|').unwrap();
+            while self.more() && self.peek()? == '|' {
+                self.consume('|')?;
                 v.push(Box::new(self.element()?));
             }
             Ok(RegEx::Alternation(v))
@@ -89,7 +429,7 @@ impl RegExParser {
 
     fn element(&mut self) -> Result<RegEx, Error> {
         let mut v = Vec::new();
-        while self.more() && self.peek() != ')' && self.peek() != '|' {
+        while self.more() && self.peek()? != ')' && self.peek()? != '|' {
             let r = self.repetition()?;
             v.push(Box::new(r));
         }
@@ -97,26 +437,44 @@ impl RegExParser {
     }
 
     fn repetition(&mut self) -> Result<RegEx, Error> {
+        if self.flavor == Flavor::Vim {
+            return self.vim_repetition();
+        }
         let b = self.group()?;
-        if self.more() {
-            match self.peek() {
-                '*' => {
-                    self.consume('*')?;
-                    Ok(RegEx::Repetition(RepetitionType::OrMore(0), Box::new(b)))
-                }
-                '+' => {
-                    self.consume('+')?;
-                    Ok(RegEx::Repetition(RepetitionType::OrMore(1), Box::new(b)))
-                }
-                '?' => {
-                    self.consume('?')?;
-                    Ok(RegEx::Repetition(RepetitionType::ZeroOrOne, Box::new(b)))
-                }
-                '{' => Ok(RegEx::Repetition(self.repetition_group()?, Box::new(b))),
-                _ => Ok(b),
+        if !self.more() {
+            return Ok(b);
+        }
+        let repetition = match self.peek()? {
+            '*' => {
+                self.consume('*')?;
+                RepetitionType::OrMore(0)
             }
-        } else {
-            Ok(b)
+            '+' => {
+                self.consume('+')?;
+                RepetitionType::OrMore(1)
+            }
+            '?' => {
+                self.consume('?')?;
+                RepetitionType::ZeroOrOne
+            }
+            '{' => self.repetition_group()?,
+            _ => return Ok(b),
+        };
+        let repeated = RegEx::Repetition(repetition, Box::new(b));
+
+        // A trailing '?' or '+' changes how the repetition backtracks
+        match self.peek_n(0) {
+            Some('?') => {
+                self.require(self.flavor.supports_lazy(), "lazy quantifiers")?;
+                self.consume('?')?;
+                Ok(RegEx::Quantifier(QuantifierMode::Lazy, Box::new(repeated)))
+            }
+            Some('+') => {
+                self.require(self.flavor.supports_possessive(), "possessive quantifiers")?;
+                self.consume('+')?;
+                Ok(RegEx::Quantifier(QuantifierMode::Possessive, Box::new(repeated)))
+            }
+            _ => Ok(repeated),
         }
     }
 
@@ -127,8 +485,8 @@ impl RegExParser {
         let mut max_count: Option<u32> = None;
         let mut two_num: bool = false;
         // Capture minimum count
-        while self.more() && self.peek() != '}' {
-            match self.peek() {
+        while self.more() && self.peek()? != '}' {
+            match self.peek()? {
                 num @ '0'..='9' => {
                     self.consume(num)?;
                     // Multiply by 10 to account for more than 1 digit numbers
@@ -148,22 +506,22 @@ impl RegExParser {
             }
         }
         // If maximum count is present, try to capture it
-        while self.more() && self.peek() != '}' {
-            match self.peek() {
+        while self.more() && self.peek()? != '}' {
+            match self.peek()? {
                 num @ '0'..='9' => {
                     match max_count {
                         Some(count) => {
                             max_count = Some(
                                 10 * count
                                     + self
-                                        .peek()
+                                        .peek()?
                                         .to_digit(10)
                                         .expect("Current char already checked to be in '0'..='9'"),
                             )
                         }
                         None => {
                             max_count = Some(
-                                self.peek()
+                                self.peek()?
                                     .to_digit(10)
                                     .expect("Current char already checked to be in '0'..='9'"),
                             )
@@ -177,7 +535,7 @@ impl RegExParser {
             }
         }
 
-        if self.more() && self.peek() == '}' {
+        if self.more() && self.peek()? == '}' {
             self.consume('}')?;
         }
         // Return final repetition type based on numbers found
@@ -194,42 +552,82 @@ impl RegExParser {
     }
 
     fn group(&mut self) -> Result<RegEx, Error> {
-        if self.peek() == '(' {
+        if self.flavor == Flavor::Vim {
+            if let Some(atom) = self.vim_atom()? {
+                return Ok(atom);
+            }
+        }
+        if self.peek()? == '(' {
+            let start = self.idx;
             self.consume('(')?;
-            let a = match self.peek() {
+            let mut group = None;
+            let a = match self.peek()? {
                 '?' => {
+                    self.require(self.flavor.supports_group_extensions(), "(?...) groups")?;
                     self.consume('?')?;
-                    if self.peek() == ':' {
-                        // Unnamed capture group
+                    if self.peek()? == ':' {
+                        // Non-capturing group
                         self.consume(':')?;
-                        self.capture_group += 1;
-                        RegEx::Capture(None, self.capture_group, Box::new(self.alternation()?))
+                        self.alternation()?
                     }
-                    else if self.peek() == '<' {
+                    else if self.peek()? == '=' || self.peek()? == '!'
+                        || (self.peek()? == '<' && matches!(self.peek_n(1), Some('=') | Some('!')))
+                    {
+                        // Lookahead and lookbehind
+                        let behind = self.peek()? == '<';
+                        if behind {
+                            self.consume('<')?;
+                            self.require(self.flavor.supports_lookbehind(), "lookbehind")?;
+                        } else {
+                            self.require(self.flavor.supports_lookahead(), "lookahead")?;
+                        }
+                        let kind = match (behind, self.next()?) {
+                            (false, '=') => LookaroundType::Ahead,
+                            (false, _) => LookaroundType::NotAhead,
+                            (true, '=') => LookaroundType::Behind,
+                            (true, _) => LookaroundType::NotBehind,
+                        };
+                        RegEx::Lookaround(kind, Box::new(self.alternation()?))
+                    }
+                    else if self.peek()? == 'P' && self.peek_n(1) == Some('=') {
+                        // Python named backreference
+                        self.consume('P')?;
+                        self.consume('=')?;
+                        let name = self.group_name(')')?;
+                        return self.named_backreference(name);
+                    }
+                    else if self.peek()? == '<' || (self.peek()? == 'P' && self.peek_n(1) == Some('<')) {
                         // Named capture group
-                        self.consume('<')?;
-                        let mut name = String::new();
-                        while self.more() && self.peek() != '>' {
-                            name = format!("{}{}", name, self.next()?);
+                        self.require(self.flavor.supports_named_groups(), "named groups")?;
+                        if self.peek()? == 'P' {
+                            self.consume('P')?;
                         }
-                        self.consume('>')?;
-                        self.capture_group += 1;
+                        self.consume('<')?;
+                        let name = self.group_name('>')?;
+                        group = Some(self.open_capture(start, Some(&name)));
                         RegEx::Capture(Some(name), self.capture_group, Box::new(self.alternation()?))
                     }
                     else {
                         return Err(Error::InvalidCharacter('?', self.idx))
                     }
                 },
-                _ => self.alternation()?
+                _ => {
+                    // Unnamed capture group
+                    group = Some(self.open_capture(start, None));
+                    RegEx::Capture(None, self.capture_group, Box::new(self.alternation()?))
+                }
             };
-            self.consume(')').unwrap();
+            self.close(')')?;
+            if let Some(group) = group {
+                self.group_spans[group - 1].1 = self.idx;
+            }
             Ok(a)
-        } else if self.peek() == '[' {
-            self.consume('[').unwrap();
+        } else if self.peek()? == '[' {
+            self.consume('[')?;
             let a = self.character()?;
-            self.consume(']').unwrap();
+            self.close(']')?;
             Ok(RegEx::Character(a))
-        } else if self.peek() == '\\' {
+        } else if self.peek()? == '\\' {
             self.consume('\\')?;
             let character_type = match self.next()? {
                 'w' => CharacterType::Meta(MetaCharacter::Word(true)),
@@ -238,11 +636,29 @@ impl RegExParser {
                 'D' => CharacterType::Meta(MetaCharacter::Digit(false)),
                 's' => CharacterType::Meta(MetaCharacter::Whitespace(true)),
                 'S' => CharacterType::Meta(MetaCharacter::Whitespace(false)),
-                other => return Ok(RegEx::Terminal(other.to_string()))
+                c @ ('p' | 'P') => CharacterType::Meta(MetaCharacter::Property(self.property_name()?, c == 'p')),
+                '<' if self.flavor.supports_word_anchors() => return Ok(RegEx::Anchor(AnchorType::WordStart)),
+                '>' if self.flavor.supports_word_anchors() => return Ok(RegEx::Anchor(AnchorType::WordEnd)),
+                'b' => return Ok(RegEx::Anchor(AnchorType::WordBoundary)),
+                'B' => return Ok(RegEx::Anchor(AnchorType::NotWordBoundary)),
+                'k' if self.more() && self.peek()? == '<' => {
+                    self.consume('<')?;
+                    let name = self.group_name('>')?;
+                    return self.named_backreference(name);
+                }
+                digit @ '1'..='9' => {
+                    self.require(self.flavor.supports_backreferences(), "backreferences")?;
+                    let mut digits = digit.to_string();
+                    while self.more() && self.peek()?.is_ascii_digit() {
+                        digits.push(self.next()?);
+                    }
+                    return self.numbered_backreference(digits);
+                }
+                other => return Ok(RegEx::Terminal(escaped_char(other).to_string()))
             };
             Ok(RegEx::Character(character_type))
-        } else if self.peek() == '^' || self.peek() == '$' {
-            match self.peek() {
+        } else if self.peek()? == '^' || self.peek()? == '$' {
+            match self.peek()? {
                 '^' => {
                     self.consume('^')?;
                     Ok(RegEx::Anchor(AnchorType::Start))
@@ -253,30 +669,42 @@ impl RegExParser {
                 },
                 _ => Ok(RegEx::Terminal(String::from("")))
             }
-        } else if self.peek() == '.' {
+        } else if self.peek()? == '.' {
             self.consume('.')?;
             Ok(RegEx::Character(CharacterType::Meta(MetaCharacter::Any)))
         } else {
             let mut string = String::from("");
-            while self.more() && !SPECIAL_CHARS.contains(&self.peek()) {
-                let fmt = STRING_FORMAT.get(&self.language).expect("Language is supported");
-                if self.peek() == fmt.escape_char() {
-                    self.consume(fmt.escape_char())?;
+            while self.more() && !self.is_special(self.peek()?) {
+                // A quantifier only applies to the last character, so leave it for the next atom
+                if !string.is_empty() && self.peek_n(1).is_some_and(|c| self.is_quantifier(c)) {
+                    break;
+                }
+                if self.peek()? == self.escape {
+                    self.consume(self.escape)?;
                 }
                 string = format!("{}{}", string, self.next()?);
             }
+            // Unmatched special characters are treated literally to guarantee progress
+            if string.is_empty() {
+                string.push(self.next()?);
+            }
             Ok(RegEx::Terminal(string))
         }
     }
 
     fn character(&mut self) -> Result<CharacterType, Error> {
         let mut match_char = true;
-        if self.peek() == '^' {
-            self.consume('^').unwrap();
+        if self.peek()? == '^' {
+            self.consume('^')?;
             match_char = false;
         }
         let mut v = Vec::new();
-        while self.more() && self.peek() != ']' {
+        // Without escapes, a `]` straight after the opening bracket is a member
+        if !self.flavor.supports_class_escapes() && self.more() && self.peek()? == ']' {
+            self.consume(']')?;
+            v.push(Box::new(CharacterType::Terminal(']')));
+        }
+        while self.more() && self.peek()? != ']' {
             let c = self.next_character()?;
             v.push(c);
         }
@@ -288,14 +716,27 @@ impl RegExParser {
     }
 
     fn next_character(&mut self) -> Result<Box<CharacterType>, Error> {
-        let c = match self.peek() {
+        let c = match self.peek()? {
+            // POSIX classes such as `[:alpha:]`
+            '[' if self.peek_n(1) == Some(':') => {
+                self.consume('[')?;
+                self.consume(':')?;
+                let name = self.group_name(':')?;
+                self.close(']')?;
+                let property = posix::class_name(&name).ok_or(Error::UnknownProperty(name))?;
+                CharacterType::Meta(MetaCharacter::Property(property.to_string(), true))
+            }
+            '\\' if !self.flavor.supports_class_escapes() => {
+                self.consume('\\')?;
+                CharacterType::Terminal('\\')
+            }
             digit_a @ '0'..='9' => {
-                self.consume(digit_a).unwrap();
-                if self.peek() == '-' {
-                    self.consume('-').unwrap();
-                    match self.peek() {
+                self.consume(digit_a)?;
+                if self.peek()? == '-' {
+                    self.consume('-')?;
+                    match self.peek()? {
                         digit_b @ '0'..='9' => {
-                            self.consume(digit_b).unwrap();
+                            self.consume(digit_b)?;
                             CharacterType::Between(
                                 Box::new(CharacterType::Terminal(digit_a)),
                                 Box::new(CharacterType::Terminal(digit_b)),
@@ -308,12 +749,12 @@ impl RegExParser {
                 }
             }
             letter_a @ 'a'..='z' => {
-                self.consume(letter_a).unwrap();
-                if self.peek() == '-' {
-                    self.consume('-').unwrap();
-                    match self.peek() {
+                self.consume(letter_a)?;
+                if self.peek()? == '-' {
+                    self.consume('-')?;
+                    match self.peek()? {
                         letter_b @ 'a'..='z' => {
-                            self.consume(letter_b).unwrap();
+                            self.consume(letter_b)?;
                             CharacterType::Between(
                                 Box::new(CharacterType::Terminal(letter_a)),
                                 Box::new(CharacterType::Terminal(letter_b)),
@@ -326,12 +767,12 @@ impl RegExParser {
                 }
             }
             capital_a @ 'A'..='Z' => {
-                self.consume(capital_a).unwrap();
-                if self.peek() == '-' {
-                    self.consume('-').unwrap();
-                    match self.peek() {
+                self.consume(capital_a)?;
+                if self.peek()? == '-' {
+                    self.consume('-')?;
+                    match self.peek()? {
                         capital_b @ 'A'..='Z' => {
-                            self.consume(capital_b).unwrap();
+                            self.consume(capital_b)?;
                             CharacterType::Between(
                                 Box::new(CharacterType::Terminal(capital_a)),
                                 Box::new(CharacterType::Terminal(capital_b)),
@@ -345,7 +786,7 @@ impl RegExParser {
             },
             '\\' => {
                 self.consume('\\')?;
-                match self.peek() {
+                match self.peek()? {
                     'w' => {
                         self.consume('w')?;
                         CharacterType::Meta(MetaCharacter::Word(true))
@@ -370,7 +811,14 @@ impl RegExParser {
                         self.consume('S')?;
                         CharacterType::Meta(MetaCharacter::Whitespace(false))
                     },
-                    _ => CharacterType::Terminal('\\')
+                    c @ ('p' | 'P') => {
+                        self.consume(c)?;
+                        CharacterType::Meta(MetaCharacter::Property(self.property_name()?, c == 'p'))
+                    },
+                    other => {
+                        self.consume(other)?;
+                        CharacterType::Terminal(escaped_char(other))
+                    }
                 }
             }
             other => {
@@ -379,7 +827,7 @@ impl RegExParser {
                 CharacterType::Terminal(other)
             }
         };
-        if self.peek() == '-' {
+        if self.peek()? == '-' {
             if self.peek_n(1) == Some(']') {
                 Ok(Box::new(c))
             } else {
@@ -393,9 +841,22 @@ impl RegExParser {
         }
     }
 
+    /// Check if a character has a special meaning rather than matching itself
+    fn is_special(&self, c: char) -> bool {
+        SPECIAL_CHARS.contains(&c) || (self.flavor == Flavor::Vim && vim::SPECIAL_CHARS.contains(c))
+    }
+
+    /// Check if a character quantifies the preceding atom
+    fn is_quantifier(&self, c: char) -> bool {
+        match self.flavor {
+            Flavor::Vim => vim::QUANTIFIER_CHARS.contains(c),
+            _ => QUANTIFIER_CHARS.contains(&c),
+        }
+    }
+
     /// Check what the next character is
-    fn peek(&self) -> char {
-        self.text.chars().nth(self.idx).unwrap()
+    fn peek(&self) -> Result<char, Error> {
+        self.peek_n(0).ok_or_else(|| Error::UnexpectedEnd(self.text.clone()))
     }
 
     /// Check n characters ahead
@@ -405,7 +866,7 @@ impl RegExParser {
 
     /// 'Consume' char c from the text
     fn consume(&mut self, c: char) -> Result<(), Error> {
-        let p = self.peek();
+        let p = self.peek()?;
         if p == c {
             self.idx += 1;
             Ok(())
@@ -414,23 +875,32 @@ impl RegExParser {
         }
     }
 
+    /// Consume a closing character, which is an error rather than a panic if missing
+    fn close(&mut self, c: char) -> Result<(), Error> {
+        match self.more() {
+            true => self.consume(c),
+            false => Err(Error::UnterminatedLiteral(self.text.clone())),
+        }
+    }
+
     /// Move to next character, consuming the current one
     fn next(&mut self) -> Result<char, Error> {
-        let c = self.peek();
+        let c = self.peek()?;
         self.consume(c)?;
         Ok(c)
     }
 
     /// Returns true if the end of the string has been reached
     fn more(&self) -> bool {
-        self.text.len() > self.idx
+        self.text.chars().count() > self.idx
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{extract::Language, parser::{
-        RegEx::{Alternation, Element, Repetition, Terminal},
+    use crate::{error::Error, extract::{definition::LanguageDefinition, Language}, parser::{
+        CharacterType, Flavor, LookaroundType, MetaCharacter,
+        RegEx::{Alternation, Backreference, Capture, Character, Element, Lookaround, Repetition, Terminal},
         RegExParser, RepetitionType,
     }};
 
@@ -462,10 +932,10 @@ mod test {
             parser.parse().unwrap(),
             Element(vec![Box::new(Repetition(
                 RepetitionType::OrMore(1),
-                Box::new(Alternation(vec![
+                Box::new(Capture(None, 1, Box::new(Alternation(vec![
                     Box::new(Element(vec![Box::new(Terminal('a'.to_string()))])),
                     Box::new(Element(vec![Box::new(Terminal('b'.to_string()))]))
-                ]))
+                ]))))
             ))])
         );
     }
@@ -498,4 +968,132 @@ mod test {
             ))])
         );
     }
+
+    #[test]
+    fn test_lookaround_backreference() {
+        let mut parser = RegExParser::new(Language::Python, &"(?<=a)(?P<x>b)(?P=x)\\1".to_string());
+        assert_eq!(
+            parser.parse().unwrap(),
+            Element(vec![
+                Box::new(Lookaround(
+                    LookaroundType::Behind,
+                    Box::new(Element(vec![Box::new(Terminal('a'.to_string()))]))
+                )),
+                Box::new(Capture(
+                    Some("x".to_string()),
+                    1,
+                    Box::new(Element(vec![Box::new(Terminal('b'.to_string()))]))
+                )),
+                Box::new(Backreference(Some("x".to_string()), 1)),
+                Box::new(Backreference(None, 1))
+            ])
+        );
+
+        // RE2 rejects constructs it cannot match in linear time
+        let mut parser = RegExParser::new(Language::Go, &"a(?!b)".to_string());
+        assert!(matches!(parser.parse(), Err(Error::UnsupportedSyntax(Flavor::Re2, _))));
+        let mut parser = RegExParser::new(Language::Rust, &"(a)\\1".to_string()).with_flavor(Flavor::Re2);
+        assert!(matches!(parser.parse(), Err(Error::UnsupportedSyntax(Flavor::Re2, _))));
+        let mut parser = RegExParser::new(Language::Cpp, &"(?<=a)b".to_string());
+        assert!(matches!(parser.parse(), Err(Error::UnsupportedSyntax(Flavor::Ecmascript, _))));
+    }
+
+    #[test]
+    fn test_unknown_backreference() {
+        let parse = |text: &str| RegExParser::new(Language::Rust, &text.to_string()).parse();
+
+        // References to groups which have not been opened are rejected
+        assert!(matches!(parse("\\1"), Err(Error::UnknownGroup(_))));
+        assert!(matches!(parse("(a)\\2"), Err(Error::UnknownGroup(_))));
+        assert!(matches!(parse("(a)\\99999999999999999999999"), Err(Error::UnknownGroup(_))));
+        assert!(parse("(a)\\1").is_ok());
+        assert!(matches!(
+            RegExParser::new(Language::Rust, &"\\(a\\)\\2".to_string()).with_flavor(Flavor::Vim).parse(),
+            Err(Error::UnknownGroup(_))
+        ));
+    }
+
+    #[test]
+    fn test_terminal_tokens() {
+        let parse = |text: &str| RegExParser::new(Language::Rust, &text.to_string()).parse().unwrap();
+        // A quantifier only applies to the last character of a run of literals
+        assert_eq!(
+            parse("abc+"),
+            Element(vec![
+                Box::new(Terminal("ab".to_string())),
+                Box::new(Repetition(RepetitionType::OrMore(1), Box::new(Terminal('c'.to_string()))))
+            ])
+        );
+        assert_eq!(
+            parse("ab?"),
+            Element(vec![
+                Box::new(Terminal('a'.to_string())),
+                Box::new(Repetition(RepetitionType::ZeroOrOne, Box::new(Terminal('b'.to_string()))))
+            ])
+        );
+        // `.` and `\` end a run of literals rather than being read as part of it
+        assert_eq!(
+            parse("a.b"),
+            Element(vec![
+                Box::new(Terminal('a'.to_string())),
+                Box::new(Character(CharacterType::Meta(MetaCharacter::Any))),
+                Box::new(Terminal('b'.to_string()))
+            ])
+        );
+        assert_eq!(
+            parse("a\\.b"),
+            Element(vec![
+                Box::new(Terminal('a'.to_string())),
+                Box::new(Terminal('.'.to_string())),
+                Box::new(Terminal('b'.to_string()))
+            ])
+        );
+        // Unmatched special characters are read literally
+        assert_eq!(
+            parse("a}"),
+            Element(vec![Box::new(Terminal('a'.to_string())), Box::new(Terminal('}'.to_string()))])
+        );
+    }
+
+    #[test]
+    fn test_unterminated_pattern() {
+        for text in ["(a", "[a", "a\\", "[a-", "(?", "(?<n", "\\p", "\\p{L", "(?<=a"] {
+            let mut parser = RegExParser::new(Language::Python, &text.to_string());
+            assert!(parser.parse().is_err(), "{} should not parse", text);
+        }
+
+        // Patterns are parsed as they are typed, so no prefix of any pattern may panic
+        let patterns = [
+            "(?P<year>\\d{4})-(?:[a-z0-9]+|\\p{Lu})*?(?<=x)\\k<year>",
+            "\\v<(foo|bar)\\%[abc]\\zs.{-1,}",
+            "%b()%f[%w_][^%s]-",
+            "[[:alpha:]]\\{1,3\\}\\(a\\)",
+            "src/**/*.{rs,[!a-c]}",
+        ];
+        for &flavor in Flavor::ALL {
+            for pattern in patterns {
+                for (end, _) in pattern.char_indices() {
+                    let text = pattern[..end].to_string();
+                    let _ = RegExParser::new(Language::Python, &text).with_flavor(flavor).parse();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_string_format() {
+        let mut definition = LanguageDefinition::new("custom");
+        definition.delimiters = vec![String::from("\"")];
+        definition.escape = Some(String::from("%"));
+        let string_format = definition.string_format().unwrap();
+        let text = String::from("a%(b");
+        assert!(RegExParser::new(Language::Custom(String::from("custom")), &text).parse().is_err());
+        assert_eq!(
+            RegExParser::new(Language::Custom(String::from("custom")), &text)
+                .with_string_format(&string_format)
+                .parse()
+                .unwrap(),
+            Element(vec![Box::new(Terminal(String::from("a(b")))])
+        );
+    }
 }
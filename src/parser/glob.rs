@@ -0,0 +1,259 @@
+use crate::{
+    error::Error,
+    parser::{posix, AnchorType, CharacterType, Flavor, MetaCharacter, RegEx, RegExParser, RepetitionType},
+};
+
+/// Any character of a single path component, as matched by `?`
+fn component_char() -> RegEx {
+    RegEx::Character(CharacterType::Not(vec![Box::new(CharacterType::Terminal('/'))]))
+}
+
+/// Whether a `/` ends the text, so that a `.gitignore` pattern only matches directories
+fn directory_only(text: &str) -> bool {
+    let body = text.strip_suffix('/').unwrap_or(text);
+    let escapes = body.len() - body.trim_end_matches('\\').len();
+    text.ends_with('/') && escapes.is_multiple_of(2)
+}
+
+impl RegExParser {
+    /// Parse a whole glob, where a leading `!` negates it
+    ///
+    /// In `.gitignore` files a trailing `/` only matches directories, and any other `/`
+    /// anchors the pattern to the directory of the file rather than matching at any
+    /// depth, both of which are drawn as anchors.
+    pub(super) fn glob_pattern(&mut self) -> Result<RegEx, Error> {
+        let mut items = Vec::new();
+        if let Some(rest) = self.text.strip_prefix('!') {
+            self.text = rest.to_string();
+            items.push(Box::new(RegEx::Anchor(AnchorType::Negated)));
+        }
+        let directory = self.flavor == Flavor::Gitignore && directory_only(&self.text);
+        if directory {
+            self.text.pop();
+        }
+        if self.flavor == Flavor::Gitignore && self.text.contains('/') {
+            if let Some(rest) = self.text.strip_prefix('/') {
+                self.text = rest.to_string();
+            }
+            items.push(Box::new(RegEx::Anchor(AnchorType::PathStart)));
+        }
+        match self.glob_sequence(false)? {
+            RegEx::Element(sequence) => items.extend(sequence),
+            other => items.push(Box::new(other)),
+        }
+        if directory {
+            items.push(Box::new(RegEx::Anchor(AnchorType::DirectoryOnly)));
+        }
+        Ok(RegEx::Element(items))
+    }
+
+    /// Parse items up to the end of the glob, or up to the `,` or `}` ending an
+    /// alternative if `in_braces`
+    fn glob_sequence(&mut self, in_braces: bool) -> Result<RegEx, Error> {
+        let mut items: Vec<Box<RegEx>> = Vec::new();
+        while self.more() && !(in_braces && [',', '}'].contains(&self.peek()?)) {
+            let item = self.glob_item()?;
+            // Adjacent characters are drawn as one literal
+            if let (RegEx::Terminal(next), Some(RegEx::Terminal(text))) = (&item, items.last_mut().map(|x| x.as_mut())) {
+                text.push_str(next);
+                continue;
+            }
+            items.push(Box::new(item));
+        }
+        Ok(RegEx::Element(items))
+    }
+
+    /// Parse a wildcard, bracket expression, brace expansion or single character
+    fn glob_item(&mut self) -> Result<RegEx, Error> {
+        match self.next()? {
+            '\\' => match self.more() {
+                true => Ok(RegEx::Terminal(self.next()?.to_string())),
+                false => Err(Error::InvalidCharacter('\\', self.idx - 1)),
+            },
+            '?' => Ok(component_char()),
+            '*' => self.glob_star(),
+            '[' => Ok(RegEx::Character(self.glob_class()?)),
+            '{' if self.flavor == Flavor::Glob => self.glob_braces(),
+            c => Ok(RegEx::Terminal(c.to_string())),
+        }
+    }
+
+    /// Parse the rest of `*` or `**`, where `**` matches across directories when it is a
+    /// whole path component and is otherwise the same as `*`
+    fn glob_star(&mut self) -> Result<RegEx, Error> {
+        let start = self.idx - 1;
+        let mut double = false;
+        while self.more() && self.peek()? == '*' {
+            self.consume('*')?;
+            double = true;
+        }
+        let component_start = start == 0 || self.text.chars().nth(start - 1) == Some('/');
+        let component_end = !self.more() || self.peek()? == '/';
+        if !(double && component_start && component_end) {
+            return Ok(RegEx::Repetition(RepetitionType::OrMore(0), Box::new(component_char())));
+        }
+        // A trailing `**` matches everything inside a directory
+        if !self.more() {
+            return Ok(RegEx::Repetition(
+                RepetitionType::OrMore(0),
+                Box::new(RegEx::Character(CharacterType::Meta(MetaCharacter::Any))),
+            ));
+        }
+        // `**/` matches any number of directories, including none
+        self.consume('/')?;
+        let directory = RegEx::Element(vec![
+            Box::new(RegEx::Repetition(RepetitionType::OrMore(1), Box::new(component_char()))),
+            Box::new(RegEx::Terminal(String::from("/"))),
+        ]);
+        Ok(RegEx::Repetition(RepetitionType::OrMore(0), Box::new(directory)))
+    }
+
+    /// Parse a bracket expression after its opening `[`, negated by `!` or `^`, where a
+    /// `]` straight after the opening bracket is a member
+    fn glob_class(&mut self) -> Result<CharacterType, Error> {
+        let matching = !(self.more() && ['!', '^'].contains(&self.peek()?));
+        if !matching {
+            self.next()?;
+        }
+        let mut members = Vec::new();
+        let mut first = true;
+        while self.more() && (first || self.peek()? != ']') {
+            first = false;
+            let c = match self.next()? {
+                '[' if self.more() && self.peek()? == ':' => {
+                    self.consume(':')?;
+                    let name = self.group_name(':')?;
+                    self.close(']')?;
+                    let property = posix::class_name(&name).ok_or(Error::UnknownProperty(name))?;
+                    members.push(Box::new(CharacterType::Meta(MetaCharacter::Property(property.to_string(), true))));
+                    continue;
+                }
+                '\\' if self.more() => self.next()?,
+                c => c,
+            };
+            // A `-` between two characters makes a range, and is a member anywhere else
+            if self.more() && self.peek()? == '-' && self.peek_n(1).is_some_and(|x| x != ']') {
+                self.consume('-')?;
+                let end = match self.next()? {
+                    '\\' if self.more() => self.next()?,
+                    end => end,
+                };
+                members.push(Box::new(CharacterType::Between(
+                    Box::new(CharacterType::Terminal(c)),
+                    Box::new(CharacterType::Terminal(end)),
+                )));
+            } else {
+                members.push(Box::new(CharacterType::Terminal(c)));
+            }
+        }
+        self.close(']')?;
+        match matching {
+            true => Ok(CharacterType::Any(members)),
+            false => Ok(CharacterType::Not(members)),
+        }
+    }
+
+    /// Parse the comma separated alternatives of `{a,b}` after the opening brace, where
+    /// braces without a comma match themselves
+    fn glob_braces(&mut self) -> Result<RegEx, Error> {
+        let mut alternatives = vec![Box::new(self.glob_sequence(true)?)];
+        while self.more() && self.peek()? == ',' {
+            self.consume(',')?;
+            alternatives.push(Box::new(self.glob_sequence(true)?));
+        }
+        self.close('}')?;
+        if alternatives.len() > 1 {
+            return Ok(RegEx::Alternation(alternatives));
+        }
+        let mut items = vec![Box::new(RegEx::Terminal(String::from("{")))];
+        match *alternatives.remove(0) {
+            RegEx::Element(sequence) => items.extend(sequence),
+            other => items.push(Box::new(other)),
+        }
+        items.push(Box::new(RegEx::Terminal(String::from("}"))));
+        Ok(RegEx::Element(items))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        extract::Language,
+        parser::{AnchorType, CharacterType, Flavor, MetaCharacter, RegEx, RegExParser, RepetitionType},
+    };
+
+    fn parse(text: &str, flavor: Flavor) -> Result<RegEx, Error> {
+        RegExParser::new(Language::Gitignore, &text.to_string()).with_flavor(flavor).parse()
+    }
+
+    fn not_slash() -> Box<RegEx> {
+        Box::new(RegEx::Character(CharacterType::Not(vec![Box::new(CharacterType::Terminal('/'))])))
+    }
+
+    #[test]
+    fn test_glob_pattern() {
+        assert_eq!(
+            parse("src/**/*.{rs,toml}", Flavor::Glob).unwrap(),
+            RegEx::Element(vec![
+                Box::new(RegEx::Terminal(String::from("src/"))),
+                Box::new(RegEx::Repetition(
+                    RepetitionType::OrMore(0),
+                    Box::new(RegEx::Element(vec![
+                        Box::new(RegEx::Repetition(RepetitionType::OrMore(1), not_slash())),
+                        Box::new(RegEx::Terminal(String::from("/"))),
+                    ]))
+                )),
+                Box::new(RegEx::Repetition(RepetitionType::OrMore(0), not_slash())),
+                Box::new(RegEx::Terminal(String::from("."))),
+                Box::new(RegEx::Alternation(vec![
+                    Box::new(RegEx::Element(vec![Box::new(RegEx::Terminal(String::from("rs")))])),
+                    Box::new(RegEx::Element(vec![Box::new(RegEx::Terminal(String::from("toml")))])),
+                ])),
+            ])
+        );
+        assert_eq!(
+            parse("![!a-c[:digit:]]?", Flavor::Glob).unwrap(),
+            RegEx::Element(vec![
+                Box::new(RegEx::Anchor(AnchorType::Negated)),
+                Box::new(RegEx::Character(CharacterType::Not(vec![
+                    Box::new(CharacterType::Between(
+                        Box::new(CharacterType::Terminal('a')),
+                        Box::new(CharacterType::Terminal('c'))
+                    )),
+                    Box::new(CharacterType::Meta(MetaCharacter::Property(String::from("Digit"), true))),
+                ]))),
+                not_slash(),
+            ])
+        );
+        // `**` next to other characters is a single `*`
+        assert_eq!(parse("a**", Flavor::Glob).unwrap(), parse("a*", Flavor::Glob).unwrap());
+        assert!(parse("{a,b", Flavor::Glob).is_err());
+        assert!(parse("[ab", Flavor::Glob).is_err());
+    }
+
+    #[test]
+    fn test_gitignore_pattern() {
+        assert_eq!(
+            parse("/build/", Flavor::Gitignore).unwrap(),
+            RegEx::Element(vec![
+                Box::new(RegEx::Anchor(AnchorType::PathStart)),
+                Box::new(RegEx::Terminal(String::from("build"))),
+                Box::new(RegEx::Anchor(AnchorType::DirectoryOnly)),
+            ])
+        );
+        // Only a slash before the end anchors the pattern
+        assert_eq!(
+            parse("target/", Flavor::Gitignore).unwrap(),
+            RegEx::Element(vec![
+                Box::new(RegEx::Terminal(String::from("target"))),
+                Box::new(RegEx::Anchor(AnchorType::DirectoryOnly)),
+            ])
+        );
+        assert_eq!(
+            parse(r"\!{a,b}", Flavor::Gitignore).unwrap(),
+            RegEx::Element(vec![Box::new(RegEx::Terminal(String::from("!{a,b}")))])
+        );
+        assert!(parse(r"a\", Flavor::Gitignore).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+use crate::{
+    error::Error,
+    parser::{AnchorType, CharacterType, MetaCharacter, QuantifierMode, RegEx, RegExParser, RepetitionType},
+};
+
+/// Character class of a `%` letter such as `%a`, where the upper case letter matches
+/// every other character
+fn class(c: char) -> Option<CharacterType> {
+    let matching = c.is_ascii_lowercase();
+    let name = match c.to_ascii_lowercase() {
+        'd' => return Some(CharacterType::Meta(MetaCharacter::Digit(matching))),
+        's' => return Some(CharacterType::Meta(MetaCharacter::Whitespace(matching))),
+        'a' => "Alpha",
+        'c' => "Cntrl",
+        'g' => "Graph",
+        'l' => "Lower",
+        'p' => "Punct",
+        'u' => "Upper",
+        'w' => "Alnum",
+        'x' => "XDigit",
+        _ => return None,
+    };
+    Some(CharacterType::Meta(MetaCharacter::Property(name.to_string(), matching)))
+}
+
+impl RegExParser {
+    /// Parse a whole Lua pattern, where `^` and `$` are only anchors at its ends
+    pub(super) fn lua_pattern(&mut self) -> Result<RegEx, Error> {
+        let anchored = self.more() && self.peek()? == '^';
+        if anchored {
+            self.consume('^')?;
+        }
+        let pattern = self.lua_sequence(anchored)?;
+        // Only an unmatched `)` stops the sequence before the end
+        if self.more() {
+            return Err(Error::InvalidCharacter(self.peek()?, self.idx));
+        }
+        Ok(pattern)
+    }
+
+    /// Parse items up to the end of the pattern or the `)` closing a capture, after a
+    /// start anchor if `anchored`
+    fn lua_sequence(&mut self, anchored: bool) -> Result<RegEx, Error> {
+        let mut items: Vec<Box<RegEx>> = Vec::new();
+        if anchored {
+            items.push(Box::new(RegEx::Anchor(AnchorType::Start)));
+        }
+        while self.more() && self.peek()? != ')' {
+            if self.peek()? == '$' && self.peek_n(1).is_none() {
+                self.consume('$')?;
+                items.push(Box::new(RegEx::Anchor(AnchorType::End)));
+                continue;
+            }
+            let item = self.lua_item()?;
+            // Adjacent characters are drawn as one literal
+            if let (RegEx::Terminal(next), Some(RegEx::Terminal(text))) = (&item, items.last_mut().map(|x| x.as_mut())) {
+                text.push_str(next);
+                continue;
+            }
+            items.push(Box::new(item));
+        }
+        Ok(RegEx::Element(items))
+    }
+
+    /// Parse a capture, a `%` item, or a single character class and its quantifier
+    fn lua_item(&mut self) -> Result<RegEx, Error> {
+        let single = match self.peek()? {
+            '(' => return self.lua_capture(),
+            '%' => {
+                self.consume('%')?;
+                if !self.more() {
+                    return Err(Error::InvalidCharacter('%', self.idx - 1));
+                }
+                match self.next()? {
+                    'b' => return self.lua_balanced(),
+                    'f' => return self.lua_frontier(),
+                    digit @ '1'..='9' => return self.lua_backreference(digit),
+                    c => match class(c) {
+                        Some(class) => RegEx::Character(class),
+                        None => RegEx::Terminal(c.to_string()),
+                    },
+                }
+            }
+            '[' => RegEx::Character(self.lua_set()?),
+            '.' => {
+                self.consume('.')?;
+                RegEx::Character(CharacterType::Meta(MetaCharacter::Any))
+            }
+            c => {
+                self.consume(c)?;
+                RegEx::Terminal(c.to_string())
+            }
+        };
+
+        // Quantifiers only apply to a single character class
+        let repetition = match self.peek_n(0) {
+            Some('*' | '-') => RepetitionType::OrMore(0),
+            Some('+') => RepetitionType::OrMore(1),
+            Some('?') => RepetitionType::ZeroOrOne,
+            _ => return Ok(single),
+        };
+        let repeated = RegEx::Repetition(repetition, Box::new(single));
+        match self.next()? {
+            '-' => Ok(RegEx::Quantifier(QuantifierMode::Lazy, Box::new(repeated))),
+            _ => Ok(repeated),
+        }
+    }
+
+    /// Parse `(...)`, where `()` captures the position rather than any text
+    fn lua_capture(&mut self) -> Result<RegEx, Error> {
+        let start = self.idx;
+        self.consume('(')?;
+        let group = self.open_capture(start, None);
+        let inner = self.lua_sequence(false)?;
+        self.close(')')?;
+        self.group_spans[group - 1].1 = self.idx;
+        Ok(RegEx::Capture(None, group, Box::new(inner)))
+    }
+
+    /// Parse the two characters of `%bxy`
+    fn lua_balanced(&mut self) -> Result<RegEx, Error> {
+        if self.peek_n(1).is_none() {
+            return Err(Error::InvalidCharacter('b', self.idx - 1));
+        }
+        let open = self.next()?;
+        let close = self.next()?;
+        Ok(RegEx::Balanced(open, close))
+    }
+
+    /// Parse the set of `%f[set]`
+    fn lua_frontier(&mut self) -> Result<RegEx, Error> {
+        if !self.more() || self.peek()? != '[' {
+            return Err(Error::InvalidCharacter('f', self.idx - 1));
+        }
+        Ok(RegEx::Frontier(Box::new(RegEx::Character(self.lua_set()?))))
+    }
+
+    /// Parse `%1` to `%9`, which may only refer to a capture which has been opened
+    fn lua_backreference(&mut self, digit: char) -> Result<RegEx, Error> {
+        let group = digit.to_digit(10).expect("Current char already checked to be in '1'..='9'") as usize;
+        if group > self.capture_group {
+            return Err(Error::UnknownGroup(digit.to_string()));
+        }
+        Ok(RegEx::Backreference(None, group))
+    }
+
+    /// Parse `[...]`, where a `]` straight after the opening bracket is a member and `%`
+    /// escapes characters and classes
+    fn lua_set(&mut self) -> Result<CharacterType, Error> {
+        self.consume('[')?;
+        let matching = !(self.more() && self.peek()? == '^');
+        if !matching {
+            self.consume('^')?;
+        }
+        let mut members = Vec::new();
+        let mut first = true;
+        while self.more() && (first || self.peek()? != ']') {
+            first = false;
+            let c = self.next()?;
+            if c == '%' {
+                if !self.more() {
+                    return Err(Error::UnterminatedLiteral(self.text.clone()));
+                }
+                let escaped = self.next()?;
+                members.push(Box::new(class(escaped).unwrap_or(CharacterType::Terminal(escaped))));
+                continue;
+            }
+            // A `-` between two characters makes a range, and is a member anywhere else
+            if self.more() && self.peek()? == '-' && self.peek_n(1).is_some_and(|x| x != ']') {
+                self.consume('-')?;
+                let end = self.next()?;
+                members.push(Box::new(CharacterType::Between(
+                    Box::new(CharacterType::Terminal(c)),
+                    Box::new(CharacterType::Terminal(end)),
+                )));
+            } else {
+                members.push(Box::new(CharacterType::Terminal(c)));
+            }
+        }
+        self.close(']')?;
+        match matching {
+            true => Ok(CharacterType::Any(members)),
+            false => Ok(CharacterType::Not(members)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        extract::Language,
+        parser::{AnchorType, CharacterType, MetaCharacter, QuantifierMode, RegEx, RegExParser, RepetitionType},
+    };
+
+    fn parse(text: &str) -> Result<RegEx, Error> {
+        RegExParser::new(Language::Lua, &text.to_string()).parse()
+    }
+
+    #[test]
+    fn test_lua_pattern() {
+        assert_eq!(
+            parse("^%a+%s-x$").unwrap(),
+            RegEx::Element(vec![
+                Box::new(RegEx::Anchor(AnchorType::Start)),
+                Box::new(RegEx::Repetition(
+                    RepetitionType::OrMore(1),
+                    Box::new(RegEx::Character(CharacterType::Meta(MetaCharacter::Property(
+                        String::from("Alpha"),
+                        true
+                    ))))
+                )),
+                Box::new(RegEx::Quantifier(
+                    QuantifierMode::Lazy,
+                    Box::new(RegEx::Repetition(
+                        RepetitionType::OrMore(0),
+                        Box::new(RegEx::Character(CharacterType::Meta(MetaCharacter::Whitespace(true))))
+                    ))
+                )),
+                Box::new(RegEx::Terminal(String::from("x"))),
+                Box::new(RegEx::Anchor(AnchorType::End)),
+            ])
+        );
+        // Alternation and anchors away from the ends are literal
+        assert_eq!(
+            parse("a|b^$c%.").unwrap(),
+            RegEx::Element(vec![Box::new(RegEx::Terminal(String::from("a|b^$c.")))])
+        );
+        assert_eq!(
+            parse("%b()%f[%w_]").unwrap(),
+            RegEx::Element(vec![
+                Box::new(RegEx::Balanced('(', ')')),
+                Box::new(RegEx::Frontier(Box::new(RegEx::Character(CharacterType::Any(vec![
+                    Box::new(CharacterType::Meta(MetaCharacter::Property(String::from("Alnum"), true))),
+                    Box::new(CharacterType::Terminal('_')),
+                ]))))),
+            ])
+        );
+        assert_eq!(
+            parse("[]a-]").unwrap(),
+            RegEx::Element(vec![Box::new(RegEx::Character(CharacterType::Any(vec![
+                Box::new(CharacterType::Terminal(']')),
+                Box::new(CharacterType::Terminal('a')),
+                Box::new(CharacterType::Terminal('-')),
+            ])))])
+        );
+        assert!(matches!(parse("(a)%1").unwrap(), RegEx::Element(v) if v.len() == 2));
+        assert!(matches!(parse("%2(a)"), Err(Error::UnknownGroup(_))));
+        assert!(parse("a)").is_err());
+        assert!(parse("%f%w").is_err());
+        assert!(parse("[a").is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use std::{iter::Peekable, str::Chars};
+
+/// Characters which are special in extended patterns but match themselves in basic ones
+const EXTENDED_CHARS: &str = "(){}|+?";
+
+/// Characters which are special anywhere in extended patterns
+const SPECIAL_CHARS: &str = "\\.[]*^$(){}|+?";
+
+/// Property name of a POSIX class such as `[:alpha:]`, as used by `\p{Alpha}`
+pub fn class_name(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "alpha" => "Alpha",
+        "digit" => "Digit",
+        "alnum" => "Alnum",
+        "upper" => "Upper",
+        "lower" => "Lower",
+        "space" => "Space",
+        "blank" => "Blank",
+        "punct" => "Punct",
+        "print" => "Print",
+        "graph" => "Graph",
+        "cntrl" => "Cntrl",
+        "xdigit" => "XDigit",
+        _ => return None,
+    })
+}
+
+/// Escape text so that it matches itself as an extended pattern
+pub fn escape(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| SPECIAL_CHARS.contains(c).then_some('\\').into_iter().chain([c]))
+        .collect()
+}
+
+/// Copy a bracket expression after its opening `[`, where a backslash matches itself and
+/// a `]` straight after the opening bracket is a member
+pub(super) fn copy_class(chars: &mut Peekable<Chars>, out: &mut String) {
+    out.extend(chars.next_if_eq(&'^'));
+    out.extend(chars.next_if_eq(&']'));
+    while let Some(c) = chars.next() {
+        out.push(c);
+        match c {
+            '[' if chars.peek().is_some_and(|x| [':', '=', '.'].contains(x)) => {
+                let close = chars.next().expect("Next character already checked");
+                out.push(close);
+                while let Some(c) = chars.next() {
+                    out.push(c);
+                    if c == close && chars.next_if_eq(&']').is_some() {
+                        out.push(']');
+                        break;
+                    }
+                }
+            }
+            ']' => return,
+            _ => (),
+        }
+    }
+}
+
+/// Check if the text after a `$` ends the pattern or a group or branch, so the `$` is an
+/// anchor
+fn branch_end(mut rest: Peekable<Chars>) -> bool {
+    match rest.next() {
+        None => true,
+        Some('\\') => matches!(rest.next(), Some('|' | ')')),
+        Some(_) => false,
+    }
+}
+
+/// Rewrite a basic regular expression as an extended one, where groups, intervals and
+/// alternation are written without backslashes
+///
+/// GNU's `\+`, `\?` and `\|` are also read, and `^`, `$` and `*` match themselves where
+/// they cannot be anchors or quantifiers.
+pub fn extended(text: &str) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars().peekable();
+    // Whether the next item starts the pattern, a group or a branch
+    let mut branch_start = true;
+    while let Some(c) = chars.next() {
+        let start = std::mem::replace(&mut branch_start, false);
+        match c {
+            '\\' => match chars.next() {
+                Some(c @ ('(' | '|')) => {
+                    out.push(c);
+                    branch_start = true;
+                }
+                Some(c) if EXTENDED_CHARS.contains(c) => out.push(c),
+                Some(c) => {
+                    out.push('\\');
+                    out.push(c);
+                }
+                None => out.push_str("\\\\"),
+            },
+            c if EXTENDED_CHARS.contains(c) => {
+                out.push('\\');
+                out.push(c);
+            }
+            '^' if start => {
+                out.push('^');
+                branch_start = true;
+            }
+            '*' if start => out.push_str("\\*"),
+            '^' => out.push_str("\\^"),
+            '$' if !branch_end(chars.clone()) => out.push_str("\\$"),
+            '[' => {
+                out.push('[');
+                copy_class(&mut chars, &mut out);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        extract::Language,
+        parser::{posix::extended, AnchorType, CharacterType, Flavor, MetaCharacter, RegEx, RegExParser},
+    };
+
+    fn parse(text: &str, flavor: Flavor) -> Result<RegEx, Error> {
+        RegExParser::new(Language::Shell, &text.to_string()).with_flavor(flavor).parse()
+    }
+
+    #[test]
+    fn test_posix_regex() {
+        assert_eq!(extended(r"\(a\|b\)\{2\}+?"), r"(a|b){2}\+\?");
+        assert_eq!(extended(r"*a^b$c$"), r"\*a\^b\$c$");
+        assert_eq!(extended(r"\(^*x$\)"), r"(^\*x$)");
+        assert_eq!(extended(r"[]\(]"), r"[]\(]");
+        assert_eq!(parse(r"\(ab\)*", Flavor::Bre).unwrap(), parse("(ab)*", Flavor::Ere).unwrap());
+
+        assert_eq!(
+            parse(r"[]\[:alpha:]]\<", Flavor::Ere).unwrap(),
+            RegEx::Element(vec![
+                Box::new(RegEx::Character(CharacterType::Any(vec![
+                    Box::new(CharacterType::Terminal(']')),
+                    Box::new(CharacterType::Terminal('\\')),
+                    Box::new(CharacterType::Meta(MetaCharacter::Property(String::from("Alpha"), true))),
+                ]))),
+                Box::new(RegEx::Anchor(AnchorType::WordStart)),
+            ])
+        );
+        assert!(matches!(parse("a*?", Flavor::Ere), Err(Error::UnsupportedSyntax(Flavor::Ere, _))));
+        assert!(matches!(parse("(?:a)", Flavor::Ere), Err(Error::UnsupportedSyntax(Flavor::Ere, _))));
+        assert!(matches!(parse("[[:word:]]", Flavor::Pcre), Err(Error::UnknownProperty(_))));
+    }
+}
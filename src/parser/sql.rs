@@ -0,0 +1,84 @@
+use crate::{
+    error::Error,
+    parser::{posix, AnchorType, Flavor, RegEx, RegExParser},
+};
+
+/// Rewrite a `LIKE` or `SIMILAR TO` pattern as an extended regular expression, where `%`
+/// matches any text, `_` any character and a backslash makes the next character match
+/// itself
+///
+/// `SIMILAR TO` patterns also have groups, alternation, quantifiers and bracket
+/// expressions, but a `.`, `^` or `$` matches itself.
+pub fn extended(text: &str, similar: bool) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '%' => out.push_str(".*"),
+            '_' => out.push('.'),
+            '\\' => match chars.next() {
+                Some(c) => out.push_str(&posix::escape(&c.to_string())),
+                None => out.push_str("\\\\"),
+            },
+            '[' if similar => {
+                out.push('[');
+                posix::copy_class(&mut chars, &mut out);
+            }
+            '(' | ')' | '|' | '*' | '+' | '?' | '{' | '}' if similar => out.push(c),
+            c => out.push_str(&posix::escape(&c.to_string())),
+        }
+    }
+    out
+}
+
+impl RegExParser {
+    /// Parse a `LIKE` or `SIMILAR TO` pattern, which always matches the whole value
+    pub(super) fn sql_pattern(&mut self) -> Result<RegEx, Error> {
+        self.text = extended(&self.text, self.flavor == Flavor::SimilarTo);
+        let pattern = self.alternation()?;
+        Ok(RegEx::Element(vec![
+            Box::new(RegEx::Anchor(AnchorType::Start)),
+            Box::new(pattern),
+            Box::new(RegEx::Anchor(AnchorType::End)),
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        extract::Language,
+        parser::{sql::extended, AnchorType, CharacterType, Flavor, MetaCharacter, RegEx, RegExParser, RepetitionType},
+    };
+
+    fn parse(text: &str, flavor: Flavor) -> Result<RegEx, Error> {
+        RegExParser::new(Language::Sql, &text.to_string()).with_flavor(flavor).parse()
+    }
+
+    #[test]
+    fn test_sql_pattern() {
+        assert_eq!(extended(r"a.%_\%(b|c)", false), r"a\..*.%\(b\|c\)");
+        assert_eq!(extended(r"a.%_\%(b|c)*[%_]", true), r"a\..*.%(b|c)*[%_]");
+
+        assert_eq!(
+            parse("a%_", Flavor::Like).unwrap(),
+            RegEx::Element(vec![
+                Box::new(RegEx::Anchor(AnchorType::Start)),
+                Box::new(RegEx::Element(vec![
+                    Box::new(RegEx::Terminal(String::from("a"))),
+                    Box::new(RegEx::Repetition(
+                        RepetitionType::OrMore(0),
+                        Box::new(RegEx::Character(CharacterType::Meta(MetaCharacter::Any)))
+                    )),
+                    Box::new(RegEx::Character(CharacterType::Meta(MetaCharacter::Any))),
+                ])),
+                Box::new(RegEx::Anchor(AnchorType::End)),
+            ])
+        );
+        assert!(matches!(
+            parse("(?:a)%", Flavor::SimilarTo),
+            Err(Error::UnsupportedSyntax(Flavor::SimilarTo, _))
+        ));
+    }
+}
@@ -0,0 +1,543 @@
+use std::{iter::Peekable, str::Chars};
+
+use crate::{
+    error::Error,
+    parser::{
+        AnchorType, CharacterType, LookaroundType, MetaCharacter, QuantifierMode, RegEx, RegExParser, RepetitionType,
+    },
+};
+
+/// Characters which are special in very magic patterns, besides those special in every flavor
+pub const SPECIAL_CHARS: &str = "=@%<>~&";
+
+/// Characters which follow the atom they apply to in very magic patterns
+pub const QUANTIFIER_CHARS: &str = "*+=?{@";
+
+/// Punctuation with a special meaning in very magic patterns
+const VERY_MAGIC_CHARS: &str = "()|+?={@%<>*.[~^$&";
+
+/// How much punctuation is special without a backslash
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+enum Magic {
+    /// `\v`, where all punctuation is special
+    Very,
+    /// `\m`, the default, where `.`, `*`, `[` and `~` are special
+    Normal,
+    /// `\M`, where only `^` and `$` are special
+    No,
+    /// `\V`, where only `^` and `$` are special, and only at the ends of the pattern
+    VeryNo,
+}
+
+impl Magic {
+    /// Whether punctuation other than `^` and `$` is special without a backslash
+    fn is_special(self, c: char) -> bool {
+        match self {
+            Magic::Very => VERY_MAGIC_CHARS.contains(c),
+            Magic::Normal => ".*[~".contains(c),
+            Magic::No | Magic::VeryNo => false,
+        }
+    }
+}
+
+/// Check if the text after a `$` ends a branch, so the `$` is an anchor
+fn branch_end(mut rest: Peekable<Chars>) -> bool {
+    match rest.next() {
+        None => true,
+        Some('\\') => matches!(rest.next(), Some('|' | ')' | 'n')),
+        Some(_) => false,
+    }
+}
+
+/// Copy a character class after its opening `[`, which is written the same way at every
+/// magic level
+fn copy_class(chars: &mut Peekable<Chars>, out: &mut String) {
+    if let Some(c) = chars.next_if_eq(&'^') {
+        out.push(c);
+    }
+    // A `]` at the start is part of the class
+    if chars.next_if_eq(&']').is_some() {
+        out.push_str("\\]");
+    }
+    while let Some(c) = chars.next() {
+        out.push(c);
+        match c {
+            '\\' => out.extend(chars.next()),
+            '[' if chars.peek().is_some_and(|x| [':', '=', '.'].contains(x)) => {
+                let close = chars.next().expect("Next character already checked");
+                out.push(close);
+                while let Some(c) = chars.next() {
+                    out.push(c);
+                    if c == close && chars.next_if_eq(&']').is_some() {
+                        out.push(']');
+                        break;
+                    }
+                }
+            }
+            ']' => return,
+            _ => (),
+        }
+    }
+}
+
+/// Rewrite a Vim pattern at any magic level as a very magic pattern, where all
+/// punctuation is special unless escaped, dropping the `\c` and `\C` case modifiers
+pub fn very_magic(text: &str) -> String {
+    let mut magic = Magic::Normal;
+    let mut out = String::new();
+    let mut chars = text.chars().peekable();
+    // Whether the next item starts a branch, where `^` is an anchor
+    let mut branch_start = true;
+    // Whether the text is within `\%[...]`, which is closed by a plain `]`
+    let mut in_sequence = false;
+    while let Some(c) = chars.next() {
+        let (c, escaped) = match c {
+            '\\' => match chars.next() {
+                Some(next) => (next, true),
+                None => {
+                    out.push_str("\\\\");
+                    break;
+                }
+            },
+            c => (c, false),
+        };
+        if escaped {
+            match c {
+                'v' => magic = Magic::Very,
+                'm' => magic = Magic::Normal,
+                'M' => magic = Magic::No,
+                'V' => magic = Magic::VeryNo,
+                'c' | 'C' => (),
+                // Items such as `\_s` and `\_[` also match a newline, and are kept whole
+                '_' => {
+                    out.push_str("\\_");
+                    match chars.next() {
+                        Some('[') => {
+                            out.push('[');
+                            copy_class(&mut chars, &mut out);
+                        }
+                        next => out.extend(next),
+                    }
+                    branch_start = false;
+                }
+                c if c.is_alphanumeric() => {
+                    out.push('\\');
+                    out.push(c);
+                    branch_start = false;
+                }
+                _ => (),
+            }
+            if c.is_alphanumeric() || c == '_' {
+                continue;
+            }
+        } else if c.is_alphanumeric() || c == '_' {
+            out.push(c);
+            branch_start = false;
+            continue;
+        }
+
+        let special = match c {
+            '^' => !escaped && (magic == Magic::Very || branch_start),
+            '$' => !escaped && (magic == Magic::Very || branch_end(chars.clone())),
+            ']' if in_sequence && !escaped => {
+                in_sequence = false;
+                true
+            }
+            c => escaped != magic.is_special(c),
+        };
+        branch_start = false;
+        if !special {
+            if VERY_MAGIC_CHARS.contains(c) || "\\]}".contains(c) {
+                out.push('\\');
+            }
+            out.push(c);
+            continue;
+        }
+
+        out.push(c);
+        match c {
+            '(' | '|' => branch_start = true,
+            '[' => copy_class(&mut chars, &mut out),
+            // The closing brace may also be escaped, as in `\{1,3\}`
+            '{' => {
+                while let Some(c) = chars.next() {
+                    if c == '\\' && chars.peek() == Some(&'}') {
+                        continue;
+                    }
+                    out.push(c);
+                    if c == '}' {
+                        break;
+                    }
+                }
+            }
+            '%' => match chars.next_if(|x| *x != '\\') {
+                Some('(') => {
+                    out.push('(');
+                    branch_start = true;
+                }
+                Some('[') => {
+                    out.push('[');
+                    in_sequence = true;
+                }
+                next => out.extend(next),
+            },
+            '@' => {
+                while let Some(c) = chars.next_if(|x| x.is_ascii_digit()) {
+                    out.push(c);
+                }
+                out.extend(chars.next_if_eq(&'<'));
+                out.extend(chars.next_if(|x| ['=', '!', '>'].contains(x)));
+            }
+            _ => (),
+        }
+    }
+    out
+}
+
+/// Character class of the given ranges, or of every other character if not `matching`
+fn ranges(ranges: &[(char, char)], matching: bool) -> RegEx {
+    let members = ranges
+        .iter()
+        .map(|(a, b)| {
+            Box::new(match a == b {
+                true => CharacterType::Terminal(*a),
+                false => CharacterType::Between(
+                    Box::new(CharacterType::Terminal(*a)),
+                    Box::new(CharacterType::Terminal(*b)),
+                ),
+            })
+        })
+        .collect();
+    match matching {
+        true => RegEx::Character(CharacterType::Any(members)),
+        false => RegEx::Character(CharacterType::Not(members)),
+    }
+}
+
+impl RegExParser {
+    /// Parse a Vim atom and the multi following it, if any
+    pub(super) fn vim_repetition(&mut self) -> Result<RegEx, Error> {
+        let atom = self.group()?;
+        if !self.more() {
+            return Ok(atom);
+        }
+        let repetition = match self.peek()? {
+            '*' => RepetitionType::OrMore(0),
+            '+' => RepetitionType::OrMore(1),
+            '=' | '?' => RepetitionType::ZeroOrOne,
+            '{' => return self.vim_braces(atom),
+            '@' => return self.vim_lookaround(atom),
+            _ => return Ok(atom),
+        };
+        self.next()?;
+        Ok(RegEx::Repetition(repetition, Box::new(atom)))
+    }
+
+    /// Read a decimal number, if there is one
+    fn vim_number(&mut self) -> Result<Option<u32>, Error> {
+        let mut number = None;
+        while self.more() && self.peek()?.is_ascii_digit() {
+            let digit = self.next()?.to_digit(10).expect("Current char is a digit");
+            number = Some(number.unwrap_or(0) * 10 + digit);
+        }
+        Ok(number)
+    }
+
+    /// Parse `{n,m}`, where either count may be left out and a leading `-` repeats as
+    /// few times as possible
+    fn vim_braces(&mut self, atom: RegEx) -> Result<RegEx, Error> {
+        self.consume('{')?;
+        let lazy = self.more() && self.peek()? == '-';
+        if lazy {
+            self.consume('-')?;
+        }
+        let min = self.vim_number()?;
+        let comma = self.more() && self.peek()? == ',';
+        if comma {
+            self.consume(',')?;
+        }
+        let max = self.vim_number()?;
+        if self.more() && self.peek()? != '}' {
+            return Err(Error::RepetitionValue(self.peek()?));
+        }
+        self.close('}')?;
+
+        let repetition = match (min, comma, max) {
+            (None, false, _) => RepetitionType::OrMore(0),
+            (Some(n), false, _) => RepetitionType::Exactly(n),
+            (n, true, None) => RepetitionType::OrMore(n.unwrap_or(0)),
+            (n, true, Some(m)) => RepetitionType::Between(n.unwrap_or(0), m),
+        };
+        let repeated = RegEx::Repetition(repetition, Box::new(atom));
+        match lazy {
+            true => Ok(RegEx::Quantifier(QuantifierMode::Lazy, Box::new(repeated))),
+            false => Ok(repeated),
+        }
+    }
+
+    /// Parse `@=`, `@!`, `@<=` or `@<!`, which follow the atom they look for
+    fn vim_lookaround(&mut self, atom: RegEx) -> Result<RegEx, Error> {
+        self.consume('@')?;
+        // A limit on how far to look behind, as in `\@1<=`, doesn't change what is drawn
+        self.vim_number()?;
+        let behind = self.more() && self.peek()? == '<';
+        if behind {
+            self.consume('<')?;
+        }
+        if !self.more() {
+            return Err(Error::UnterminatedLiteral(self.text.clone()));
+        }
+        let kind = match (behind, self.next()?) {
+            (false, '=') => LookaroundType::Ahead,
+            (false, '!') => LookaroundType::NotAhead,
+            (true, '=') => LookaroundType::Behind,
+            (true, '!') => LookaroundType::NotBehind,
+            (false, '>') => return Err(Error::UnsupportedSyntax(self.flavor, String::from("atomic groups \\@>"))),
+            (_, other) => return Err(Error::InvalidCharacter(other, self.idx - 1)),
+        };
+        Ok(RegEx::Lookaround(kind, Box::new(atom)))
+    }
+
+    /// Parse an atom which is written differently in Vim, or return `None` if it is
+    /// written as in other flavors
+    pub(super) fn vim_atom(&mut self) -> Result<Option<RegEx>, Error> {
+        let atom = match self.peek()? {
+            // Groups are always capturing, as `(?` has no special meaning
+            '(' => {
+                let start = self.idx;
+                self.consume('(')?;
+                let group = self.open_capture(start, None);
+                let inner = self.alternation()?;
+                self.close(')')?;
+                self.group_spans[group - 1].1 = self.idx;
+                RegEx::Capture(None, group, Box::new(inner))
+            }
+            '<' => {
+                self.consume('<')?;
+                RegEx::Anchor(AnchorType::WordStart)
+            }
+            '>' => {
+                self.consume('>')?;
+                RegEx::Anchor(AnchorType::WordEnd)
+            }
+            '%' => {
+                self.consume('%')?;
+                self.vim_percent()?
+            }
+            '\\' if self.peek_n(1).is_some() => {
+                self.consume('\\')?;
+                let c = self.next()?;
+                self.vim_escape(c)?
+            }
+            '~' => {
+                return Err(Error::UnsupportedSyntax(self.flavor, String::from("the last substitute string ~")));
+            }
+            '&' => return Err(Error::UnsupportedSyntax(self.flavor, String::from("concats \\&"))),
+            // Multis with nothing to repeat match themselves
+            c @ ('=' | '@' | '{' | '*' | '+' | '?') => {
+                self.consume(c)?;
+                RegEx::Terminal(c.to_string())
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(atom))
+    }
+
+    /// Parse the item following `%`
+    fn vim_percent(&mut self) -> Result<RegEx, Error> {
+        if !self.more() {
+            return Err(Error::InvalidCharacter('%', self.idx));
+        }
+        match self.next()? {
+            // Non-capturing group
+            '(' => {
+                let inner = self.alternation()?;
+                self.close(')')?;
+                Ok(inner)
+            }
+            '[' => self.vim_optional_sequence(),
+            '^' => Ok(RegEx::Anchor(AnchorType::Start)),
+            '$' => Ok(RegEx::Anchor(AnchorType::End)),
+            // Character codes in decimal, octal or hexadecimal
+            c @ ('d' | 'o' | 'x' | 'u' | 'U') => {
+                let (radix, max_len) = match c {
+                    'd' => (10, usize::MAX),
+                    'o' => (8, 4),
+                    'x' => (16, 2),
+                    'u' => (16, 4),
+                    _ => (16, 8),
+                };
+                let mut digits = String::new();
+                while digits.len() < max_len && self.more() && self.peek()?.is_digit(radix) {
+                    digits.push(self.next()?);
+                }
+                let value = u32::from_str_radix(&digits, radix)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or(Error::InvalidCharacter(c, self.idx))?;
+                Ok(RegEx::Terminal(value.to_string()))
+            }
+            _ => Err(Error::UnsupportedSyntax(
+                self.flavor,
+                String::from("positions such as \\%V, \\%# and \\%23l"),
+            )),
+        }
+    }
+
+    /// Parse the atoms of `%[...]` up to its closing `]`
+    fn vim_optional_sequence(&mut self) -> Result<RegEx, Error> {
+        let start = self.idx;
+        let mut atoms = Vec::new();
+        while self.more() && self.peek()? != ']' {
+            match self.group()? {
+                // Each character of a literal is a separate atom
+                RegEx::Terminal(text) => {
+                    atoms.extend(text.chars().map(|c| Box::new(RegEx::Terminal(c.to_string()))));
+                }
+                atom => atoms.push(Box::new(atom)),
+            }
+        }
+        self.close(']')?;
+        if atoms.is_empty() {
+            return Err(Error::InvalidCharacter(']', start));
+        }
+        Ok(RegEx::OptionalSequence(atoms))
+    }
+
+    /// Parse the escape `\c`, where Vim uses letters for many character classes and
+    /// items which other flavors do not have
+    fn vim_escape(&mut self, c: char) -> Result<RegEx, Error> {
+        let next = self.peek_n(0);
+        Ok(match c {
+            'z' => {
+                let anchor = match next {
+                    Some('s') => AnchorType::MatchStart,
+                    Some('e') => AnchorType::MatchEnd,
+                    _ => {
+                        return Err(Error::UnsupportedSyntax(
+                            self.flavor,
+                            String::from("\\z items other than \\zs and \\ze"),
+                        ))
+                    }
+                };
+                self.next()?;
+                RegEx::Anchor(anchor)
+            }
+            // The same item, also matching a newline
+            '_' => match next {
+                Some('[') => self.group()?,
+                Some('.') => {
+                    self.next()?;
+                    RegEx::Character(CharacterType::Meta(MetaCharacter::Any))
+                }
+                Some('^') => {
+                    self.next()?;
+                    RegEx::Anchor(AnchorType::Start)
+                }
+                Some('$') => {
+                    self.next()?;
+                    RegEx::Anchor(AnchorType::End)
+                }
+                Some(c) if c.is_ascii_alphabetic() => {
+                    self.next()?;
+                    self.vim_escape(c)?
+                }
+                _ => return Err(Error::InvalidCharacter('_', self.idx)),
+            },
+            'd' => RegEx::Character(CharacterType::Meta(MetaCharacter::Digit(true))),
+            'D' => RegEx::Character(CharacterType::Meta(MetaCharacter::Digit(false))),
+            'w' | 'i' | 'k' => RegEx::Character(CharacterType::Meta(MetaCharacter::Word(true))),
+            'W' => RegEx::Character(CharacterType::Meta(MetaCharacter::Word(false))),
+            's' => RegEx::Character(CharacterType::Meta(MetaCharacter::Whitespace(true))),
+            'S' => RegEx::Character(CharacterType::Meta(MetaCharacter::Whitespace(false))),
+            'a' | 'A' => ranges(&[('a', 'z'), ('A', 'Z')], c == 'a'),
+            'l' | 'L' => ranges(&[('a', 'z')], c == 'l'),
+            'u' | 'U' => ranges(&[('A', 'Z')], c == 'u'),
+            'x' | 'X' => ranges(&[('0', '9'), ('a', 'f'), ('A', 'F')], c == 'x'),
+            'o' | 'O' => ranges(&[('0', '7')], c == 'o'),
+            'h' | 'H' => ranges(&[('a', 'z'), ('A', 'Z'), ('_', '_')], c == 'h'),
+            'I' | 'K' => ranges(&[('a', 'z'), ('A', 'Z'), ('_', '_')], true),
+            'f' | 'F' | 'p' | 'P' => {
+                return Err(Error::UnsupportedSyntax(
+                    self.flavor,
+                    String::from("character classes set by options, such as \\f"),
+                ))
+            }
+            'n' => RegEx::Terminal(String::from("\n")),
+            't' => RegEx::Terminal(String::from("\t")),
+            'r' => RegEx::Terminal(String::from("\r")),
+            'e' => RegEx::Terminal(String::from("\x1b")),
+            'b' => RegEx::Terminal(String::from("\x08")),
+            digit @ '1'..='9' => return self.numbered_backreference(digit.to_string()),
+            other => RegEx::Terminal(other.to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        extract::Language,
+        parser::{
+            vim::very_magic, AnchorType, Flavor, LookaroundType, QuantifierMode, RegEx, RegExParser, RepetitionType,
+        },
+    };
+
+    fn parse(text: &str) -> RegEx {
+        RegExParser::new(Language::Rust, &text.to_string()).with_flavor(Flavor::Vim).parse().unwrap()
+    }
+
+    #[test]
+    fn test_vim_regex() {
+        // Every magic level is rewritten as very magic
+        assert_eq!(very_magic(r"\(foo\|bar\)\+ x*"), r"(foo|bar)+ x*");
+        assert_eq!(very_magic(r"\v(a|b)+=\="), r"(a|b)+=\=");
+        assert_eq!(very_magic(r"\Ma*\.\{1,2\}"), r"a\*.{1,2}");
+        assert_eq!(very_magic(r"\V^a.b$"), r"^a\.b$");
+        assert_eq!(very_magic(r"a^b$c"), r"a\^b\$c");
+        assert_eq!(very_magic(r"func\%[tion]("), r"func%[tion]\(");
+        assert_eq!(parse(r"\va+"), parse(r"a\+"));
+        assert_eq!(parse(r"\Va+"), parse(r"a+"));
+
+        assert_eq!(
+            parse(r"\<foo\zsbar\ze\>"),
+            RegEx::Element(vec![
+                Box::new(RegEx::Anchor(AnchorType::WordStart)),
+                Box::new(RegEx::Terminal(String::from("foo"))),
+                Box::new(RegEx::Anchor(AnchorType::MatchStart)),
+                Box::new(RegEx::Terminal(String::from("bar"))),
+                Box::new(RegEx::Anchor(AnchorType::MatchEnd)),
+                Box::new(RegEx::Anchor(AnchorType::WordEnd)),
+            ])
+        );
+        assert_eq!(
+            parse(r"fu\%[nc]"),
+            RegEx::Element(vec![
+                Box::new(RegEx::Terminal(String::from("fu"))),
+                Box::new(RegEx::OptionalSequence(vec![
+                    Box::new(RegEx::Terminal(String::from("n"))),
+                    Box::new(RegEx::Terminal(String::from("c"))),
+                ])),
+            ])
+        );
+        assert_eq!(
+            parse(r"a\{-2,}"),
+            RegEx::Element(vec![Box::new(RegEx::Quantifier(
+                QuantifierMode::Lazy,
+                Box::new(RegEx::Repetition(RepetitionType::OrMore(2), Box::new(RegEx::Terminal(String::from("a"))))),
+            ))])
+        );
+        assert_eq!(
+            parse(r"\(foo\)\@<!"),
+            RegEx::Element(vec![Box::new(RegEx::Lookaround(
+                LookaroundType::NotBehind,
+                Box::new(RegEx::Capture(
+                    None,
+                    1,
+                    Box::new(RegEx::Element(vec![Box::new(RegEx::Terminal(String::from("foo")))])),
+                )),
+            ))])
+        );
+        assert!(RegExParser::new(Language::Rust, &r"a\%V".to_string()).with_flavor(Flavor::Vim).parse().is_err());
+    }
+}
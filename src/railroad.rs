@@ -1,5 +1,5 @@
 #[allow(dead_code)]
-mod sym;
+pub(crate) mod sym;
 mod draw;
 
 pub mod renderer;
@@ -25,7 +25,7 @@ impl std::fmt::Debug for dyn Draw {
     }
 }
 
-impl<'a, N> Draw for &'a N
+impl<N> Draw for &N
 where
     N: Draw + ?Sized,
 {
@@ -67,7 +67,7 @@ where
     }
 }
 
-impl<'a, N> Draw for &'a mut N
+impl<N> Draw for &mut N
 where
     N: Draw + ?Sized,
 {
@@ -92,9 +92,6 @@ pub trait DrawGroup {
     /// The maximum `entry_height()`-value.
     fn max_entry_height(self) -> usize;
 
-    /// The maximum `height()`-value.
-    fn max_height(self) -> usize;
-
     /// The maximum `width()`-value.
     fn max_width(self) -> usize;
 
@@ -114,13 +111,6 @@ where
             .unwrap_or_default()
     }
 
-    fn max_height(self) -> usize {
-        self.into_iter()
-            .map(|n| n.height())
-            .max()
-            .unwrap_or_default()
-    }
-
     fn max_width(self) -> usize {
         self.into_iter()
             .map(|n| n.width())
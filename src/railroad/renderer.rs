@@ -3,8 +3,9 @@ use tracing::info;
 
 use crate::parser::{CharacterType, MetaCharacter};
 use crate::{
+    diff::{DiffKind, DiffTree, Status},
     error::Error,
-    parser::{AnchorType, RegEx, RepetitionType},
+    parser::{AnchorType, LookaroundType, QuantifierMode, RegEx, RepetitionType},
     railroad::sym,
     railroad::draw::{Draw, DrawGroup}
 };
@@ -14,7 +15,7 @@ const H_PADDING: usize = 2;
 
 // Repeat character n times
 fn repeat(character: char, n: usize) -> String {
-    iter::repeat(character).take(n).collect::<String>()
+    iter::repeat_n(character, n).collect::<String>()
 }
 
 
@@ -62,7 +63,14 @@ where
     }
 
     fn height(&self) -> usize {
-        self.children.iter().max_height()
+        // Children are aligned on their entries, and an empty sequence is still drawn as
+        // one line
+        let entry_height = self.entry_height();
+        self.children
+            .iter()
+            .map(|x| entry_height - x.entry_height() + x.height())
+            .max()
+            .unwrap_or(1)
     }
 
     fn width(&self) -> usize {
@@ -498,21 +506,22 @@ where
     }
 
     fn width(&self) -> usize {
-        self.inner.iter().max_width()
+        self.inner.iter().max_width() + 2
     }
 
     fn draw(&self) -> Vec<String> {
         let mut diagram: Vec<String> = Vec::new();
         let choices = self.inner.len();
         let odd = choices % 2 == 1;
+        // Size from the drawn choices, which may themselves contain choices
+        let sub_diagrams: Vec<Vec<String>> = self.inner.iter().map(|x| x.draw()).collect();
         // Zero-indexed midpoint
-        let midpoint = (self.inner.iter().total_height() + 1) / 2 - 1;
-        let width = self.inner.iter().max_width();
+        let midpoint = sub_diagrams.iter().map(|x| x.len()).sum::<usize>().div_ceil(2) - 1;
+        let width = sub_diagrams.iter().map(|x| x[0].chars().count()).max().unwrap_or_default();
         info!("{} {} {}", choices, midpoint, odd);
 
         // Stack all choices vertically
-        for (i, node) in self.inner.iter().enumerate() { 
-            let sub_diagram = node.draw();
+        for (i, (node, sub_diagram)) in self.inner.iter().zip(sub_diagrams.iter()).enumerate() {
             let sub_len = sub_diagram[0].chars().count();
 
             // Ensure all nodes have the same width
@@ -545,9 +554,11 @@ where
                     ));
                 }
                 else if diagram.len() == midpoint {
-                    diagram.push(format!("{}{}{}",
+                    diagram.push(format!("{}{}{}{}{}",
                         sym::J_LEFT,
+                        repeat(' ', left_pad),
                         line,
+                        repeat(' ', right_pad),
                         sym::J_RIGHT
                     ));
                 }
@@ -691,26 +702,39 @@ where
     }
 
     fn width(&self) -> usize {
-        self.inner.width() + 4
+        usize::max(self.inner.width() + 4, self.name.chars().count() + 2)
     }
 
     fn draw(&self) -> Vec<String> {
         let mut diagram = self.inner.draw();
+        // Widen the box when the name is wider than the inner node
+        let len_name = self.name.chars().count();
+        let extra = (len_name + 2).saturating_sub(diagram[0].chars().count() + 4);
+        let (inner_left, inner_right) = (extra / 2 + 1, extra - extra / 2 + 1);
         // Iterate through inner node
         for (i, d) in diagram.iter_mut().enumerate() {
             match self.entry_height() {
                 height if height == i + 1 => {
                     *d = format!("{}{}{}{}{}",
-                        sym::CROSS, sym::L_HORZ, *d, sym::L_HORZ, sym::CROSS
+                        sym::CROSS,
+                        repeat(sym::L_HORZ, inner_left),
+                        *d,
+                        repeat(sym::L_HORZ, inner_right),
+                        sym::CROSS
                     );
                 },
                 _ => {
-                    *d = format!("{} {} {}", sym::L_VERT_D, *d, sym::L_VERT_D);
+                    *d = format!("{}{}{}{}{}",
+                        sym::L_VERT_D,
+                        repeat(' ', inner_left),
+                        *d,
+                        repeat(' ', inner_right),
+                        sym::L_VERT_D
+                    );
                 }
             }
         }
         let len_full = diagram[0].chars().count() - 2;
-        let len_name = self.name.chars().count();
         let left_pad = (len_full - len_name) / 2;
         let right_pad = len_full - len_name - left_pad;
         diagram.insert(0, format!("{}{}{}{}{}",
@@ -732,6 +756,83 @@ where
 }
 
 
+/// A node `Marked` with how it changed between two regular expressions
+///
+///  ┏━━━━━ + ━━━━━┓    ╔═════ - ═════╗    ╭───── ~ ─────╮
+///  ┃ ┌─────────┐ ┃    ║ ┌─────────┐ ║    │ ┌─────────┐ │
+///  ╂─┤  Added  ├─╂    ╫─┤ Removed ├─╫    ┼─┤ Changed ├─┼
+///  ┃ └─────────┘ ┃    ║ └─────────┘ ║    │ └─────────┘ │
+///  ┗━━━━━━━━━━━━━┛    ╚═════════════╝    ╰─────────────╯
+///
+#[derive(Debug)]
+pub struct Marked<N> {
+    inner: N,
+    status: Status
+}
+
+impl<N> Marked<N> {
+    pub fn new(inner: N, status: Status) -> Self {
+        Self { inner, status }
+    }
+}
+
+impl<N> Draw for Marked<N>
+where
+    N: Draw,
+{
+    fn entry_height(&self) -> usize {
+        self.inner.entry_height() + 1
+    }
+
+    fn height(&self) -> usize {
+        self.inner.height() + 2
+    }
+
+    fn width(&self) -> usize {
+        self.inner.width() + 4
+    }
+
+    fn draw(&self) -> Vec<String> {
+        let (tl, tr, bl, br, horz, vert, cross, label) = match self.status {
+            Status::Inserted => (
+                sym::C_TL_SQR_B, sym::C_TR_SQR_B, sym::C_BL_SQR_B, sym::C_BR_SQR_B,
+                sym::L_HORZ_B, sym::L_VERT_B, sym::CROSS_B, " + "
+            ),
+            Status::Deleted => (
+                sym::C_TL_DB, sym::C_TR_DB, sym::C_BL_DB, sym::C_BR_DB,
+                sym::L_HORZ_DB, sym::L_VERT_DB, sym::CROSS_DB, " - "
+            ),
+            _ => (
+                sym::C_TL_RND, sym::C_TR_RND, sym::C_BL_RND, sym::C_BR_RND,
+                sym::L_HORZ, sym::L_VERT, sym::CROSS, " ~ "
+            )
+        };
+        let mut diagram = self.inner.draw();
+        for (i, d) in diagram.iter_mut().enumerate() {
+            if i + 1 == self.entry_height() {
+                *d = format!("{}{}{}{}{}", cross, sym::L_HORZ, *d, sym::L_HORZ, cross);
+            } else {
+                *d = format!("{} {} {}", vert, *d, vert);
+            }
+        }
+        let len_full = diagram[0].chars().count() - 2;
+        let len_label = label.chars().count();
+        let left_pad = len_full.saturating_sub(len_label) / 2;
+        let right_pad = len_full.saturating_sub(len_label + left_pad);
+        diagram.insert(0, format!("{}{}{}{}{}",
+            tl,
+            repeat(horz, left_pad),
+            label,
+            repeat(horz, right_pad),
+            tr
+        ));
+        diagram.push(format!("{}{}{}", bl, repeat(horz, len_full), br));
+
+        diagram
+    }
+}
+
+
 #[derive(Default)]
 pub struct RailroadRenderer {
     _diagram: Vec<String>,
@@ -797,8 +898,32 @@ impl RailroadRenderer {
                     AnchorType::End => {
                         Ok(Box::new(Anchor { text: String::from("LINE END")}))
                     },
-                    _ => {
-                        Ok(Box::new(Anchor { text: String::from("")}))
+                    AnchorType::WordBoundary => {
+                        Ok(Box::new(Anchor { text: String::from("WORD BOUNDARY")}))
+                    },
+                    AnchorType::NotWordBoundary => {
+                        Ok(Box::new(Anchor { text: String::from("NOT WORD BOUNDARY")}))
+                    },
+                    AnchorType::WordStart => {
+                        Ok(Box::new(Anchor { text: String::from("WORD START")}))
+                    },
+                    AnchorType::WordEnd => {
+                        Ok(Box::new(Anchor { text: String::from("WORD END")}))
+                    },
+                    AnchorType::MatchStart => {
+                        Ok(Box::new(Anchor { text: String::from("MATCH STARTS HERE")}))
+                    },
+                    AnchorType::MatchEnd => {
+                        Ok(Box::new(Anchor { text: String::from("MATCH ENDS HERE")}))
+                    },
+                    AnchorType::PathStart => {
+                        Ok(Box::new(Anchor { text: String::from("PATH START")}))
+                    },
+                    AnchorType::DirectoryOnly => {
+                        Ok(Box::new(Anchor { text: String::from("DIRECTORY ONLY")}))
+                    },
+                    AnchorType::Negated => {
+                        Ok(Box::new(Anchor { text: String::from("NEGATED")}))
                     }
                 }
             },
@@ -832,7 +957,120 @@ impl RailroadRenderer {
                         }
                     }
                 )
-            )
+            ),
+            RegEx::Lookaround(kind, a) => Ok(
+                Box::new(
+                    Capture {
+                        inner: Self::generate_diagram_element(a)?,
+                        name: String::from(match kind {
+                            LookaroundType::Ahead => "FOLLOWED BY",
+                            LookaroundType::NotAhead => "NOT FOLLOWED BY",
+                            LookaroundType::Behind => "PRECEDED BY",
+                            LookaroundType::NotBehind => "NOT PRECEDED BY"
+                        })
+                    }
+                )
+            ),
+            RegEx::Quantifier(mode, a) => Ok(
+                Box::new(
+                    Capture {
+                        inner: Self::generate_diagram_element(a)?,
+                        name: String::from(match mode {
+                            QuantifierMode::Greedy => "GREEDY",
+                            QuantifierMode::Lazy => "LAZY",
+                            QuantifierMode::Possessive => "POSSESSIVE"
+                        })
+                    }
+                )
+            ),
+            RegEx::Backreference(name, group) => Ok(Box::new(Anchor {
+                text: match name {
+                    Some(n) => format!("SAME AS {}", n),
+                    None => format!("SAME AS GROUP {}", group)
+                }
+            })),
+            // Drawn as nested optionals, so each atom is only reached after those before it
+            RegEx::OptionalSequence(a) => {
+                let mut inner: Option<Box<dyn Draw>> = None;
+                for i in a.iter().rev() {
+                    let mut seq = vec![Self::generate_diagram_element(i)?];
+                    seq.extend(inner);
+                    inner = Some(Box::new(Optional::<Box<dyn Draw>> {
+                        inner: Box::new(Sequence::<Box<dyn Draw>>::new(seq)),
+                    }));
+                }
+                Ok(Box::new(Capture {
+                    inner: inner.ok_or(Error::InvalidParsing)?,
+                    name: String::from("AS MUCH AS MATCHES")
+                }))
+            }
+            RegEx::Balanced(open, close) => Ok(Box::new(Anchor {
+                text: format!("BALANCED {} TO {}", open, close)
+            })),
+            RegEx::Frontier(a) => Ok(
+                Box::new(
+                    Capture {
+                        inner: Self::generate_diagram_element(a)?,
+                        name: String::from("FRONTIER OF")
+                    }
+                )
+            ),
+        }
+    }
+
+    /// Generate a diagram of a structural diff, marking changed nodes
+    pub fn generate_diff_diagram(tree: &DiffTree) -> Result<Sequence<Box<dyn Draw>>, Error> {
+        let mut diagram = Sequence::new(vec![Box::new(Start {}) as Box<dyn Draw>]);
+        match (&tree.kind, tree.status) {
+            (DiffKind::Element(a), Status::Unchanged) => {
+                for i in a.iter() {
+                    diagram.push(Self::generate_diff_element(i)?);
+                }
+            },
+            _ => diagram.push(Self::generate_diff_element(tree)?)
+        }
+        diagram.push(Box::new(End {}));
+        Ok(diagram)
+    }
+
+    pub fn generate_diff_element(tree: &DiffTree) -> Result<Box<dyn Draw>, Error> {
+        let node: Box<dyn Draw> = match &tree.kind {
+            DiffKind::Node(a) => Self::generate_diagram_element(a)?,
+            DiffKind::Element(a) => {
+                let mut seq = Vec::new();
+                for i in a.iter() {
+                    seq.push(Self::generate_diff_element(i)?);
+                }
+                Box::new(Sequence::<Box<dyn Draw>>::new(seq))
+            },
+            DiffKind::Alternation(a) => {
+                let mut choices = Vec::new();
+                for i in a.iter() {
+                    choices.push(Self::generate_diff_element(i)?);
+                }
+                Box::new(Choice::<Box<dyn Draw>>::new(choices))
+            },
+            DiffKind::Repetition(repetition, a) => match repetition {
+                RepetitionType::ZeroOrOne => Box::new(Optional::<Box<dyn Draw>>::new(
+                    Self::generate_diff_element(a)?
+                )),
+                _ => Box::new(Repetition::<Box<dyn Draw>>::new(
+                    Self::generate_diff_element(a)?,
+                    *repetition
+                )),
+            },
+            DiffKind::Capture(name, group, a) => Box::new(Capture::new(
+                Self::generate_diff_element(a)?,
+                if let Some(n) = name {
+                    n.clone()
+                } else {
+                    format!("Group {}", group)
+                }
+            )),
+        };
+        match tree.status {
+            Status::Unchanged => Ok(node),
+            status => Ok(Box::new(Marked::new(node, status)))
         }
     }
 
@@ -849,7 +1087,8 @@ impl RailroadRenderer {
                     MetaCharacter::Word(m) => Ok(format!("{}Word", if *m { "" } else { "Non-" })),
                     MetaCharacter::Digit(m) => Ok(format!("{}Digit", if *m { "" } else { "Non-" })),
                     MetaCharacter::Whitespace(m) => Ok(format!("{}Whitespace", if *m { "" } else { "Non-" })),
-                    MetaCharacter::Any => Ok(String::from("Any"))
+                    MetaCharacter::Any => Ok(String::from("Any")),
+                    MetaCharacter::Property(name, m) => Ok(format!("{}{}", if *m { "" } else { "Non-" }, name))
                 }
             }
             _ => Err(Error::InvalidParsing),
@@ -861,3 +1100,61 @@ impl RailroadRenderer {
     }
 }
 
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        extract::Language,
+        parser::{Flavor, RegExParser},
+        railroad::renderer::RailroadRenderer,
+    };
+
+    fn render(flavor: Flavor, regex: &str) -> Vec<String> {
+        let tree = RegExParser::new(Language::Rust, &regex.to_string()).with_flavor(flavor).parse().unwrap();
+        let diagram = RailroadRenderer::generate_diagram(&tree).unwrap();
+        RailroadRenderer::render_diagram(&diagram).unwrap()
+    }
+
+    fn assert_rectangular(text: &[String]) {
+        let width = text[0].chars().count();
+        assert!(text.iter().all(|x| x.chars().count() == width), "{:#?}", text);
+    }
+
+    #[test]
+    fn test_render_labels() {
+        // Boxes widen to fit labels longer than the node they contain
+        for regex in ["(?!a)b", "(?<!a)b", "(?=ab)c", "(?=)"] {
+            assert_rectangular(&render(Flavor::Pcre, regex));
+        }
+        let text = render(Flavor::Pcre, "(?<!a)b");
+        assert!(text[0].contains("NOT PRECEDED BY"));
+
+        assert_rectangular(&render(Flavor::Vim, r"a\@<="));
+
+        // Empty groups
+        for regex in ["()", "a()b"] {
+            assert_rectangular(&render(Flavor::Pcre, regex));
+        }
+        assert_rectangular(&render(Flavor::Ere, "()"));
+        assert_rectangular(&render(Flavor::Lua, "()a"));
+
+        // Vim optional sequences
+        assert_rectangular(&render(Flavor::Vim, r"\%[a]"));
+        assert_rectangular(&render(Flavor::Vim, r"func\%[tion]"));
+    }
+
+    #[test]
+    fn test_render_nested_choices() {
+        // Choices are sized by their drawn alternatives, which may be choices themselves
+        for regex in ["{a,{b,c}}", "x{a,{b,c}}", "{a,b{c,d}}", "*.{js,{ts,tsx}}"] {
+            let text = render(Flavor::Glob, regex);
+            assert_rectangular(&text);
+        }
+        assert_rectangular(&render(Flavor::Pcre, "a|(?:b|c)d"));
+
+        // Empty alternatives are drawn as a single line
+        for regex in ["|", "a||b", "(|)", "x(?:|)y"] {
+            assert_rectangular(&render(Flavor::Pcre, regex));
+        }
+    }
+}
@@ -4,6 +4,8 @@ pub const END: char = '╢';
 
 // Junctions
 pub const CROSS: char = '┼';
+pub const CROSS_B: char = '╂';
+pub const CROSS_DB: char = '╫';
 pub const J_LEFT: char = '┤';
 pub const J_RIGHT: char = '├';
 pub const J_UP: char = '┴';
@@ -28,6 +30,12 @@ pub const C_TL_SQR_B: char = '┏';
 pub const C_TR_SQR_B: char = '┓';
 pub const C_BL_SQR_B: char = '┗';
 pub const C_BR_SQR_B: char = '┛';
+pub const L_HORZ_DB: char = '═';
+pub const L_VERT_DB: char = '║';
+pub const C_TL_DB: char = '╔';
+pub const C_TR_DB: char = '╗';
+pub const C_BL_DB: char = '╚';
+pub const C_BR_DB: char = '╝';
 pub const C_TL_RND: char = '╭';
 pub const C_TR_RND: char = '╮';
 pub const C_BL_RND: char = '╰';
@@ -0,0 +1,279 @@
+use std::ops::Range;
+
+use crate::extract::{
+    call::{is_literal, literal_len, parse_call},
+    delimited, glob, sql, Language,
+};
+
+/// Words after which a `/` starts a regex literal rather than a division
+const REGEX_KEYWORDS: &[&str] = &[
+    "return", "typeof", "instanceof", "in", "of", "case", "do", "else", "new", "delete", "void", "throw", "yield",
+    "await", "and", "or", "not", "if", "unless", "when", "while", "until", "split", "grep",
+];
+
+/// A regular expression found in a buffer, with zero-indexed lines and byte columns
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    /// Source text of the call or literal
+    pub text: String,
+}
+
+/// Line and block comment delimiters of a language
+fn comments(language: &Language) -> (&'static [&'static str], Option<(&'static str, &'static str)>) {
+    match language {
+        Language::Python | Language::Ruby | Language::Perl => (&["#"], None),
+        Language::Php => (&["//", "#"], Some(("/*", "*/"))),
+        Language::Rust
+        | Language::Javascript
+        | Language::Typescript
+        | Language::Go
+        | Language::Java
+        | Language::Kotlin
+        | Language::Cpp
+        | Language::Csharp
+        | Language::Swift => (&["//"], Some(("/*", "*/"))),
+        Language::Lua => (&["--"], Some(("--[[", "]]"))),
+        Language::Shell | Language::Awk | Language::Sed | Language::Elixir => (&["#"], None),
+        Language::Yaml | Language::Toml => (&["#"], None),
+        Language::Sql => (&["--"], Some(("/*", "*/"))),
+        Language::Erlang => (&["%"], None),
+        Language::Haskell => (&["--"], Some(("{-", "-}"))),
+        _ => (&[], None),
+    }
+}
+
+fn is_identifier(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Check if a `/` following the text starts a regex literal rather than a division
+fn regex_allowed(before: &str) -> bool {
+    let before = before.trim_end();
+    match before.chars().last() {
+        None => true,
+        Some(c) if is_identifier(c) => {
+            let word = before.rsplit(|c| !is_identifier(c)).next().unwrap_or_default();
+            REGEX_KEYWORDS.contains(&word)
+        }
+        Some(c) => !")]}\"'`$".contains(c),
+    }
+}
+
+/// Byte length of the regex literal at the start of the text, such as JavaScript's
+/// `/a+/g` or Perl's `s{a}{b}`, if there is one
+fn regex_literal_len(language: &Language, before: &str, text: &str) -> Option<usize> {
+    let (operator, count) = match language {
+        Language::Swift if text.starts_with("#/") || text.starts_with("##/") => return literal_len(text, true),
+        Language::Javascript | Language::Typescript | Language::Swift if text.starts_with('/') && regex_allowed(before) => {
+            return slash_literal_len(text)
+        }
+        Language::Sql => return sql::regex_len(text),
+        Language::Haskell => return delimited::quasi_quote_len(text),
+        Language::Elixir if text.starts_with("~r") || text.starts_with("~R") => ("~r", 1),
+        Language::Ruby | Language::Perl if text.starts_with('/') && regex_allowed(before) => ("", 1),
+        Language::Ruby if text.starts_with("%r") => ("%r", 1),
+        Language::Perl if !before.ends_with(['$', '@', '%', '&', '-']) => {
+            let operator = ["qr", "m", "s"].into_iter().find(|x| text.starts_with(x))?;
+            (operator, if operator == "s" { 2 } else { 1 })
+        }
+        _ => return None,
+    };
+    let rest = &text[operator.len()..];
+    let delimiter = rest.chars().next()?;
+    if is_identifier(delimiter) || delimiter.is_whitespace() || ",;=)]}>".contains(delimiter) {
+        return None;
+    }
+    delimited::delimited_len(rest, count).map(|len| operator.len() + len)
+}
+
+/// Byte length of a `/.../flags` literal, where a `/` within a character class does not
+/// close the literal
+fn slash_literal_len(text: &str) -> Option<usize> {
+    let mut in_class = false;
+    let mut chars = text.char_indices().skip(1);
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '\n' => return None,
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '/' if !in_class => {
+                let flags = &text[idx + 1..];
+                return Some(idx + 1 + flags.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(flags.len()));
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+/// Byte length of a call to a known regular expression function with a literal pattern
+fn call_len(language: &Language, text: &str) -> Option<usize> {
+    let call = parse_call(text)?;
+    let regex_call = call.regex_call(language)?;
+    call.argument(regex_call.pattern, "pattern").filter(|x| is_literal(x))?;
+    Some(text.trim_end().len() - call.rest.len())
+}
+
+/// Byte length of the string literal at the start of the text, if there is one
+///
+/// Single quotes must close on the same line, so that Rust lifetimes and apostrophes
+/// are not taken as the start of a string.
+fn string_len(text: &str) -> Option<usize> {
+    let len = literal_len(text, false)?;
+    let literal = &text[..len];
+    let quoted = literal.trim_start_matches(|c: char| c.is_ascii_alphanumeric() || c == '@' || c == '$');
+    let single_quoted = quoted.starts_with('\'') && !quoted.starts_with("'''");
+    (!(single_quoted && literal.contains('\n'))).then_some(len)
+}
+
+/// Spans of the pattern on each line of a file with one pattern per line, such as a
+/// `.gitignore` file, skipping blank lines and comments
+fn pattern_lines(language: &Language, text: &str) -> Vec<Range<usize>> {
+    let parse = match language {
+        Language::Codeowners => glob::codeowners_pattern,
+        Language::Editorconfig => glob::editorconfig_pattern,
+        _ => glob::gitignore_pattern,
+    };
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if let Some(Ok(regex)) = parse(line) {
+            let start = offset + line.find(regex.pattern.as_str()).unwrap_or_default();
+            spans.push(start..start + regex.pattern.len());
+        }
+        offset += line.len();
+    }
+    spans
+}
+
+/// Spans of every regex literal, and every call to a known regular expression function
+/// with a literal pattern, skipping strings and comments
+fn code_spans(language: &Language, text: &str) -> Vec<Range<usize>> {
+    let (line_comments, block_comment) = comments(language);
+    let mut spans = Vec::new();
+    let mut idx = 0;
+    while idx < text.len() {
+        let before = &text[..idx];
+        let rest = &text[idx..];
+        // Block comments are checked first, as Lua's start with its line comment
+        if let Some((open, close)) = block_comment.filter(|(open, _)| rest.starts_with(open)) {
+            idx += rest[open.len()..].find(close).map_or(rest.len(), |x| open.len() + x + close.len());
+            continue;
+        }
+        if line_comments.iter().any(|x| rest.starts_with(x)) {
+            idx += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        }
+
+        let word_start = !before.ends_with(|c: char| is_identifier(c) || ['.', ':', '$'].contains(&c))
+            && !rest.starts_with(char::is_whitespace);
+        let found = match word_start {
+            true => regex_literal_len(language, before, rest).or_else(|| call_len(language, rest)),
+            false => None,
+        };
+        if let Some(len) = found {
+            spans.push(idx..idx + len);
+            idx += len;
+        } else if let Some(len) = string_len(rest) {
+            idx += len;
+        } else if word_start && rest.starts_with(is_identifier) {
+            idx += rest.find(|c: char| !is_identifier(c)).unwrap_or(rest.len());
+        } else {
+            idx += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    spans
+}
+
+/// Find every regex literal, and every call to a known regular expression function with
+/// a literal pattern, in the text of a buffer
+///
+/// Strings and comments are skipped, so patterns mentioned within them are not found.
+/// Every pattern of files with one pattern per line, such as `.gitignore` files, is
+/// found.
+pub fn find_regexes(language: &Language, text: &str) -> Vec<Location> {
+    let spans = match language {
+        Language::Gitignore | Language::Codeowners | Language::Editorconfig => pattern_lines(language, text),
+        _ => code_spans(language, text),
+    };
+    let line_starts: Vec<usize> =
+        std::iter::once(0).chain(text.match_indices('\n').map(|(idx, _)| idx + 1)).collect();
+    let position = |offset: usize| {
+        let line = line_starts.partition_point(|x| *x <= offset) - 1;
+        (line, offset - line_starts[line])
+    };
+    spans
+        .into_iter()
+        .map(|span| {
+            let (line, column) = position(span.start);
+            let (end_line, end_column) = position(span.end);
+            Location {
+                line,
+                column,
+                end_line,
+                end_column,
+                text: text[span].to_string(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{extract::Language, scan::find_regexes};
+
+    #[test]
+    fn test_find_regexes() {
+        let text = "import re\n# re.compile('comment')\nx = re.compile(r'\\d+', re.I)\ny = re.sub('a', 'b', s)\nprint('re.compile(\"no\")')\nz = re.compile(pattern)\n";
+        let found = find_regexes(&Language::Python, text);
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].line, found[0].column), (2, 4));
+        assert_eq!((found[0].end_line, found[0].end_column), (2, 28));
+        assert_eq!(found[0].text, "re.compile(r'\\d+', re.I)");
+        assert_eq!(found[1].text, "re.sub('a', 'b', s)");
+
+        let text = "const a = x / 2 / y;\nconst b = /[/]+/g.test(s) // /c/\nconst c = new RegExp('d+', 'i');";
+        let found: Vec<String> = find_regexes(&Language::Javascript, text).into_iter().map(|x| x.text).collect();
+        assert_eq!(found, vec!["/[/]+/g", "new RegExp('d+', 'i')"]);
+
+        let text = "fn f<'a>(s: &'a str) { let r = Regex::new(r\"\\w+\").unwrap(); }";
+        let found: Vec<String> = find_regexes(&Language::Rust, text).into_iter().map(|x| x.text).collect();
+        assert_eq!(found, vec!["Regex::new(r\"\\w+\").unwrap()"]);
+
+        let text = "my %h = (s => 1);\nif ($x =~ m{a+}i) { $y =~ s/b/c/g; }";
+        let found: Vec<String> = find_regexes(&Language::Perl, text).into_iter().map(|x| x.text).collect();
+        assert_eq!(found, vec!["m{a+}i", "s/b/c/g"]);
+
+        let text = "# Build output\n/target/\n\n*.log  \n";
+        let found = find_regexes(&Language::Gitignore, text);
+        assert_eq!(found.iter().map(|x| x.text.as_str()).collect::<Vec<_>>(), vec!["/target/", "*.log"]);
+        assert_eq!((found[1].line, found[1].column, found[1].end_column), (3, 0, 5));
+
+        let text = "SELECT 'LIKE x' FROM t -- WHERE a LIKE 'b'\nWHERE a LIKE 'c!%' ESCAPE '!' AND regexp_like(b, '^d');";
+        let found: Vec<String> = find_regexes(&Language::Sql, text).into_iter().map(|x| x.text).collect();
+        assert_eq!(found, vec!["LIKE 'c!%' ESCAPE '!'", "regexp_like(b, '^d')"]);
+
+        let text = "x = \"~r/no/\" # ~r/no/\ny = String.split(s, ~r{a+}i)\n";
+        let found: Vec<String> = find_regexes(&Language::Elixir, text).into_iter().map(|x| x.text).collect();
+        assert_eq!(found, vec!["~r{a+}i"]);
+
+        let text = "%% re:run(S, \"no\")\nf(S) -> re:run(S, \"a+\", [caseless]).\n";
+        let found: Vec<String> = find_regexes(&Language::Erlang, text).into_iter().map(|x| x.text).collect();
+        assert_eq!(found, vec!["re:run(S, \"a+\", [caseless])"]);
+
+        let text = "{- [re|no|] -}\nf s = s =~ [re|a+|] -- [re|no|]\n";
+        let found: Vec<String> = find_regexes(&Language::Haskell, text).into_iter().map(|x| x.text).collect();
+        assert_eq!(found, vec!["[re|a+|]"]);
+
+        let text = "root = true\n\n[*.{js,py}]\nindent_size = 4\n";
+        let found: Vec<String> = find_regexes(&Language::Editorconfig, text).into_iter().map(|x| x.text).collect();
+        assert_eq!(found, vec!["*.{js,py}"]);
+    }
+}
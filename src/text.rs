@@ -3,7 +3,7 @@ use tracing::{error, info};
 
 use crate::{
     error::Error,
-    parser::{AnchorType, CharacterType, RegEx, RepetitionType},
+    parser::{AnchorType, CharacterType, LookaroundType, QuantifierMode, RegEx, RepetitionType},
 };
 
 type HighlightRegion = (usize, usize, usize);
@@ -79,7 +79,15 @@ impl TextRenderer {
                 match a {
                     AnchorType::Start => Ok(String::from("Start")),
                     AnchorType::End => Ok(String::from("End")),
-                    _ => panic!()
+                    AnchorType::WordBoundary => Ok(String::from("Word boundary")),
+                    AnchorType::NotWordBoundary => Ok(String::from("Not word boundary")),
+                    AnchorType::WordStart => Ok(String::from("Word start")),
+                    AnchorType::WordEnd => Ok(String::from("Word end")),
+                    AnchorType::MatchStart => Ok(String::from("Match starts here")),
+                    AnchorType::MatchEnd => Ok(String::from("Match ends here")),
+                    AnchorType::PathStart => Ok(String::from("Start of path, relative to the ignore file")),
+                    AnchorType::DirectoryOnly => Ok(String::from("Directories only")),
+                    AnchorType::Negated => Ok(String::from("Negated, excluding matching paths"))
                 }
             },
             RegEx::Element(a) => {
@@ -168,6 +176,56 @@ impl TextRenderer {
                     Self::render_text_element(a, text, highlight)?
                 ))
             }
+            RegEx::Lookaround(kind, a) => {
+                let msg = match kind {
+                    LookaroundType::Ahead => "FOLLOWED BY:",
+                    LookaroundType::NotAhead => "NOT FOLLOWED BY:",
+                    LookaroundType::Behind => "PRECEDED BY:",
+                    LookaroundType::NotBehind => "NOT PRECEDED BY:",
+                };
+                highlight.push((text.len(), 0, msg.len()));
+                Ok(format!(
+                    "{}\n    {}",
+                    msg,
+                    Self::render_text_element(a, text, highlight)?
+                ))
+            }
+            RegEx::Quantifier(mode, a) => {
+                let msg = match mode {
+                    QuantifierMode::Greedy => "AS MANY AS POSSIBLE:",
+                    QuantifierMode::Lazy => "AS FEW AS POSSIBLE:",
+                    QuantifierMode::Possessive => "AS MANY AS POSSIBLE, WITHOUT BACKTRACKING:",
+                };
+                highlight.push((text.len(), 0, msg.len()));
+                Ok(format!(
+                    "{}\n{}",
+                    msg,
+                    Self::render_text_element(a, text, highlight)?
+                ))
+            }
+            RegEx::Backreference(name, group) => match name {
+                Some(n) => Ok(format!("Same text as group {}", n)),
+                None => Ok(format!("Same text as group {}", group)),
+            },
+            RegEx::OptionalSequence(a) => {
+                let msg = "AS MUCH OF IN ORDER:";
+                highlight.push((text.len(), 0, msg.len()));
+                let mut atoms = Vec::new();
+                for i in a.iter() {
+                    atoms.push(Self::render_text_element(i, text, highlight)?);
+                }
+                Ok(format!("{}\n    {}", msg, atoms.join(" ")))
+            }
+            RegEx::Balanced(open, close) => Ok(format!("Balanced text from '{}' to '{}'", open, close)),
+            RegEx::Frontier(a) => {
+                let msg = "FRONTIER OF:";
+                highlight.push((text.len(), 0, msg.len()));
+                Ok(format!(
+                    "{}\n    {}",
+                    msg,
+                    Self::render_text_element(a, text, highlight)?
+                ))
+            }
         }
     }
 